@@ -0,0 +1,151 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use color_eyre::{Result, eyre::WrapErr};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+/// Stores lists of items like projects on disk, so they can be reused by later runs of the
+/// binary until they expire.
+///
+/// Entries are kept per API token, so switching accounts never shows stale data of another one.
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+/// The content of a single cache file, generic over the list so it can be stored borrowed.
+#[derive(Serialize, Deserialize)]
+struct Entry<L> {
+    fetched_at: DateTime<Utc>,
+    items: L,
+}
+
+impl DiskCache {
+    /// The time after which cached items are fetched again, unless configured otherwise.
+    pub const DEFAULT_TTL: Duration = Duration::from_secs(60 * 60);
+
+    /// Creates a new [`DiskCache`].
+    ///
+    /// * `dir` - the directory the caches of all tokens are stored in.
+    /// * `token` - the API token the cached items belong to.
+    /// * `ttl` - how long cached items are used before they are fetched again.
+    pub fn new(dir: &Path, token: &str, ttl: Duration) -> DiskCache {
+        DiskCache {
+            dir: dir.join(format!("{:016x}", fnv1a(token.as_bytes()))),
+            ttl,
+        }
+    }
+
+    fn file(&self, kind: &str) -> PathBuf {
+        self.dir.join(format!("{kind}.json"))
+    }
+
+    /// Returns the cached items of the given kind, if they exist and haven't expired at `now`.
+    /// A cache that can't be read is treated as missing.
+    pub fn load<T: DeserializeOwned>(&self, kind: &str, now: DateTime<Utc>) -> Option<Vec<T>> {
+        let data = fs::read(self.file(kind)).ok()?;
+        let entry: Entry<Vec<T>> = serde_json::from_slice(&data).ok()?;
+        let age = now.signed_duration_since(entry.fetched_at).to_std().ok()?;
+        (age < self.ttl).then_some(entry.items)
+    }
+
+    /// Stores the items of the given kind as fetched at `now`.
+    pub fn store<T: Serialize>(&self, kind: &str, items: &[T], now: DateTime<Utc>) -> Result<()> {
+        fs::create_dir_all(&self.dir).wrap_err("unable to create cache directory")?;
+        let data = serde_json::to_vec(&Entry {
+            fetched_at: now,
+            items,
+        })?;
+        fs::write(self.file(kind), data).wrap_err("unable to write cache")
+    }
+
+    /// Removes the cached items of the given kind, if there are any.
+    pub fn remove(&self, kind: &str) {
+        // A missing file is just as good as a removed one.
+        let _ = fs::remove_file(self.file(kind));
+    }
+}
+
+/// Hashes the token with FNV-1a, so it doesn't end up in a path in plain text. Unlike the hasher
+/// of the standard library, the result is guaranteed to stay the same between releases.
+fn fnv1a(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use assert_fs::TempDir;
+
+    use super::*;
+
+    fn at(time: &str) -> DateTime<Utc> {
+        time.parse().unwrap()
+    }
+
+    #[test]
+    fn hit_miss_and_expiry() -> Result<()> {
+        let dir = TempDir::new()?;
+        let cache = DiskCache::new(dir.path(), "token", Duration::from_secs(60 * 60));
+        assert_eq!(
+            cache.load::<String>("labels", at("2025-01-01T12:00:00Z")),
+            None
+        );
+
+        cache.store("labels", &["one", "two"], at("2025-01-01T12:00:00Z"))?;
+        assert_eq!(
+            cache.load::<String>("labels", at("2025-01-01T12:59:59Z")),
+            Some(vec!["one".to_string(), "two".to_string()])
+        );
+        assert_eq!(
+            cache.load::<String>("projects", at("2025-01-01T12:30:00Z")),
+            None
+        );
+        assert_eq!(
+            cache.load::<String>("labels", at("2025-01-01T13:00:00Z")),
+            None
+        );
+        // Clocks going backwards don't make stale items valid again.
+        assert_eq!(
+            cache.load::<String>("labels", at("2025-01-01T11:00:00Z")),
+            None
+        );
+
+        cache.remove("labels");
+        assert_eq!(
+            cache.load::<String>("labels", at("2025-01-01T12:30:00Z")),
+            None
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn separates_tokens() -> Result<()> {
+        let dir = TempDir::new()?;
+        let now = at("2025-01-01T12:00:00Z");
+        let cache = DiskCache::new(dir.path(), "token", DiskCache::DEFAULT_TTL);
+        cache.store("labels", &["one"], now)?;
+        let other = DiskCache::new(dir.path(), "other", DiskCache::DEFAULT_TTL);
+        assert_eq!(other.load::<String>("labels", now), None);
+        Ok(())
+    }
+
+    #[test]
+    fn ignores_broken_files() -> Result<()> {
+        let dir = TempDir::new()?;
+        let cache = DiskCache::new(dir.path(), "token", DiskCache::DEFAULT_TTL);
+        fs::create_dir_all(&cache.dir)?;
+        fs::write(cache.file("labels"), "not json")?;
+        assert_eq!(
+            cache.load::<String>("labels", at("2025-01-01T12:00:00Z")),
+            None
+        );
+        Ok(())
+    }
+}
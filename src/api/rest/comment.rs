@@ -66,6 +66,34 @@ pub struct Comment {
     pub reactions: Option<serde_json::Map<String, serde_json::Value>>,
 }
 
+impl Comment {
+    /// Summarizes the reactions as emoji and how often they were given, like "👍 x3, 🎉 x1".
+    ///
+    /// The API maps each reaction to the IDs of the users that gave it.
+    pub fn reaction_summary(&self) -> Option<String> {
+        let reactions = self.reactions.as_ref()?;
+        let mut counts = reactions
+            .iter()
+            .map(|(reaction, given)| {
+                let count = match given {
+                    serde_json::Value::Array(uids) => uids.len() as u64,
+                    serde_json::Value::Number(count) => count.as_u64().unwrap_or_default(),
+                    _ => 1,
+                };
+                (reaction, count)
+            })
+            .filter(|(_, count)| *count > 0)
+            .collect::<Vec<_>>();
+        // Most popular first
+        counts.sort_by(|(_, a), (_, b)| b.cmp(a));
+        let summary = counts
+            .into_iter()
+            .map(|(reaction, count)| format!("{reaction} x{count}"))
+            .collect::<Vec<_>>();
+        (!summary.is_empty()).then(|| summary.join(", "))
+    }
+}
+
 /// An optional attachment file attached to a comment.
 /// TODO: empty for now, so it acts as a marker.
 #[derive(Debug, Serialize, Deserialize, Clone)]
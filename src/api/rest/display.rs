@@ -1,8 +1,162 @@
-use crate::{api::tree::Tree, config::Config};
+use crate::{
+    api::tree::Tree,
+    config::{Config, TreeStyle},
+};
 
-use super::{Comment, DueDateFormatter, Label, Project, Section, Task};
+use super::{Collaborator, Comment, DueDateFormatter, Label, Project, Section, Task};
 use chrono::Utc;
 use owo_colors::{OwoColorize, Stream};
+use serde::Serialize;
+
+/// Hyperlink wraps the displayed text in an OSC 8 escape sequence, so terminals can make it
+/// clickable. Only renders the link if enabled, otherwise just the text is shown.
+pub struct Hyperlink<'a, T: std::fmt::Display>(pub T, pub &'a url::Url, pub bool);
+
+impl<T: std::fmt::Display> std::fmt::Display for Hyperlink<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Hyperlink(text, url, enabled) = self;
+        if *enabled {
+            write!(f, "\x1b]8;;{url}\x1b\\{text}\x1b]8;;\x1b\\")
+        } else {
+            write!(f, "{text}")
+        }
+    }
+}
+
+/// Markdown renders the common parts of Markdown text with terminal styles: headings, bold text,
+/// list items and links. Everything else is shown as written. Links are only clickable if
+/// hyperlinks are enabled, otherwise the URL is shown after the label.
+pub struct Markdown<'a>(pub &'a str, pub bool);
+
+impl Markdown<'_> {
+    fn inline(&self, f: &mut std::fmt::Formatter<'_>, mut text: &str) -> std::fmt::Result {
+        while !text.is_empty() {
+            if let Some(after) = text.strip_prefix("**")
+                && let Some(end) = after.find("**")
+            {
+                let bold = &after[..end];
+                write!(
+                    f,
+                    "{}",
+                    bold.if_supports_color(Stream::Stdout, |text| text.bold())
+                )?;
+                text = &after[end + 2..];
+                continue;
+            }
+            if let Some(after) = text.strip_prefix('[')
+                && let Some((label, tail)) = after.split_once("](")
+                && !label.contains(']')
+                && let Some(end) = tail.find(')')
+                && let Ok(url) = url::Url::parse(&tail[..end])
+            {
+                let label = label.if_supports_color(Stream::Stdout, |text| text.underline());
+                if self.1 {
+                    write!(f, "{}", Hyperlink(label, &url, true))?;
+                } else {
+                    write!(f, "{label} ({url})")?;
+                }
+                text = &tail[end + 1..];
+                continue;
+            }
+            let first = text.chars().next().map_or(0, char::len_utf8);
+            let next = text[first..]
+                .find(['*', '['])
+                .map_or(text.len(), |i| i + first);
+            write!(f, "{}", &text[..next])?;
+            text = &text[next..];
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Markdown<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, line) in self.0.lines().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            let content = line.trim_start();
+            let indent = &line[..line.len() - content.len()];
+            let heading = content.trim_start_matches('#');
+            if heading.len() < content.len() && heading.starts_with(' ') {
+                write!(
+                    f,
+                    "{}",
+                    heading.trim().if_supports_color(Stream::Stdout, |text| text
+                        .style(owo_colors::Style::new().bold().underline()))
+                )?;
+            } else if let Some(item) = ["- ", "* ", "+ "]
+                .iter()
+                .find_map(|bullet| content.strip_prefix(bullet))
+            {
+                write!(f, "{indent}• ")?;
+                self.inline(f, item)?;
+            } else {
+                write!(f, "{indent}")?;
+                self.inline(f, content)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Highlighted displays text with every case-insensitive match of the patterns emphasized. Each
+/// pattern gets its own color, if colors are enabled.
+pub struct Highlighted<'a>(pub &'a str, pub &'a [String]);
+
+impl Highlighted<'_> {
+    /// Returns the byte ranges of the text that match a pattern, together with the index of the
+    /// pattern. Earlier patterns win if matches overlap.
+    fn ranges(&self) -> Vec<(std::ops::Range<usize>, usize)> {
+        let Highlighted(text, patterns) = self;
+        let text = text.to_ascii_lowercase();
+        let mut ranges: Vec<(std::ops::Range<usize>, usize)> = Vec::new();
+        for (index, pattern) in patterns.iter().enumerate() {
+            if pattern.is_empty() {
+                continue;
+            }
+            for (start, m) in text.match_indices(&pattern.to_ascii_lowercase()) {
+                let range = start..start + m.len();
+                if !ranges
+                    .iter()
+                    .any(|(r, _)| r.start < range.end && range.start < r.end)
+                {
+                    ranges.push((range, index));
+                }
+            }
+        }
+        ranges.sort_by_key(|(r, _)| r.start);
+        ranges
+    }
+}
+
+impl std::fmt::Display for Highlighted<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = self.0;
+        let mut position = 0;
+        for (range, index) in self.ranges() {
+            write!(f, "{}", &text[position..range.start])?;
+            let matched = &text[range.clone()];
+            write!(
+                f,
+                "{}",
+                matched.if_supports_color(Stream::Stdout, |text| {
+                    let style = owo_colors::Style::new().bold().reversed();
+                    let style = match index % 5 {
+                        0 => style.yellow(),
+                        1 => style.cyan(),
+                        2 => style.magenta(),
+                        3 => style.green(),
+                        _ => style.blue(),
+                    };
+                    text.style(style)
+                })
+            )?;
+            position = range.end;
+        }
+        write!(f, "{}", &text[position..])
+    }
+}
 
 /// FullComment allows to display full comment metadata when [std::fmt::Display]ing it.
 pub struct FullComment<'a>(pub &'a Comment);
@@ -28,6 +182,59 @@ impl std::fmt::Display for FullComment<'_> {
             }
         )?;
         write!(f, "Content: {}", comment.content)?;
+        if let Some(reactions) = comment.reaction_summary() {
+            write!(f, "\nReactions: {reactions}")?;
+        }
+        Ok(())
+    }
+}
+
+/// CommentThread displays comments as a chronological thread, with the content of each comment
+/// indented below who posted it and when. Posters are shown by name if they are among the
+/// collaborators, otherwise by their user ID.
+pub struct CommentThread<'a>(pub &'a [Comment], pub &'a [Collaborator]);
+
+impl std::fmt::Display for CommentThread<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let CommentThread(comments, collaborators) = self;
+        let mut comments = comments
+            .iter()
+            .filter(|c| !c.is_deleted)
+            .collect::<Vec<_>>();
+        comments.sort_by_key(|c| c.posted_at);
+        for (index, comment) in comments.into_iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(
+                f,
+                "{} {}",
+                comment
+                    .id
+                    .if_supports_color(Stream::Stdout, |text| text.bright_yellow()),
+                comment.posted_at.format("%Y-%m-%d %H:%M"),
+            )?;
+            if let Some(uid) = &comment.posted_uid {
+                let poster = collaborators
+                    .iter()
+                    .find(|c| &c.id == uid)
+                    .map_or(uid.as_str(), |c| c.name.as_str());
+                write!(
+                    f,
+                    " {}",
+                    poster.if_supports_color(Stream::Stdout, |text| text.bright_blue())
+                )?;
+            }
+            for line in comment.content.lines() {
+                write!(f, "\n  {line}")?;
+            }
+            if comment.file_attachment.is_some() {
+                write!(f, "\n  [attachment]")?;
+            }
+            if let Some(reactions) = comment.reaction_summary() {
+                write!(f, "\n  {reactions}")?;
+            }
+        }
         Ok(())
     }
 }
@@ -48,6 +255,39 @@ impl std::fmt::Display for FullLabel<'_> {
     }
 }
 
+/// FullSection shows a section together with the project it belongs to and its order.
+pub struct FullSection<'a>(pub &'a Section, pub Option<&'a Project>);
+
+impl std::fmt::Display for FullSection<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let FullSection(section, project) = self;
+        write!(
+            f,
+            "{} [{}] (order {})",
+            section,
+            project
+                .map(|p| p.name.as_str())
+                .unwrap_or(&section.project_id),
+            section.order
+        )
+    }
+}
+
+/// JsonTask is a [`Task`] together with the names of its project and section and its subtasks,
+/// used for machine readable output.
+#[derive(Serialize)]
+pub struct JsonTask<'a> {
+    /// The task itself.
+    #[serde(flatten)]
+    pub task: &'a Task,
+    /// The name of the project the task is in.
+    pub project_name: Option<&'a str>,
+    /// The name of the section the task is in.
+    pub section_name: Option<&'a str>,
+    /// The subtasks of the task.
+    pub subtasks: Vec<JsonTask<'a>>,
+}
+
 /// Used to display full information about a Task.
 pub struct FullTask<'a>(
     pub &'a Task,
@@ -60,21 +300,26 @@ pub struct FullTask<'a>(
 impl std::fmt::Display for FullTask<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let FullTask::<'_>(task, project, section, labels, config) = self;
+        let hyperlinks = config.use_hyperlinks();
         write!(
             f,
-            "ID: {}\nPriority: {}\nContent: {}\nDescription: {}",
-            task.id
-                .if_supports_color(Stream::Stdout, |text| text.bright_yellow()),
+            "ID: {}\nPriority: {}\nContent: {}\nDescription: ",
+            Hyperlink(
+                task.id
+                    .if_supports_color(Stream::Stdout, |text| text.bright_yellow()),
+                &task.web_url(),
+                hyperlinks
+            ),
             task.priority,
             task.content,
-            task.description,
         )?;
+        if config.use_markdown() {
+            write!(f, "{}", Markdown(&task.description, hyperlinks))?;
+        } else {
+            write!(f, "{}", task.description)?;
+        }
         if let Some(due) = &task.due {
-            write!(
-                f,
-                "\nDue: {}",
-                DueDateFormatter(due, &config.override_time.unwrap_or_else(Utc::now))
-            )?;
+            write!(f, "\nDue: {}", DueDateFormatter(due, config))?;
         }
         if !labels.is_empty() {
             write!(
@@ -88,7 +333,18 @@ impl std::fmt::Display for FullTask<'_> {
             )?;
         }
         if let Some(project) = &project {
-            write!(f, "\nProject: {project}")?;
+            write!(
+                f,
+                "\nProject: {} {}",
+                Hyperlink(
+                    project
+                        .id
+                        .if_supports_color(Stream::Stdout, |text| text.bright_yellow()),
+                    &project.web_url(),
+                    hyperlinks
+                ),
+                project.name
+            )?;
         }
         if let Some(section) = &section {
             write!(f, "\nSection: {section}")?;
@@ -104,127 +360,513 @@ impl std::fmt::Display for FullTask<'_> {
             write!(f, "\nDuration: {} {}", amount, unit)?;
         }
         write!(f, "\nComments: {}", task.comment_count)?;
+        write!(f, "\nURL: {}", task.web_url())?;
         Ok(())
     }
 }
 
+/// Renders the indentation in front of a task in a tree. `branches` holds for every level below
+/// the top whether the task on that level is the last of its siblings, ending with the task itself.
+pub fn tree_prefix(style: TreeStyle, branches: &[bool]) -> String {
+    let (branch, last_branch, line, empty) = match style {
+        TreeStyle::Unicode => ("├─ ", "└─ ", "│  ", "   "),
+        TreeStyle::Ascii => ("|- ", "`- ", "|  ", "   "),
+    };
+    let Some((&last, ancestors)) = branches.split_last() else {
+        return String::new();
+    };
+    ancestors
+        .iter()
+        .map(|&last| if last { empty } else { line })
+        .chain(std::iter::once(if last { last_branch } else { branch }))
+        .collect()
+}
+
 /// Used to display task as an item in a list.
-pub struct TableTask<'a>(
-    pub &'a Tree<Task>,
-    pub Option<&'a Project>,
-    pub Option<&'a Section>,
-    pub Vec<&'a Label>,
-    pub &'a Config,
-    pub bool, // show_id
-);
+pub struct TableTask<'a> {
+    /// The task to show.
+    pub task: &'a Tree<Task>,
+    /// The project of the task, shown if known.
+    pub project: Option<&'a Project>,
+    /// The section of the task, shown together with the project.
+    pub section: Option<&'a Section>,
+    /// The labels of the task, only labels that are known are shown.
+    pub labels: Vec<&'a Label>,
+    /// Configuration for how the task is shown.
+    pub config: &'a Config,
+    /// Shows the ID of the task and links its content, if no fields are given.
+    pub show_id: bool,
+    /// Text to highlight in the content of the task.
+    pub highlights: &'a [String],
+    /// The fields to show in this order, or the default fields if unset.
+    pub fields: Option<&'a [TaskField]>,
+    /// The position of the task in its tree, see [`tree_prefix`]. Without it every level is drawn
+    /// as if it was the last one.
+    pub branches: Option<&'a [bool]>,
+}
 
 impl TableTask<'_> {
     /// Initializes a TableTask item that only displays data that is directly available from a
     /// [`Task`].
     pub fn from_task<'a>(task: &'a Tree<Task>, config: &'a Config) -> TableTask<'a> {
-        TableTask(task, None, None, vec![], config, false)
+        TableTask {
+            task,
+            project: None,
+            section: None,
+            labels: vec![],
+            config,
+            show_id: false,
+            highlights: &[],
+            fields: None,
+            branches: None,
+        }
     }
 }
 
 impl std::fmt::Display for TableTask<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let TableTask::<'_>(task, project, section, labels, config, show_id) = self;
-        let subtask_padding = if task.depth > 0 {
-            format!("{}⌞ ", "  ".repeat(task.depth))
+        let TableTask {
+            task,
+            project,
+            section,
+            labels,
+            config,
+            show_id,
+            highlights,
+            fields,
+            branches,
+        } = self;
+        let content = Highlighted(&task.content, highlights);
+        let tree_style = if config.use_plain() {
+            TreeStyle::Ascii
         } else {
-            "".to_string()
+            config.tree_style
+        };
+        let subtask_padding = match branches {
+            Some(branches) => tree_prefix(tree_style, branches),
+            // Without knowing the siblings, every level is drawn as if it was the last one.
+            None => tree_prefix(tree_style, &vec![true; task.depth]),
         };
 
-        if *show_id {
-            write!(
-                f,
-                "{}{} {} {}",
-                subtask_padding,
-                task.id
-                    .if_supports_color(Stream::Stdout, |text| text.bright_yellow()),
-                task.priority,
-                task.content,
-            )?;
-        } else {
-            write!(f, "{}{} {}", subtask_padding, task.priority, task.content,)?;
-        }
-
-        // Show task age (days since created)
-        let now = config.override_time.unwrap_or_else(Utc::now);
-        let days_ago = (now - task.created_at).num_days();
-        if days_ago >= 7 {
-            write!(
-                f,
-                " {}({}일 전)",
-                "".if_supports_color(Stream::Stdout, |_| "📅"),
-                days_ago
-            )?;
-        }
-        if let Some(due) = &task.due {
-            write!(
-                f,
-                " {}",
-                DueDateFormatter(due, &config.override_time.unwrap_or_else(Utc::now))
-            )?;
-        }
-        if !labels.is_empty() {
-            write!(
-                f,
-                " {}",
-                labels
-                    .iter()
-                    .map(|l| l.to_string())
-                    .collect::<Vec<_>>()
-                    .join(" ")
-            )?;
-        }
-        if let Some(deadline) = &task.deadline
-            && let Some(date) = deadline.date()
-        {
-            write!(
-                f,
-                " {}⏰{}",
-                "".if_supports_color(Stream::Stdout, |_| "📅"),
-                date.format("%m/%d")
-            )?;
-        }
-        if let Some(duration) = &task.duration
-            && let (Some(amount), Some(unit)) = (duration.amount(), duration.unit())
-        {
-            let unit_symbol = match unit {
-                crate::api::rest::task::DurationUnit::Minute => "⏱️",
-                crate::api::rest::task::DurationUnit::Day => "📅",
-            };
-            write!(
-                f,
-                " {}{}{}",
-                unit_symbol.if_supports_color(Stream::Stdout, |_| "⏱️"),
-                amount,
-                match unit {
-                    crate::api::rest::task::DurationUnit::Minute => "m",
-                    crate::api::rest::task::DurationUnit::Day => "d",
+        let default_fields;
+        let fields = match fields {
+            Some(fields) => *fields,
+            None => {
+                default_fields = TaskField::defaults(*show_id);
+                &default_fields[..]
+            }
+        };
+        let fragments = fields
+            .iter()
+            .filter_map(|field| match field {
+                TaskField::Id => Some(
+                    Hyperlink(
+                        task.id
+                            .if_supports_color(Stream::Stdout, |text| text.bright_yellow()),
+                        &task.web_url(),
+                        config.use_hyperlinks(),
+                    )
+                    .to_string(),
+                ),
+                TaskField::Priority => Some(task.priority.to_string()),
+                // With IDs shown the task is meant to be worked with, so the content links to it.
+                TaskField::Content => Some(
+                    Hyperlink(
+                        &content,
+                        &task.web_url(),
+                        *show_id && config.use_hyperlinks(),
+                    )
+                    .to_string(),
+                ),
+                TaskField::Age => {
+                    // Show task age (days since created)
+                    let badge = &config.age_badge;
+                    let now = config.override_time.unwrap_or_else(Utc::now);
+                    let days_ago = (now - task.created_at).num_days();
+                    (badge.enabled && days_ago >= badge.threshold_days.into()).then(|| {
+                        format!(
+                            "{}({}{})",
+                            marker(config, "📅", ""),
+                            days_ago,
+                            badge.suffix(config.use_plain())
+                        )
+                    })
                 }
-            )?;
+                TaskField::Due => task
+                    .due
+                    .as_ref()
+                    .map(|due| DueDateFormatter(due, config).to_string()),
+                TaskField::Labels => (!labels.is_empty()).then(|| {
+                    labels
+                        .iter()
+                        .map(|l| l.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                }),
+                TaskField::Deadline => task.deadline.as_ref().and_then(|d| d.date()).map(|date| {
+                    if config.use_plain() {
+                        format!("[deadline] {}", date.format("%m/%d"))
+                    } else {
+                        format!(
+                            "{}⏰{}",
+                            "".if_supports_color(Stream::Stdout, |_| "📅"),
+                            date.format("%m/%d")
+                        )
+                    }
+                }),
+                TaskField::Duration => {
+                    let duration = task.duration.as_ref()?;
+                    let (Some(amount), Some(unit)) = (duration.amount(), duration.unit()) else {
+                        return None;
+                    };
+                    let unit_symbol = match unit {
+                        crate::api::rest::task::DurationUnit::Minute => "⏱️",
+                        crate::api::rest::task::DurationUnit::Day => "📅",
+                    };
+                    Some(format!(
+                        "{}{}{}",
+                        if config.use_plain() {
+                            "[duration] ".to_string()
+                        } else {
+                            unit_symbol
+                                .if_supports_color(Stream::Stdout, |_| "⏱️")
+                                .to_string()
+                        },
+                        amount,
+                        match unit {
+                            crate::api::rest::task::DurationUnit::Minute => "m",
+                            crate::api::rest::task::DurationUnit::Day => "d",
+                        }
+                    ))
+                }
+                TaskField::Project => project.map(|p| match section {
+                    Some(s) => format!("[{}/{}]", p.name, s.name),
+                    None => format!("[{}]", p.name),
+                }),
+                TaskField::Completed => {
+                    let completed_at = task.completed_at.as_ref()?;
+                    let dt = chrono::DateTime::parse_from_rfc3339(completed_at).ok()?;
+                    let local_dt = dt.with_timezone(&chrono::Local);
+                    Some(format!(
+                        "{}{}",
+                        marker(config, "✅ ", "[done] "),
+                        local_dt.format("%m/%d %H:%M")
+                    ))
+                }
+            })
+            .collect::<Vec<_>>();
+        write!(f, "{}{}", subtask_padding, fragments.join(" "))
+    }
+}
+
+/// Returns the emoji if the terminal supports it, or the ASCII marker in plain mode.
+pub fn marker(config: &Config, emoji: &'static str, plain: &'static str) -> String {
+    if config.use_plain() {
+        plain.to_string()
+    } else {
+        "".if_supports_color(Stream::Stdout, |_| emoji).to_string()
+    }
+}
+
+/// TaskField is a part of a task that can be shown in a [`TableTask`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display, strum::VariantNames)]
+#[strum(serialize_all = "lowercase")]
+pub enum TaskField {
+    /// The ID of the task.
+    Id,
+    /// The priority of the task.
+    Priority,
+    /// The name of the task.
+    Content,
+    /// How long ago the task was created, only shown for older tasks.
+    Age,
+    /// The due date of the task.
+    Due,
+    /// The labels of the task.
+    Labels,
+    /// The deadline of the task.
+    Deadline,
+    /// The estimated duration of the task.
+    Duration,
+    /// The project and section of the task.
+    Project,
+    /// When the task was completed.
+    Completed,
+}
+
+impl TaskField {
+    /// Returns the fields that are shown if no fields are specified.
+    pub fn defaults(show_id: bool) -> Vec<TaskField> {
+        let mut fields = vec![
+            TaskField::Priority,
+            TaskField::Content,
+            TaskField::Age,
+            TaskField::Due,
+            TaskField::Labels,
+            TaskField::Deadline,
+            TaskField::Duration,
+            TaskField::Project,
+            TaskField::Completed,
+        ];
+        if show_id {
+            fields.insert(0, TaskField::Id);
         }
-        if let Some(p) = &project {
-            write!(f, " [{}", p.name)?;
-            if let Some(s) = &section {
-                write!(f, "/{}", s.name)?;
+        fields
+    }
+}
+
+impl std::str::FromStr for TaskField {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use strum::VariantNames;
+        let field = match s.trim().to_lowercase().as_str() {
+            "id" => TaskField::Id,
+            "priority" => TaskField::Priority,
+            "content" => TaskField::Content,
+            "age" => TaskField::Age,
+            "due" => TaskField::Due,
+            "labels" => TaskField::Labels,
+            "deadline" => TaskField::Deadline,
+            "duration" => TaskField::Duration,
+            "project" => TaskField::Project,
+            "completed" => TaskField::Completed,
+            _ => {
+                return Err(format!(
+                    "unknown field '{s}', valid fields are: {}",
+                    TaskField::VARIANTS.join(", ")
+                ));
             }
-            write!(f, "]")?;
-        }
-        if let Some(completed_at) = &task.completed_at
-            && let Ok(dt) = chrono::DateTime::parse_from_rfc3339(completed_at)
-        {
-            let local_dt = dt.with_timezone(&chrono::Local);
-            let formatted = local_dt.format("%m/%d %H:%M");
-            write!(
-                f,
-                " {}{}",
-                "".if_supports_color(Stream::Stdout, |_| "✅ "),
-                formatted
-            )?;
+        };
+        Ok(field)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::api::rest::{Deadline, DueDate, Duration, DurationUnit};
+
+    const DESCRIPTION: &str = "# Plan\n- **buy** [milk](https://example.com/)\n  * fresh";
+
+    #[test]
+    fn raw_description() {
+        let mut task = Task::new("1", "Shopping");
+        task.description = DESCRIPTION.to_string();
+        let config = Config::default();
+        let shown = FullTask(&task, None, None, vec![], &config).to_string();
+        let description = shown
+            .lines()
+            .skip_while(|l| !l.starts_with("Description: "))
+            .take(3)
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(description, format!("Description: {DESCRIPTION}"));
+    }
+
+    #[test]
+    fn full_task_url() {
+        let config = Config::default();
+        let mut task = Task::new("123", "Shopping");
+        let shown = FullTask(&task, None, None, vec![], &config).to_string();
+        assert!(
+            shown.ends_with("\nURL: https://app.todoist.com/app/task/123"),
+            "{shown}"
+        );
+        task.url = "https://app.todoist.com/app/task/shopping-123"
+            .parse()
+            .unwrap();
+        let shown = FullTask(&task, None, None, vec![], &config).to_string();
+        assert!(shown.contains("\nURL: https://app.todoist.com/app/task/shopping-123"));
+    }
+
+    #[test]
+    fn markdown() {
+        assert_eq!(
+            Markdown(DESCRIPTION, true).to_string(),
+            "Plan\n• buy \x1b]8;;https://example.com/\x1b\\milk\x1b]8;;\x1b\\\n  • fresh"
+        );
+        assert_eq!(
+            Markdown(DESCRIPTION, false).to_string(),
+            "Plan\n• buy milk (https://example.com/)\n  • fresh"
+        );
+        assert_eq!(
+            Markdown("3 * 4 [not a link]", true).to_string(),
+            "3 * 4 [not a link]"
+        );
+    }
+
+    #[test]
+    fn comment_thread() {
+        let comments: Vec<Comment> = serde_json::from_value(serde_json::json!([
+            {
+                "id": "2",
+                "posted_uid": "300",
+                "posted_at": "2025-10-02T10:00:00Z",
+                "content": "Sounds good",
+                "reactions": {"👍": ["100", "200", "300"], "🎉": ["100"]}
+            },
+            {
+                "id": "1",
+                "posted_uid": "100",
+                "posted_at": "2025-10-01T10:00:00Z",
+                "content": "Kickoff\nat noon",
+                "reactions": null
+            }
+        ]))
+        .unwrap();
+        assert_eq!(comments[0].reaction_summary().unwrap(), "👍 x3, 🎉 x1");
+        assert_eq!(comments[1].reaction_summary(), None);
+
+        let collaborators = vec![Collaborator {
+            id: "100".to_string(),
+            name: "Alice".to_string(),
+            email: "alice@example.com".to_string(),
+        }];
+        assert_eq!(
+            CommentThread(&comments, &collaborators).to_string(),
+            "1 2025-10-01 10:00 Alice\n  Kickoff\n  at noon\n\
+             2 2025-10-02 10:00 300\n  Sounds good\n  👍 x3, 🎉 x1"
+        );
+    }
+
+    #[test]
+    fn full_section() {
+        let project = Project::new("1", "Work");
+        let mut section = Section::new("10", "1", "Meetings");
+        section.order = 2;
+        assert_eq!(
+            FullSection(&section, Some(&project)).to_string(),
+            "10 Meetings [Work] (order 2)"
+        );
+        assert_eq!(
+            FullSection(&section, None).to_string(),
+            "10 Meetings [1] (order 2)"
+        );
+    }
+
+    #[test]
+    fn hyperlink() {
+        let url: url::Url = "https://app.todoist.com/app/task/123".parse().unwrap();
+        assert_eq!(Hyperlink("123", &url, false).to_string(), "123");
+        assert_eq!(
+            Hyperlink("123", &url, true).to_string(),
+            "\x1b]8;;https://app.todoist.com/app/task/123\x1b\\123\x1b]8;;\x1b\\"
+        );
+    }
+
+    #[test]
+    fn highlighted_ranges() {
+        let patterns = vec!["review".to_string(), "PR".to_string(), "".to_string()];
+        let text = "Review the pr, then review again";
+        assert_eq!(
+            Highlighted(text, &patterns).ranges(),
+            vec![(0..6, 0), (11..13, 1), (20..26, 0)]
+        );
+        assert_eq!(Highlighted(text, &[]).to_string(), text);
+    }
+
+    #[test]
+    fn table_task_fields() {
+        let config = Config {
+            override_time: Some(Utc::now()),
+            ..Default::default()
+        };
+        let project = Project::new("1", "Work");
+        let task = Tree::new(Task::new("123", "Write report"));
+        let fields: Vec<TaskField> = "content,project,id"
+            .split(',')
+            .map(|f| f.parse().unwrap())
+            .collect();
+        let table = TableTask {
+            project: Some(&project),
+            fields: Some(&fields),
+            ..TableTask::from_task(&task, &config)
+        };
+        assert_eq!(table.to_string(), "Write report [Work] 123");
+        let err = "name".parse::<TaskField>().unwrap_err();
+        assert!(err.contains("valid fields are: id, priority, content"));
+    }
+
+    #[test]
+    fn age_badge() {
+        let now: chrono::DateTime<Utc> = "2025-10-10T12:00:00Z".parse().unwrap();
+        let mut config = Config {
+            override_time: Some(now),
+            age_badge: crate::config::AgeBadge {
+                threshold_days: 5,
+                suffix: Some("d ago".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let fields = [TaskField::Content, TaskField::Age];
+        let shown = |days: i64, config: &Config| {
+            let mut task = Task::new("1", "Old");
+            task.created_at = now - chrono::TimeDelta::days(days);
+            let task = Tree::new(task);
+            TableTask {
+                fields: Some(&fields),
+                ..TableTask::from_task(&task, config)
+            }
+            .to_string()
+        };
+        assert_eq!(shown(3, &config), "Old");
+        assert_eq!(shown(10, &config), "Old (10d ago)");
+        config.age_badge.enabled = false;
+        assert_eq!(shown(10, &config), "Old");
+        // The default keeps the original badge.
+        config.age_badge = Default::default();
+        assert_eq!(shown(10, &config), "Old (10일 전)");
+    }
+
+    #[test]
+    fn plain_markers() {
+        let now: chrono::DateTime<Utc> = "2025-10-10T12:00:00Z".parse().unwrap();
+        let config = Config {
+            override_time: Some(now),
+            plain: true,
+            ..Default::default()
+        };
+        let mut task = Task::new("1", "Water plants");
+        task.created_at = now - chrono::TimeDelta::days(30);
+        task.due = Some(DueDate {
+            string: "every day".to_string(),
+            is_recurring: true,
+            ..DueDate::on("2025-10-10")
+        });
+        task.deadline = Some(Deadline::Structured {
+            date: "2025-10-24".parse().unwrap(),
+            lang: None,
+        });
+        task.duration = Some(Duration::Structured {
+            amount: 30,
+            unit: DurationUnit::Minute,
+        });
+        task.completed_at = Some("2025-10-10T11:00:00Z".to_string());
+        let mut tree = Tree::new(task.clone());
+        tree.depth = 2;
+        let fields = [
+            TaskField::Content,
+            TaskField::Age,
+            TaskField::Due,
+            TaskField::Deadline,
+            TaskField::Duration,
+            TaskField::Completed,
+        ];
+        let table = TableTask {
+            show_id: true,
+            fields: Some(&fields),
+            ..TableTask::from_task(&tree, &config)
         }
-        Ok(())
+        .to_string();
+        assert!(table.is_ascii(), "{table}");
+        assert!(table.contains("[repeat] every day"), "{table}");
+        assert!(table.contains("(30d ago)"), "{table}");
+        assert!(table.contains("[deadline] 10/24"), "{table}");
+        assert!(table.contains("[duration] 30m"), "{table}");
+        assert!(table.contains("[done] "), "{table}");
+        let full = FullTask(&task, None, None, vec![], &config).to_string();
+        assert!(full.is_ascii(), "{full}");
     }
 }
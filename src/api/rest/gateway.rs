@@ -1,4 +1,7 @@
-use std::time::Duration;
+use std::{
+    sync::{Mutex, PoisonError},
+    time::Duration,
+};
 
 use chrono::Utc;
 use color_eyre::{
@@ -8,15 +11,49 @@ use color_eyre::{
 use lazy_static::lazy_static;
 use reqwest::{Client, StatusCode};
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware, RequestBuilder};
-use reqwest_retry::{RetryTransientMiddleware, policies::ExponentialBackoff};
+use reqwest_retry::{
+    RetryTransientMiddleware, Retryable, RetryableStrategy, default_on_request_failure,
+    default_on_request_success, policies::ExponentialBackoff,
+};
 use serde::{Serialize, de::DeserializeOwned};
 use uuid::Uuid;
 
 use super::{
-    Comment, CompletedTasksResponse, CreateComment, CreateLabel, CreateProject, CreateSection,
-    CreateTask, Label, LabelID, PaginatedResponse, Project, ProjectID, Section, SectionID, Task,
-    TaskDue, TaskID, UpdateTask,
+    Collaborator, Comment, CompletedTasksResponse, CreateComment, CreateLabel, CreateProject,
+    CreateSection, CreateTask, DiskCache, Label, LabelID, PaginatedResponse, Project, ProjectID,
+    Section, SectionID, SyncCommand, SyncResponse, Task, TaskDue, TaskID, UpdateLabel,
+    UpdateProject, UpdateSection, UpdateTask, User,
 };
+use crate::output::{info, verbose};
+
+/// Returned when the API rejects the token, so commands can tell it apart from other failures.
+#[derive(thiserror::Error, Debug)]
+#[error("the API token was rejected ({0}), set up a valid token with `doist auth <TOKEN>`")]
+pub struct TokenRejected(pub StatusCode);
+
+/// Returned when the API answers with an unsuccessful status code.
+#[derive(thiserror::Error, Debug)]
+#[error("Bad response from API: {status} - {text}")]
+pub struct BadResponse {
+    /// The status code of the response.
+    pub status: StatusCode,
+    /// The body of the response, which usually explains the error.
+    pub text: String,
+}
+
+/// Returned in dry-run mode by changes that need a response from the API to go on, after the
+/// change was printed. Commands stop there, as nothing can be done with a made up response.
+#[derive(thiserror::Error, Debug)]
+#[error("dry run, stopped before the first change that needs a response from the API")]
+pub struct DryRunStopped;
+
+/// The maximum amount of completed tasks the API returns per page.
+pub const COMPLETED_TASKS_MAX_LIMIT: u32 = 200;
+
+/// How often a rate limited request is retried before giving up.
+const RATE_LIMIT_RETRIES: u32 = 3;
+/// Upper bound for waiting on a rate limit, in case the API asks for an unreasonable delay.
+const RATE_LIMIT_MAX_WAIT: Duration = Duration::from_secs(60);
 
 /// Parameters for fetching completed tasks by due date.
 pub struct CompletedTasksByDueDateParams<'a> {
@@ -32,7 +69,7 @@ pub struct CompletedTasksByDueDateParams<'a> {
     pub filter_query: Option<&'a str>,
     /// Pagination cursor for fetching next page.
     pub cursor: Option<&'a str>,
-    /// Limit results per page (max: 200).
+    /// Limit results per page, values above [`COMPLETED_TASKS_MAX_LIMIT`] are clamped.
     pub limit: Option<u32>,
 }
 
@@ -54,15 +91,107 @@ pub struct CompletedTasksByCompletionDateParams<'a> {
     pub filter_query: Option<&'a str>,
     /// Pagination cursor for fetching next page.
     pub cursor: Option<&'a str>,
-    /// Limit results per page (max: 200).
+    /// Limit results per page, values above [`COMPLETED_TASKS_MAX_LIMIT`] are clamped.
     pub limit: Option<u32>,
 }
 
+/// Tunes how the [`Gateway`] talks to the API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GatewayOptions {
+    /// How long to wait for a response before giving up on a request.
+    pub timeout: Duration,
+    /// How often a request that failed with a transient error, like a network issue or a server
+    /// error, is retried.
+    pub max_retries: u32,
+    /// Only prints the changes commands would make instead of sending them, see
+    /// [`Gateway::dry_run`].
+    pub dry_run: bool,
+}
+
+impl Default for GatewayOptions {
+    fn default() -> Self {
+        GatewayOptions {
+            timeout: Duration::from_secs(30),
+            max_retries: 3,
+            dry_run: false,
+        }
+    }
+}
+
 /// Makes network calls to the Todoist API and returns structs that can then be worked with.
+///
+/// Projects, sections and labels are cached for the lifetime of the gateway, as most commands
+/// need them and they rarely change. Changing them through the gateway invalidates the cache.
+/// With [`Gateway::with_disk_cache`] they are also kept between runs.
 pub struct Gateway {
     client: ClientWithMiddleware,
     token: String,
     url: url::Url,
+    timeout: Duration,
+    dry_run: bool,
+    projects: Cached<Project>,
+    sections: Cached<Section>,
+    labels: Cached<Label>,
+    disk_cache: Option<DiskCache>,
+}
+
+/// Keeps the result of a list request around, so it's only fetched once.
+struct Cached<T> {
+    /// Names the items in the [`DiskCache`].
+    kind: &'static str,
+    items: Mutex<Option<Vec<T>>>,
+}
+
+impl<T: Clone + Serialize + DeserializeOwned> Cached<T> {
+    fn new(kind: &'static str) -> Self {
+        Cached {
+            kind,
+            items: Mutex::new(None),
+        }
+    }
+
+    /// Returns the cached items, or fetches and caches them if there are none yet. The disk cache
+    /// is tried before fetching, if there is one.
+    async fn get_or_fetch(
+        &self,
+        disk: Option<&DiskCache>,
+        fetch: impl Future<Output = Result<Vec<T>>>,
+    ) -> Result<Vec<T>> {
+        if let Some(items) = self
+            .items
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_ref()
+        {
+            return Ok(items.clone());
+        }
+        let stored = disk.and_then(|disk| disk.load(self.kind, Utc::now()));
+        let items = match stored {
+            Some(items) => {
+                verbose!("using cached {}", self.kind);
+                items
+            }
+            None => {
+                let items = fetch.await?;
+                if let Some(disk) = disk
+                    && let Err(e) = disk.store(self.kind, &items, Utc::now())
+                {
+                    // The cache is only an optimization, so failing to write it isn't fatal.
+                    verbose!("unable to cache {}: {e}", self.kind);
+                }
+                items
+            }
+        };
+        *self.items.lock().unwrap_or_else(PoisonError::into_inner) = Some(items.clone());
+        Ok(items)
+    }
+
+    fn clear(&self, disk: Option<&DiskCache>) {
+        *self.items.lock().unwrap_or_else(PoisonError::into_inner) = None;
+        if let Some(disk) = disk {
+            disk.remove(self.kind);
+        }
+    }
 }
 
 lazy_static! {
@@ -78,15 +207,84 @@ impl Gateway {
     /// * `token` - the API token used for network calls.
     /// * `url` - the base URL to call. See [`struct@TODOIST_API_URL`]
     pub fn new(token: &str, url: &url::Url) -> Gateway {
-        let retry_policy = ExponentialBackoff::builder().build_with_max_retries(3);
+        Gateway::with_options(token, url, GatewayOptions::default())
+    }
+
+    /// Create a new [`Gateway`] with a custom timeout and amount of retries.
+    ///
+    /// * `token` - the API token used for network calls.
+    /// * `url` - the base URL to call. See [`struct@TODOIST_API_URL`]
+    /// * `options` - how requests are sent, see [`GatewayOptions`].
+    pub fn with_options(token: &str, url: &url::Url, options: GatewayOptions) -> Gateway {
+        let retry_policy =
+            ExponentialBackoff::builder().build_with_max_retries(options.max_retries);
         let client = ClientBuilder::new(Client::new())
-            .with(RetryTransientMiddleware::new_with_policy(retry_policy))
+            .with(RetryTransientMiddleware::new_with_policy_and_strategy(
+                retry_policy,
+                TransientExceptRateLimit,
+            ))
             .build();
         Gateway {
             client,
             token: token.to_string(),
             url: url.clone(),
+            timeout: options.timeout,
+            dry_run: options.dry_run,
+            projects: Cached::new("projects"),
+            sections: Cached::new("sections"),
+            labels: Cached::new("labels"),
+            disk_cache: None,
+        }
+    }
+
+    /// Keeps projects, sections and labels in the given [`DiskCache`], so they can be reused by
+    /// later runs.
+    pub fn with_disk_cache(mut self, cache: DiskCache) -> Gateway {
+        self.disk_cache = Some(cache);
+        self
+    }
+
+    /// Drops the cached projects, sections and labels, so they are fetched again on next use.
+    pub fn refresh(&self) {
+        let disk = self.disk_cache.as_ref();
+        self.projects.clear(disk);
+        self.sections.clear(disk);
+        self.labels.clear(disk);
+    }
+
+    /// Drops the cached items that a change to the given path might have modified.
+    fn invalidate(&self, path: &str) {
+        let disk = self.disk_cache.as_ref();
+        if path.starts_with("api/v1/projects") {
+            // Sections are removed or archived together with their project.
+            self.projects.clear(disk);
+            self.sections.clear(disk);
+        } else if path.starts_with("api/v1/sections") {
+            self.sections.clear(disk);
+        } else if path.starts_with("api/v1/labels") {
+            self.labels.clear(disk);
+        }
+    }
+
+    /// Returns true if changes are only printed instead of sent, see [`Gateway::dry_run`].
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// In dry-run mode, prints the change that would be sent to the API as JSON and returns true,
+    /// so the caller can skip the actual request. Returns false otherwise.
+    ///
+    /// * `action` - what the change does, like "create task".
+    /// * `payload` - the content that would be sent.
+    pub fn dry_run<T: Serialize>(&self, action: &str, payload: &T) -> Result<bool> {
+        if !self.dry_run {
+            return Ok(false);
         }
+        info!(
+            "dry run, would {action}: {}",
+            serde_json::to_string_pretty(payload)?
+        );
+        Ok(true)
     }
 
     /// Retuns a [`Task`].
@@ -102,18 +300,55 @@ impl Gateway {
     ///
     /// * `filter` - a filter query as described in the [documentation](https://todoist.com/help/articles/205248842).
     pub async fn tasks(&self, filter: Option<&str>) -> Result<Vec<Task>> {
-        let response: PaginatedResponse<Task> = if let Some(filter_str) = filter {
+        if let Some(filter_str) = filter {
             // API v1 uses /api/v1/tasks/filter with query parameter
-            self.get("api/v1/tasks/filter", Some(&[("query", filter_str)]))
+            self.get_paginated("api/v1/tasks/filter", &[("query", filter_str)])
                 .await
-                .wrap_err("unable to get tasks with filter")?
+                .wrap_err("unable to get tasks with filter")
         } else {
             // Without filter, use regular /api/v1/tasks endpoint
-            self.get::<(), _>("api/v1/tasks", None)
+            self.get_paginated("api/v1/tasks", &[])
                 .await
-                .wrap_err("unable to get tasks")?
+                .wrap_err("unable to get tasks")
+        }
+    }
+
+    /// Returns all open tasks of a single project, including the subtasks.
+    pub async fn project_tasks(&self, id: &ProjectID) -> Result<Vec<Task>> {
+        self.get_paginated("api/v1/tasks", &[("project_id", id)])
+            .await
+            .wrap_err("unable to get tasks of project")
+    }
+
+    /// Returns a single page of tasks, together with the cursor of the next page if there is one.
+    ///
+    /// * `filter` - a filter query, see [`Gateway::tasks`].
+    /// * `cursor` - the `next_cursor` of the previous page, or [`None`] for the first page.
+    /// * `limit` - the amount of tasks on the page, the API default is used if unset.
+    pub async fn tasks_paged(
+        &self,
+        filter: Option<&str>,
+        cursor: Option<&str>,
+        limit: Option<u32>,
+    ) -> Result<PaginatedResponse<Task>> {
+        let limit = limit.map(|l| l.to_string());
+        let mut query = Vec::new();
+        if let Some(filter) = filter {
+            query.push(("query", filter));
+        }
+        if let Some(cursor) = cursor {
+            query.push(("cursor", cursor));
+        }
+        if let Some(limit) = &limit {
+            query.push(("limit", limit));
+        }
+        let path = match filter {
+            Some(_) => "api/v1/tasks/filter",
+            None => "api/v1/tasks",
         };
-        Ok(response.results)
+        self.get(path, Some(&query))
+            .await
+            .wrap_err("unable to get tasks")
     }
 
     /// Returns a list of completed tasks by due date range (up to 6 weeks).
@@ -139,7 +374,7 @@ impl Gateway {
 
         let limit_str;
         if let Some(l) = params.limit {
-            limit_str = l.to_string();
+            limit_str = l.min(COMPLETED_TASKS_MAX_LIMIT).to_string();
             query_params.push(("limit", &limit_str));
         }
 
@@ -177,7 +412,7 @@ impl Gateway {
 
         let limit_str;
         if let Some(l) = params.limit {
-            limit_str = l.to_string();
+            limit_str = l.min(COMPLETED_TASKS_MAX_LIMIT).to_string();
             query_params.push(("limit", &limit_str));
         }
 
@@ -199,15 +434,47 @@ impl Gateway {
         Ok(())
     }
 
+    /// Reopens a closed task.
+    ///
+    /// Tasks closed with [`Gateway::complete`] had their due date replaced, so reopening them will
+    /// not restore the original recurring due date.
+    pub async fn reopen(&self, id: &TaskID) -> Result<()> {
+        self.post_empty(
+            &format!("api/v1/tasks/{id}/reopen"),
+            &serde_json::Map::new(),
+        )
+        .await
+        .wrap_err("unable to reopen task")?;
+        Ok(())
+    }
+
     /// Complete will complete a task by first updating the due date to today, so if it's
-    /// recurring, it will stop doing that.
-    /// This is a bit hacky, but the REST API does not support completely closing tasks without
-    /// deleting them.
+    /// recurring, it will stop doing that. Use [`Gateway::close`] to keep it recurring.
+    ///
+    /// Both steps are sent as a single [`Gateway::sync`] request, so the task can't be left
+    /// updated but open. Only if the Sync API is unavailable, it falls back to two separate REST
+    /// calls. Other errors, like a failed command, are returned as they are.
     pub async fn complete(&self, id: &TaskID) -> Result<()> {
+        let now = Utc::now();
+        let commands = [
+            SyncCommand::new(
+                "item_update",
+                serde_json::json!({
+                    "id": id,
+                    "due": {"date": now.format("%Y-%m-%dT%H:%M:%SZ").to_string()},
+                }),
+            ),
+            SyncCommand::new("item_close", serde_json::json!({ "id": id })),
+        ];
+        match self.sync(&commands).await {
+            Ok(()) => return Ok(()),
+            Err(e) if !sync_unavailable(&e) => return Err(e.wrap_err("unable to complete task")),
+            Err(_) => {}
+        }
         self.update(
             id,
             &UpdateTask {
-                due: Some(TaskDue::DateTime(Utc::now())),
+                due: Some(TaskDue::DateTime(now)),
                 ..Default::default()
             },
         )
@@ -217,6 +484,67 @@ impl Gateway {
         Ok(())
     }
 
+    /// Sends the given commands to the Sync API in a single request and makes sure all of them
+    /// succeeded.
+    pub async fn sync(&self, commands: &[SyncCommand]) -> Result<()> {
+        if self.dry_run("sync", &commands)? {
+            return Ok(());
+        }
+        let body = url::form_urlencoded::Serializer::new(String::new())
+            .append_pair("commands", &serde_json::to_string(commands)?)
+            .finish();
+        for command in commands {
+            // Sync command types are named after the resource they change, like `item_close`.
+            match command.kind.split_once('_').map(|(resource, _)| resource) {
+                Some("project") => self.invalidate("api/v1/projects"),
+                Some("section") => self.invalidate("api/v1/sections"),
+                Some("label") => self.invalidate("api/v1/labels"),
+                _ => {}
+            }
+        }
+        let response: SyncResponse = handle_req(
+            self.client
+                .post(self.url.join("api/v1/sync")?)
+                .bearer_auth(&self.token)
+                .body(body)
+                .header(
+                    reqwest::header::CONTENT_TYPE,
+                    "application/x-www-form-urlencoded",
+                ),
+            self.timeout,
+        )
+        .await
+        .wrap_err("unable to sync")?
+        .ok_or_else(|| eyre!("unable to sync: empty response"))?;
+        for command in commands {
+            match response.sync_status.get(&command.uuid) {
+                Some(serde_json::Value::String(status)) if status == "ok" => {}
+                Some(error) => {
+                    return Err(eyre!("sync command {} failed: {}", command.kind, error));
+                }
+                None => return Err(eyre!("sync command {} got no result", command.kind)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets the order of the given tasks among their siblings with a single `item_reorder`
+    /// command of the Sync API.
+    ///
+    /// * `items` - the IDs of the tasks with their new [`Task::order`].
+    pub async fn reorder_tasks(&self, items: &[(TaskID, isize)]) -> Result<()> {
+        let items = items
+            .iter()
+            .map(|(id, order)| serde_json::json!({"id": id, "child_order": order}))
+            .collect::<Vec<_>>();
+        self.sync(&[SyncCommand::new(
+            "item_reorder",
+            serde_json::json!({ "items": items }),
+        )])
+        .await
+        .wrap_err("unable to reorder tasks")
+    }
+
     /// Creates a task by calling the Todoist API.
     pub async fn create(&self, task: &CreateTask) -> Result<Task> {
         self.post("api/v1/tasks", task)
@@ -233,49 +561,82 @@ impl Gateway {
         Ok(())
     }
 
+    /// Moves a task into another project and/or section.
+    pub async fn move_task(
+        &self,
+        id: &TaskID,
+        project_id: Option<&ProjectID>,
+        section_id: Option<&SectionID>,
+    ) -> Result<()> {
+        let mut body = serde_json::Map::new();
+        if let Some(project_id) = project_id {
+            body.insert("project_id".to_string(), project_id.clone().into());
+        }
+        if let Some(section_id) = section_id {
+            body.insert("section_id".to_string(), section_id.clone().into());
+        }
+        if body.is_empty() {
+            return Err(eyre!("no project or section to move the task to"));
+        }
+        self.post_empty(&format!("api/v1/tasks/{id}/move"), &body)
+            .await
+            .wrap_err("unable to move task")?;
+        Ok(())
+    }
+
     /// Returns the list of Projects.
     pub async fn projects(&self) -> Result<Vec<Project>> {
-        let response: PaginatedResponse<Project> = self
-            .get::<(), _>("api/v1/projects", None)
+        self.projects
+            .get_or_fetch(self.disk_cache.as_ref(), async {
+                self.get_paginated("api/v1/projects", &[])
+                    .await
+                    .wrap_err("unable to get projects")
+            })
             .await
-            .wrap_err("unable to get projects")?;
-        Ok(response.results)
     }
 
     /// Returns the list of all Sections.
     pub async fn sections(&self) -> Result<Vec<Section>> {
-        let response: PaginatedResponse<Section> = self
-            .get::<(), _>("api/v1/sections", None)
+        self.sections
+            .get_or_fetch(self.disk_cache.as_ref(), async {
+                self.get_paginated("api/v1/sections", &[])
+                    .await
+                    .wrap_err("unable to get sections")
+            })
             .await
-            .wrap_err("unable to get sections")?;
-        Ok(response.results)
     }
 
     /// Returns the list of all Labels.
     pub async fn labels(&self) -> Result<Vec<Label>> {
-        let response: PaginatedResponse<Label> = self
-            .get::<(), _>("api/v1/labels", None)
+        self.labels
+            .get_or_fetch(self.disk_cache.as_ref(), async {
+                self.get_paginated("api/v1/labels", &[])
+                    .await
+                    .wrap_err("unable to get labels")
+            })
+            .await
+    }
+
+    /// Returns the names of the labels that are used on tasks of shared projects, which includes
+    /// labels of collaborators that aren't part of [`Gateway::labels`].
+    pub async fn shared_labels(&self) -> Result<Vec<String>> {
+        self.get_paginated("api/v1/labels/shared", &[])
             .await
-            .wrap_err("unable to get labels")?;
-        Ok(response.results)
+            .wrap_err("unable to get shared labels")
     }
 
     /// Returns the list of all comments attached to the given Project.
     pub async fn project_comments(&self, id: &ProjectID) -> Result<Vec<Comment>> {
-        let response: PaginatedResponse<Comment> = self
-            .get("api/v1/comments", Some(&[("project_id", id)]))
+        self.get_paginated("api/v1/comments", &[("project_id", id)])
             .await
-            .wrap_err("unable to get comments")?;
-        Ok(response.results)
+            .wrap_err("unable to get comments")
     }
 
     /// Returns the list of all comments attached to the given Task.
     pub async fn task_comments(&self, id: &TaskID) -> Result<Vec<Comment>> {
-        let response: PaginatedResponse<Comment> = self
-            .get("api/v1/comments", Some(&[("task_id", id)]))
+        self.get_paginated("api/v1/comments", &[("task_id", id)])
             .await
-            .wrap_err("unable to get comments")?;
-        Ok(response.results)
+            .wrap_err("unable to get comments")
     }
 
     /// Creates a comment by calling the API.
@@ -286,6 +647,20 @@ impl Gateway {
             .ok_or_else(|| eyre!("unable to create comment"))
     }
 
+    /// Returns the users that have access to the given shared project.
+    pub async fn collaborators(&self, id: &ProjectID) -> Result<Vec<Collaborator>> {
+        self.get_paginated(&format!("api/v1/projects/{id}/collaborators"), &[])
+            .await
+            .wrap_err("unable to get collaborators")
+    }
+
+    /// Returns the user that the API token belongs to.
+    pub async fn user(&self) -> Result<User> {
+        self.get::<(), _>("api/v1/user", None)
+            .await
+            .wrap_err("unable to get user")
+    }
+
     /// Returns details about a single project.
     ///
     /// * `id` - the ID as used by the Todoist API.
@@ -303,6 +678,43 @@ impl Gateway {
             .ok_or_else(|| eyre!("unable to create project"))
     }
 
+    /// Updates a project with the data as specified in UpdateProject.
+    pub async fn update_project(&self, id: &ProjectID, update: &UpdateProject) -> Result<Project> {
+        self.post(&format!("api/v1/projects/{id}"), update)
+            .await
+            .wrap_err("unable to update project")?
+            .ok_or_else(|| eyre!("unable to update project"))
+    }
+
+    /// Returns the list of archived Projects, which aren't part of [`Gateway::projects`].
+    pub async fn archived_projects(&self) -> Result<Vec<Project>> {
+        self.get_paginated("api/v1/projects/archived", &[])
+            .await
+            .wrap_err("unable to get archived projects")
+    }
+
+    /// Archives a project, hiding it and its tasks from the project list.
+    pub async fn archive_project(&self, id: &ProjectID) -> Result<Project> {
+        self.post(
+            &format!("api/v1/projects/{id}/archive"),
+            &serde_json::Map::new(),
+        )
+        .await
+        .wrap_err("unable to archive project")?
+        .ok_or_else(|| eyre!("unable to archive project"))
+    }
+
+    /// Restores an archived project.
+    pub async fn unarchive_project(&self, id: &ProjectID) -> Result<Project> {
+        self.post(
+            &format!("api/v1/projects/{id}/unarchive"),
+            &serde_json::Map::new(),
+        )
+        .await
+        .wrap_err("unable to unarchive project")?
+        .ok_or_else(|| eyre!("unable to unarchive project"))
+    }
+
     /// Deletes a project by calling the Todoist API.
     pub async fn delete_project(&self, project: &ProjectID) -> Result<()> {
         self.delete(&format!("api/v1/projects/{project}"))
@@ -327,6 +739,14 @@ impl Gateway {
             .ok_or_else(|| eyre!("unable to create section"))
     }
 
+    /// Updates a section with the given fields and returns the updated section.
+    pub async fn update_section(&self, id: &SectionID, update: &UpdateSection) -> Result<Section> {
+        self.post(&format!("api/v1/sections/{id}"), update)
+            .await
+            .wrap_err("unable to update section")?
+            .ok_or_else(|| eyre!("unable to update section"))
+    }
+
     /// Deletes a section by calling the Todoist API.
     pub async fn delete_section(&self, section: &SectionID) -> Result<()> {
         self.delete(&format!("api/v1/sections/{section}"))
@@ -351,6 +771,34 @@ impl Gateway {
             .ok_or_else(|| eyre!("unable to create label"))
     }
 
+    /// Updates a personal label with the given fields and returns the updated label.
+    ///
+    /// Tasks refer to labels by name, so renaming a label leaves tasks with the old name alone.
+    /// Use [`Gateway::rename_shared_label`] to rename it on all tasks as well.
+    pub async fn update_label(&self, id: &LabelID, update: &UpdateLabel) -> Result<Label> {
+        if update.name.as_deref().is_some_and(|n| n.trim().is_empty()) {
+            return Err(eyre!("unable to update label: the name can't be empty"));
+        }
+        self.post(&format!("api/v1/labels/{id}"), update)
+            .await
+            .wrap_err("unable to update label")?
+            .ok_or_else(|| eyre!("unable to update label"))
+    }
+
+    /// Renames a label on all tasks that use it, including labels shared by collaborators.
+    pub async fn rename_shared_label(&self, name: &str, new_name: &str) -> Result<()> {
+        if new_name.trim().is_empty() {
+            return Err(eyre!("unable to rename label: the name can't be empty"));
+        }
+        self.post_empty(
+            "api/v1/labels/shared/rename",
+            &serde_json::json!({"name": name, "new_name": new_name}),
+        )
+        .await
+        .wrap_err("unable to rename label")?;
+        Ok(())
+    }
+
     /// Deletes a label by calling the Todoist API.
     pub async fn delete_label(&self, label: &LabelID) -> Result<()> {
         self.delete(&format!("api/v1/labels/{label}"))
@@ -373,17 +821,47 @@ impl Gateway {
         } else {
             req
         };
-        handle_req(req)
+        handle_req(req, self.timeout)
             .await?
             .ok_or_else(|| eyre!("Invalid response from API"))
     }
 
+    /// Fetches all pages of a paginated endpoint, following the `next_cursor` of each page until
+    /// there are no more results.
+    async fn get_paginated<R: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> Result<Vec<R>> {
+        let mut results = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut page_query = query.to_vec();
+            if let Some(cursor) = &cursor {
+                page_query.push(("cursor", cursor));
+            }
+            let page: PaginatedResponse<R> = self.get(path, Some(&page_query)).await?;
+            results.extend(page.results);
+            cursor = page.next_cursor;
+            if cursor.is_none() {
+                return Ok(results);
+            }
+        }
+    }
+
     /// Sends a POST request to the Todoist API with the given content.
+    ///
+    /// In dry-run mode, the request is only printed. As there is no response to return, this
+    /// fails with [`DryRunStopped`].
     async fn post<T: Serialize, R: DeserializeOwned>(
         &self,
         path: &str,
         content: &T,
     ) -> Result<Option<R>> {
+        if self.dry_run(&format!("POST {path}"), content)? {
+            return Err(DryRunStopped.into());
+        }
+        self.invalidate(path);
         let uuid = Uuid::new_v4();
         handle_req(
             self.client
@@ -392,43 +870,113 @@ impl Gateway {
                 .body(serde_json::to_string(&content)?)
                 .header(reqwest::header::CONTENT_TYPE, "application/json")
                 .header("X-Request-Id", uuid.to_string()),
+            self.timeout,
         )
         .await
     }
 
-    /// Sends a DELETE request to the Todoist API.
+    /// Sends a DELETE request to the Todoist API. In dry-run mode, it's only printed.
     async fn delete(&self, path: &str) -> Result<()> {
+        if self.dry_run(&format!("DELETE {path}"), &())? {
+            return Ok(());
+        }
+        self.invalidate(path);
         handle_req::<()>(
             self.client
                 .delete(self.url.join(path)?)
                 .bearer_auth(&self.token),
+            self.timeout,
         )
         .await?;
         Ok(())
     }
 
     /// Same as [`Gateway::post`], but doesn't require content to be set for the POST request.
+    /// In dry-run mode, the request is only printed and the caller goes on.
     async fn post_empty<T: Serialize>(&self, path: &str, content: &T) -> Result<()> {
+        if self.dry_run(&format!("POST {path}"), content)? {
+            return Ok(());
+        }
         self.post::<_, Task>(path, content).await?;
         Ok(())
     }
 }
 
-/// Does the actual call to the Todoist API and handles error handling.
-async fn handle_req<R: DeserializeOwned>(req: RequestBuilder) -> Result<Option<R>> {
-    // TODO: implement retries/backoffs
-    let resp = req
-        .timeout(Duration::from_secs(30))
-        .send()
-        .await
-        .wrap_err("unable to send request")?;
+/// Checks if a [`Gateway::sync`] failed because the endpoint itself can't be used right now, as
+/// opposed to the API rejecting the request or one of its commands.
+fn sync_unavailable(err: &color_eyre::Report) -> bool {
+    err.chain()
+        .filter_map(|e| e.downcast_ref::<BadResponse>())
+        .any(|e| e.status.is_server_error() || e.status == StatusCode::GONE)
+}
+
+/// Retries transient errors like the default strategy, but leaves rate limits to [`handle_req`],
+/// which knows how long the API wants us to wait.
+struct TransientExceptRateLimit;
+
+impl RetryableStrategy for TransientExceptRateLimit {
+    fn handle(
+        &self,
+        res: &std::result::Result<reqwest::Response, reqwest_middleware::Error>,
+    ) -> Option<Retryable> {
+        match res {
+            Ok(resp) if resp.status() == StatusCode::TOO_MANY_REQUESTS => None,
+            Ok(resp) => default_on_request_success(resp),
+            Err(err) => default_on_request_failure(err),
+        }
+    }
+}
+
+/// Returns how long to wait before retrying a rate limited request. Uses the `Retry-After` header
+/// if it's given in seconds, and otherwise backs off exponentially with the attempt.
+fn retry_after(resp: &reqwest::Response, attempt: u32) -> Duration {
+    resp.headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or_else(|| Duration::from_secs(2u64.pow(attempt)))
+        .min(RATE_LIMIT_MAX_WAIT)
+}
+
+/// Does the actual call to the Todoist API and handles error handling. Gives up on requests that
+/// take longer than `timeout`.
+///
+/// Rate limited requests are retried up to [`RATE_LIMIT_RETRIES`] times, other transient errors
+/// are retried by the middleware of the client.
+async fn handle_req<R: DeserializeOwned>(
+    req: RequestBuilder,
+    timeout: Duration,
+) -> Result<Option<R>> {
+    if let Some(request) = req.try_clone().and_then(|r| r.build().ok()) {
+        verbose!("{} {}", request.method(), request.url());
+    }
+    let mut attempt = 0;
+    let resp = loop {
+        let request = req
+            .try_clone()
+            .ok_or_else(|| eyre!("unable to send request, the request can't be retried"))?;
+        let resp = request
+            .timeout(timeout)
+            .send()
+            .await
+            .wrap_err("unable to send request")?;
+        if resp.status() != StatusCode::TOO_MANY_REQUESTS || attempt == RATE_LIMIT_RETRIES {
+            break resp;
+        }
+        attempt += 1;
+        tokio::time::sleep(retry_after(&resp, attempt)).await;
+    };
     let status = resp.status();
     if status == StatusCode::NO_CONTENT {
         return Ok(None);
     }
+    if status == StatusCode::UNAUTHORIZED {
+        return Err(TokenRejected(status).into());
+    }
     let text = resp.text().await.wrap_err("unable to read response")?;
     if !status.is_success() {
-        return Err(eyre!("Bad response from API: {} - {}", status, text));
+        return Err(BadResponse { status, text }.into());
     }
     let result = serde_json::from_str(&text).wrap_err("unable to parse API response")?;
     Ok(Some(result))
@@ -438,11 +986,11 @@ async fn handle_req<R: DeserializeOwned>(req: RequestBuilder) -> Result<Option<R
 mod test {
     use wiremock::{
         Mock, MockServer, ResponseTemplate,
-        matchers::{bearer_token, method, path, query_param},
+        matchers::{bearer_token, body_json, method, path, query_param, query_param_is_missing},
     };
 
     use super::*;
-    use crate::api::rest::{Task, ThreadID};
+    use crate::api::rest::{FieldUpdate, Task, ThreadID};
     use color_eyre::Result;
 
     #[tokio::test]
@@ -461,6 +1009,81 @@ mod test {
         assert!(task.is_ok());
     }
 
+    #[tokio::test]
+    async fn caches_projects() -> Result<()> {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/projects"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "results": [{
+                    "id": "1",
+                    "name": "Inbox",
+                    "color": "grey",
+                    "is_shared": false,
+                    "child_order": 0,
+                    "is_favorite": false,
+                    "view_style": "list"
+                }],
+                "next_cursor": null
+            })))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("hellothere", &mock_server);
+        let first = gw.projects().await?;
+        let second = gw.projects().await?;
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].id, second[0].id);
+        // Only a refresh fetches them again.
+        gw.refresh();
+        gw.projects().await?;
+        mock_server.verify().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn disk_cache() -> Result<()> {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/labels"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "results": [{
+                    "id": "1",
+                    "name": "one",
+                    "color": "grey",
+                    "order": 1,
+                    "is_favorite": false
+                }],
+                "next_cursor": null
+            })))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/api/v1/labels/1"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let dir = assert_fs::TempDir::new()?;
+        let cache = DiskCache::new(dir.path(), "hellothere", DiskCache::DEFAULT_TTL);
+        gateway("hellothere", &mock_server)
+            .with_disk_cache(cache.clone())
+            .labels()
+            .await?;
+        // A new gateway, like the next run of the binary, reuses the labels from disk.
+        let gw = gateway("hellothere", &mock_server).with_disk_cache(cache.clone());
+        assert_eq!(gw.labels().await?[0].name, "one");
+        // Deleting a label drops them from disk, so the next run fetches them again.
+        gw.delete_label(&"1".to_string()).await?;
+        gateway("hellothere", &mock_server)
+            .with_disk_cache(cache)
+            .labels()
+            .await?;
+        mock_server.verify().await;
+        Ok(())
+    }
+
     #[tokio::test]
     async fn task() {
         let mock_server = MockServer::start().await;
@@ -499,6 +1122,26 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn project_tasks() -> Result<()> {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/tasks"))
+            .and(query_param("project_id", "456"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(PaginatedResponse {
+                results: vec![create_task("123", "456", "hello there")],
+                next_cursor: None,
+            }))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let tasks = gw.project_tasks(&"456".to_string()).await?;
+        mock_server.verify().await;
+        assert_eq!(tasks.len(), 1);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn close_task() {
         let mock_server = MockServer::start().await;
@@ -513,99 +1156,341 @@ mod test {
     }
 
     #[tokio::test]
-    async fn complete_task() {
+    async fn reopen_task() {
         let mock_server = MockServer::start().await;
         Mock::given(method("POST"))
-            .and(path("/api/v1/tasks/123"))
-            .respond_with(ResponseTemplate::new(204))
-            .mount(&mock_server)
-            .await;
-        Mock::given(method("POST"))
-            .and(path("/api/v1/tasks/123/close"))
+            .and(path("/api/v1/tasks/123/reopen"))
             .respond_with(ResponseTemplate::new(204))
+            .expect(1)
             .mount(&mock_server)
             .await;
         let gw = gateway("", &mock_server);
-        let completed = gw.complete(&"123".to_string()).await;
+        let reopened = gw.reopen(&"123".to_string()).await;
         mock_server.verify().await;
-        assert!(completed.is_ok());
+        assert!(reopened.is_ok());
     }
 
     #[tokio::test]
-    async fn update_task() {
+    async fn complete_task_with_sync() {
         let mock_server = MockServer::start().await;
         Mock::given(method("POST"))
-            .and(path("/api/v1/tasks/123"))
-            .respond_with(ResponseTemplate::new(204))
+            .and(path("/api/v1/sync"))
+            .respond_with(|req: &wiremock::Request| {
+                let commands = url::form_urlencoded::parse(&req.body)
+                    .find(|(key, _)| key == "commands")
+                    .map(|(_, value)| serde_json::from_str::<Vec<SyncCommand>>(&value).unwrap())
+                    .unwrap();
+                assert_eq!(
+                    commands.iter().map(|c| c.kind.as_str()).collect::<Vec<_>>(),
+                    vec!["item_update", "item_close"]
+                );
+                assert!(commands.iter().all(|c| c.args["id"] == "123"));
+                let status = commands
+                    .iter()
+                    .map(|c| (c.uuid.clone(), serde_json::json!("ok")))
+                    .collect::<serde_json::Map<_, _>>();
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "sync_status": status
+                }))
+            })
+            .expect(1)
             .mount(&mock_server)
             .await;
         let gw = gateway("", &mock_server);
-        let completed = gw
-            .update(
-                &"123".to_string(),
-                &UpdateTask {
-                    content: Some("hello".to_string()),
-                    ..Default::default()
-                },
-            )
-            .await;
+        let completed = gw.complete(&"123".to_string()).await;
         mock_server.verify().await;
         assert!(completed.is_ok());
     }
 
     #[tokio::test]
-    async fn creates_task() {
+    async fn sync_reports_failed_command() {
         let mock_server = MockServer::start().await;
+        let command = SyncCommand::new("item_close", serde_json::json!({"id": "123"}));
         Mock::given(method("POST"))
-            .and(path("/api/v1/tasks"))
-            .respond_with(
-                ResponseTemplate::new(200).set_body_json(create_task("123", "456", "hello")),
-            )
+            .and(path("/api/v1/sync"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "sync_status": {command.uuid.clone(): {"error": "Item not found"}}
+            })))
             .mount(&mock_server)
             .await;
         let gw = gateway("", &mock_server);
-        let task = gw
-            .create(&CreateTask {
-                content: "hello".to_string(),
-                project_id: Some("456".to_string()),
-                ..Default::default()
-            })
-            .await
-            .unwrap();
-        mock_server.verify().await;
-        assert_eq!(task.id, "123");
+        assert!(gw.sync(&[command]).await.is_err());
     }
 
     #[tokio::test]
-    async fn lists_projects() {
+    async fn sync_keeps_unaffected_cache() -> Result<()> {
         let mock_server = MockServer::start().await;
         Mock::given(method("GET"))
-            .and(path("/api/v1/projects"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(PaginatedResponse {
-                results: vec![Project::new("123", "one"), Project::new("456", "two")],
-                next_cursor: None,
-            }))
+            .and(path("/api/v1/labels"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "results": [{"id": "1", "name": "errand", "color": "grey", "order": 1, "is_favorite": false}],
+                "next_cursor": null
+            })))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/sync"))
+            .respond_with(|req: &wiremock::Request| {
+                let commands = url::form_urlencoded::parse(&req.body)
+                    .find(|(key, _)| key == "commands")
+                    .map(|(_, value)| serde_json::from_str::<Vec<SyncCommand>>(&value).unwrap())
+                    .unwrap();
+                let status: serde_json::Map<_, _> = commands
+                    .into_iter()
+                    .map(|c| (c.uuid, serde_json::json!("ok")))
+                    .collect();
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "sync_status": status }))
+            })
             .mount(&mock_server)
             .await;
         let gw = gateway("", &mock_server);
-        let projects = gw.projects().await.unwrap();
+        gw.labels().await?;
+        // Closing a task leaves the labels cached.
+        gw.sync(&[SyncCommand::new(
+            "item_close",
+            serde_json::json!({"id": "123"}),
+        )])
+        .await?;
+        gw.labels().await?;
+        // Changing a label fetches them again.
+        gw.sync(&[SyncCommand::new(
+            "label_update",
+            serde_json::json!({"id": "1", "name": "chore"}),
+        )])
+        .await?;
+        gw.labels().await?;
         mock_server.verify().await;
-        assert_eq!(projects.len(), 2);
+        Ok(())
     }
 
     #[tokio::test]
-    async fn show_project() {
+    async fn reorder_tasks() {
         let mock_server = MockServer::start().await;
-        Mock::given(method("GET"))
-            .and(path("/api/v1/projects/123"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(Project::new("123", "one")))
+        Mock::given(method("POST"))
+            .and(path("/api/v1/sync"))
+            .respond_with(|req: &wiremock::Request| {
+                let commands = url::form_urlencoded::parse(&req.body)
+                    .find(|(key, _)| key == "commands")
+                    .map(|(_, value)| serde_json::from_str::<Vec<SyncCommand>>(&value).unwrap())
+                    .unwrap();
+                assert_eq!(commands.len(), 1);
+                assert_eq!(commands[0].kind, "item_reorder");
+                assert_eq!(
+                    commands[0].args,
+                    serde_json::json!({"items": [
+                        {"id": "2", "child_order": 1},
+                        {"id": "1", "child_order": 2},
+                    ]})
+                );
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "sync_status": {commands[0].uuid.clone(): "ok"}
+                }))
+            })
+            .expect(1)
             .mount(&mock_server)
             .await;
         let gw = gateway("", &mock_server);
-        let project = gw.project(&"123".to_string()).await.unwrap();
+        gw.reorder_tasks(&[("2".to_string(), 1), ("1".to_string(), 2)])
+            .await
+            .unwrap();
         mock_server.verify().await;
-        assert_eq!(project.id, "123");
-        assert_eq!(project.name, "one");
+    }
+
+    /// Without the Sync API, completing falls back to the REST endpoints.
+    #[tokio::test]
+    async fn complete_task() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/sync"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/tasks/123"))
+            .respond_with(ResponseTemplate::new(204))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/tasks/123/close"))
+            .respond_with(ResponseTemplate::new(204))
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let completed = gw.complete(&"123".to_string()).await;
+        mock_server.verify().await;
+        assert!(completed.is_ok());
+    }
+
+    /// Errors that aren't about the Sync API being unavailable are returned without trying again
+    /// over REST, as the first commands might have been applied already.
+    #[tokio::test]
+    async fn complete_task_reports_sync_errors() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/sync"))
+            .respond_with(|req: &wiremock::Request| {
+                let commands = url::form_urlencoded::parse(&req.body)
+                    .find(|(key, _)| key == "commands")
+                    .map(|(_, value)| serde_json::from_str::<Vec<SyncCommand>>(&value).unwrap())
+                    .unwrap();
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "sync_status": {
+                        commands[0].uuid.clone(): "ok",
+                        commands[1].uuid.clone(): {"error": "Item is already completed"},
+                    }
+                }))
+            })
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/tasks/123"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let err = gw.complete(&"123".to_string()).await.unwrap_err();
+        assert!(format!("{err:?}").contains("Item is already completed"));
+
+        mock_server.reset().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/sync"))
+            .respond_with(ResponseTemplate::new(403))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let err = gw.complete(&"123".to_string()).await.unwrap_err();
+        assert!(format!("{err:?}").contains("403"));
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn update_task() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/tasks/123"))
+            .respond_with(ResponseTemplate::new(204))
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let completed = gw
+            .update(
+                &"123".to_string(),
+                &UpdateTask {
+                    content: Some("hello".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await;
+        mock_server.verify().await;
+        assert!(completed.is_ok());
+    }
+
+    #[tokio::test]
+    async fn unassign_task() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/tasks/123"))
+            .and(body_json(serde_json::json!({ "assignee_id": null })))
+            .respond_with(ResponseTemplate::new(204))
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let updated = gw
+            .update(
+                &"123".to_string(),
+                &UpdateTask {
+                    assignee: FieldUpdate::Clear,
+                    ..Default::default()
+                },
+            )
+            .await;
+        mock_server.verify().await;
+        assert!(updated.is_ok());
+    }
+
+    #[tokio::test]
+    async fn move_task() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/tasks/123/move"))
+            .and(body_json(serde_json::json!({
+                "project_id": "456",
+                "section_id": "789"
+            })))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let moved = gw
+            .move_task(
+                &"123".to_string(),
+                Some(&"456".to_string()),
+                Some(&"789".to_string()),
+            )
+            .await;
+        mock_server.verify().await;
+        assert!(moved.is_ok());
+        assert!(gw.move_task(&"123".to_string(), None, None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn creates_task() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/tasks"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(create_task("123", "456", "hello")),
+            )
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let task = gw
+            .create(&CreateTask {
+                content: "hello".to_string(),
+                project_id: Some("456".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        mock_server.verify().await;
+        assert_eq!(task.id, "123");
+    }
+
+    #[tokio::test]
+    async fn lists_projects() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/projects"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(PaginatedResponse {
+                results: vec![Project::new("123", "one"), Project::new("456", "two")],
+                next_cursor: None,
+            }))
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let projects = gw.projects().await.unwrap();
+        mock_server.verify().await;
+        assert_eq!(projects.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn show_project() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/projects/123"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(Project::new("123", "one")))
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let project = gw.project(&"123".to_string()).await.unwrap();
+        mock_server.verify().await;
+        assert_eq!(project.id, "123");
+        assert_eq!(project.name, "one");
     }
 
     #[tokio::test]
@@ -827,6 +1712,71 @@ mod test {
         assert!(closed.is_ok());
     }
 
+    #[tokio::test]
+    async fn updates_label() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/labels/123"))
+            .and(body_json(serde_json::json!({
+                "name": "renamed",
+                "color": "blue"
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(Label::new("123", "renamed")))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let label = gw
+            .update_label(
+                &"123".to_string(),
+                &UpdateLabel {
+                    name: Some("renamed".to_string()),
+                    color: Some("blue".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        mock_server.verify().await;
+        assert_eq!(label.name, "renamed");
+    }
+
+    #[tokio::test]
+    async fn update_label_rejects_empty_name() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let update = UpdateLabel {
+            name: Some(" ".to_string()),
+            ..Default::default()
+        };
+        assert!(gw.update_label(&"123".to_string(), &update).await.is_err());
+        assert!(gw.rename_shared_label("one", "").await.is_err());
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn renames_shared_label() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/labels/shared/rename"))
+            .and(body_json(serde_json::json!({
+                "name": "one",
+                "new_name": "two"
+            })))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        gw.rename_shared_label("one", "two").await.unwrap();
+        mock_server.verify().await;
+    }
+
     #[tokio::test]
     async fn creates_project() {
         let mock_server = MockServer::start().await;
@@ -847,6 +1797,125 @@ mod test {
         assert_eq!(project.id, "123");
     }
 
+    #[tokio::test]
+    async fn updates_project() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/projects/123"))
+            .and(body_json(serde_json::json!({
+                "name": "renamed",
+                "is_favorite": true
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(Project::new("123", "renamed")))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let project = gw
+            .update_project(
+                &"123".to_string(),
+                &UpdateProject {
+                    name: Some("renamed".to_string()),
+                    is_favorite: Some(true),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        mock_server.verify().await;
+        assert_eq!(project.name, "renamed");
+    }
+
+    #[tokio::test]
+    async fn updates_section() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/sections/456"))
+            .and(body_json(serde_json::json!({"name": "renamed"})))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(Section::new("456", "123", "renamed")),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let section = gw
+            .update_section(
+                &"456".to_string(),
+                &UpdateSection {
+                    name: Some("renamed".to_string()),
+                },
+            )
+            .await
+            .unwrap();
+        mock_server.verify().await;
+        assert_eq!(section.name, "renamed");
+    }
+
+    #[tokio::test]
+    async fn collaborators() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/projects/123/collaborators"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(PaginatedResponse {
+                results: vec![
+                    Collaborator::new("1", "Alice"),
+                    Collaborator::new("2", "Bob"),
+                ],
+                next_cursor: None,
+            }))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let collaborators = gw.collaborators(&"123".to_string()).await.unwrap();
+        mock_server.verify().await;
+        assert_eq!(
+            collaborators,
+            vec![
+                Collaborator::new("1", "Alice"),
+                Collaborator::new("2", "Bob")
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn user() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/user"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "42",
+                "full_name": "Alice Doe",
+                "email": "alice@example.com",
+                "tz_info": {"timezone": "Asia/Seoul", "gmt_string": "+09:00"}
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let user = gw.user().await.unwrap();
+        mock_server.verify().await;
+        assert_eq!(user.id, "42");
+        assert_eq!(user.full_name, "Alice Doe");
+        assert_eq!(user.timezone(), Some("Asia/Seoul"));
+    }
+
+    #[tokio::test]
+    async fn user_unauthorized() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/user"))
+            .respond_with(ResponseTemplate::new(401).set_body_string("Forbidden"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let err = gw.user().await.unwrap_err();
+        mock_server.verify().await;
+        assert!(format!("{err:#}").contains("the API token was rejected"));
+    }
+
     #[tokio::test]
     async fn delete_project() {
         let mock_server = MockServer::start().await;
@@ -860,10 +1929,189 @@ mod test {
         assert!(closed.is_ok());
     }
 
+    #[tokio::test]
+    async fn follows_pages() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/projects"))
+            .and(query_param_is_missing("cursor"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(PaginatedResponse {
+                results: vec![Project::new("123", "one"), Project::new("456", "two")],
+                next_cursor: Some("page2".to_string()),
+            }))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/projects"))
+            .and(query_param("cursor", "page2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(PaginatedResponse {
+                results: vec![Project::new("789", "three")],
+                next_cursor: None,
+            }))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let projects = gw.projects().await.unwrap();
+        mock_server.verify().await;
+        assert_eq!(
+            projects.iter().map(|p| p.id.as_str()).collect::<Vec<_>>(),
+            ["123", "456", "789"]
+        );
+    }
+
+    #[tokio::test]
+    async fn tasks_paged() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/tasks/filter"))
+            .and(query_param("query", "today"))
+            .and(query_param("cursor", "page2"))
+            .and(query_param("limit", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(PaginatedResponse {
+                results: vec![create_task("123", "456", "hello there")],
+                next_cursor: Some("page3".to_string()),
+            }))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let page = gw
+            .tasks_paged(Some("today"), Some("page2"), Some(1))
+            .await
+            .unwrap();
+        mock_server.verify().await;
+        assert_eq!(page.results.len(), 1);
+        assert_eq!(page.next_cursor.as_deref(), Some("page3"));
+    }
+
+    #[tokio::test]
+    async fn archives_project() {
+        let mock_server = MockServer::start().await;
+        let mut archived = Project::new("123", "one");
+        archived.is_archived = true;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/projects/123/archive"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&archived))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/projects/123/unarchive"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(Project::new("123", "one")))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/projects/archived"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(PaginatedResponse {
+                results: vec![archived],
+                next_cursor: None,
+            }))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let id = "123".to_string();
+        assert!(gw.archive_project(&id).await.unwrap().is_archived);
+        assert!(!gw.unarchive_project(&id).await.unwrap().is_archived);
+        assert_eq!(gw.archived_projects().await.unwrap()[0].id, "123");
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn retries_rate_limits() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/tasks/123"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/tasks/123"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(create_task("123", "456", "hello")),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let task = gw.task(&"123".to_string()).await.unwrap();
+        mock_server.verify().await;
+        assert_eq!(task.id, "123");
+    }
+
+    #[tokio::test]
+    async fn gives_up_on_rate_limits() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/tasks/123"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+            .expect(u64::from(RATE_LIMIT_RETRIES) + 1)
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let err = gw.task(&"123".to_string()).await.unwrap_err();
+        mock_server.verify().await;
+        assert!(format!("{err:?}").contains("429"));
+    }
+
+    #[tokio::test]
+    async fn times_out() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/tasks/123"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(create_task("123", "456", "hello"))
+                    .set_delay(Duration::from_secs(5)),
+            )
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+        let gw = Gateway::with_options(
+            "",
+            &mock_server.uri().parse().unwrap(),
+            GatewayOptions {
+                timeout: Duration::from_millis(50),
+                max_retries: 1,
+                dry_run: false,
+            },
+        );
+        let err = gw.task(&"123".to_string()).await.unwrap_err();
+        mock_server.verify().await;
+        assert!(format!("{err:?}").contains("timed out"));
+    }
+
     fn gateway(token: &str, ms: &MockServer) -> Gateway {
         Gateway::new(token, &ms.uri().parse().unwrap())
     }
 
+    #[tokio::test]
+    async fn dry_run_sends_no_changes() {
+        let mock_server = MockServer::start().await;
+        let gw = Gateway::with_options(
+            "",
+            &mock_server.uri().parse().unwrap(),
+            GatewayOptions {
+                dry_run: true,
+                ..Default::default()
+            },
+        );
+        let id = "123".to_string();
+        assert!(gw.update(&id, &UpdateTask::default()).await.is_ok());
+        assert!(gw.close(&id).await.is_ok());
+        assert!(gw.delete_label(&id).await.is_ok());
+        assert!(gw.reorder_tasks(&[(id.clone(), 1)]).await.is_ok());
+        let err = gw.create(&CreateTask::default()).await.unwrap_err();
+        assert!(err.chain().any(|e| e.is::<DryRunStopped>()));
+        let requests = mock_server.received_requests().await.unwrap_or_default();
+        assert!(requests.is_empty(), "unexpected requests: {requests:?}");
+    }
+
     #[tokio::test]
     async fn creates_section() {
         let mock_server = MockServer::start().await;
@@ -961,6 +2209,40 @@ mod test {
         assert_eq!(response.next_cursor, Some("cursor123".to_string()));
     }
 
+    #[tokio::test]
+    async fn completed_tasks_limit_is_clamped() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/tasks/completed/by_completion_date"))
+            .and(query_param("limit", "200"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(CompletedTasksResponse {
+                    items: vec![],
+                    next_cursor: None,
+                }),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let gw = gateway("", &mock_server);
+        gw.completed_tasks_by_completion_date(CompletedTasksByCompletionDateParams {
+            since: "2025-10-01",
+            until: "2025-10-06",
+            workspace_id: None,
+            project_id: None,
+            section_id: None,
+            parent_id: None,
+            filter_query: None,
+            cursor: None,
+            limit: Some(500),
+        })
+        .await
+        .unwrap();
+
+        mock_server.verify().await;
+    }
+
     #[tokio::test]
     async fn completed_tasks_by_completion_date() {
         let mock_server = MockServer::start().await;
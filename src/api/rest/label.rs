@@ -63,6 +63,28 @@ pub struct CreateLabel {
     pub is_favorite: Option<bool>,
 }
 
+/// Command used with [`super::Gateway::update_label`] to update a [`Label`]. Fields that are not
+/// set are left untouched.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct UpdateLabel {
+    /// New name of the label.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// New color of the label icon.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Mark as favorite or not.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_favorite: Option<bool>,
+}
+
+impl UpdateLabel {
+    /// Returns true if no field would be updated.
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.color.is_none() && self.is_favorite.is_none()
+    }
+}
+
 #[cfg(test)]
 impl Label {
     /// This is initializer is used for tests, as in general the tool relies on the API and not
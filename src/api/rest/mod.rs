@@ -6,23 +6,29 @@
 //! serialization/deserialization..
 //!
 //! To get started, take a look at [`Gateway`].
+mod cache;
 mod comment;
 mod display;
 mod gateway;
 mod label;
 mod project;
 mod section;
+mod sync;
 mod task;
+mod user;
 
 use serde::{Deserialize, Serialize};
 
+pub use cache::*;
 pub use comment::*;
 pub use display::*;
 pub use gateway::*;
 pub use label::*;
 pub use project::*;
 pub use section::*;
+pub use sync::*;
 pub use task::*;
+pub use user::*;
 
 /// Paginated response wrapper for API v1 endpoints.
 #[derive(Debug, Serialize, Deserialize)]
@@ -84,13 +84,28 @@ fn default_project_url() -> Url {
     "http://localhost".parse().unwrap()
 }
 
+impl Project {
+    /// Returns the link to this Project in the Todoist web app.
+    ///
+    /// API v1 does not always return [`Project::url`], so it is built from the ID if missing.
+    pub fn web_url(&self) -> Url {
+        if self.url != default_project_url() {
+            return self.url.clone();
+        }
+        format!("https://app.todoist.com/app/project/{}", self.id)
+            .parse()
+            .unwrap_or_else(|_| default_project_url())
+    }
+}
+
 /// ViewStyle for viewing of the project in different clients.
 ///
 /// Taken from the [Developer Documentation](https://developer.todoist.com/api/v1/#tag/Projects).
-#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Clone)]
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Clone, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum ViewStyle {
     /// Project as list view (default).
+    #[default]
     List,
     /// Project as board view.
     Board,
@@ -98,12 +113,6 @@ pub enum ViewStyle {
     Calendar,
 }
 
-impl Default for ViewStyle {
-    fn default() -> Self {
-        Self::List
-    }
-}
-
 impl Treeable for Project {
     type ID = ProjectID;
 
@@ -151,6 +160,34 @@ pub struct CreateProject {
     pub view_style: Option<ViewStyle>,
 }
 
+/// Command used with [`super::Gateway::update_project`] to update a [`Project`]. Fields that are
+/// not set are left untouched.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct UpdateProject {
+    /// New name of the project.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// New color of the project icon.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Mark as favorite or not.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_favorite: Option<bool>,
+    /// Sets the view style of the project.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub view_style: Option<ViewStyle>,
+}
+
+impl UpdateProject {
+    /// Returns true if no field would be updated.
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.color.is_none()
+            && self.is_favorite.is_none()
+            && self.view_style.is_none()
+    }
+}
+
 #[cfg(test)]
 impl Project {
     /// This is initializer is used for tests, as in general the tool relies on the API and not
@@ -83,6 +83,15 @@ pub struct CreateSection {
     pub order: Option<isize>,
 }
 
+/// Command used with [`super::Gateway::update_section`] to update a [`Section`]. Fields that are
+/// not set are left untouched.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct UpdateSection {
+    /// New name of the section.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
 #[cfg(test)]
 impl Section {
     /// This is initializer is used for tests, as in general the tool relies on the API and not
@@ -0,0 +1,38 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// SyncCommand is a single write operation sent to the Sync API with [`super::Gateway::sync`].
+///
+/// All commands that are sent together are applied in a single request, in order.
+/// Taken from the [Developer Documentation](https://developer.todoist.com/api/v1/#tag/Sync).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SyncCommand {
+    /// The type of the command, like `item_update` or `item_close`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Unique ID of the command, used to look up its result in [`SyncResponse::sync_status`].
+    pub uuid: String,
+    /// The arguments of the command, which depend on its type.
+    pub args: serde_json::Value,
+}
+
+impl SyncCommand {
+    /// Creates a new command with a random UUID.
+    pub fn new(kind: &str, args: serde_json::Value) -> SyncCommand {
+        SyncCommand {
+            kind: kind.to_string(),
+            uuid: Uuid::new_v4().to_string(),
+            args,
+        }
+    }
+}
+
+/// SyncResponse is the answer of the Sync API to a list of [`SyncCommand`]s.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncResponse {
+    /// Result of each command by its UUID. Either the string "ok" or an error object.
+    #[serde(default)]
+    pub sync_status: HashMap<String, serde_json::Value>,
+}
@@ -3,13 +3,14 @@ use std::fmt::Display;
 
 use crate::api::serialize::todoist_rfc3339;
 use crate::api::tree::Treeable;
-use chrono::{DateTime, FixedOffset, Utc};
+use chrono::{DateTime, FixedOffset, NaiveTime, Utc};
 use owo_colors::{OwoColorize, Stream};
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
 use super::{ProjectID, SectionID};
+use crate::config::Config;
 
 /// TaskID describes the unique ID of a [`Task`].
 pub type TaskID = String;
@@ -90,6 +91,133 @@ fn default_url() -> Url {
     "http://localhost".parse().unwrap()
 }
 
+impl Task {
+    /// Returns the link to this Task in the Todoist web app.
+    ///
+    /// API v1 does not always return [`Task::url`], so it is built from the ID if missing.
+    pub fn web_url(&self) -> Url {
+        if self.url != default_url() {
+            return self.url.clone();
+        }
+        format!("https://app.todoist.com/app/task/{}", self.id)
+            .parse()
+            .unwrap_or_else(|_| default_url())
+    }
+
+    /// Returns the parsed [`Task::updated_at`] if it is set and valid.
+    pub fn last_updated(&self) -> Option<DateTime<Utc>> {
+        self.updated_at
+            .as_deref()
+            .and_then(|u| DateTime::parse_from_rfc3339(u).ok())
+            .map(|u| u.with_timezone(&Utc))
+    }
+
+    /// Returns true if the task is due before `now`. Tasks without an exact due time are only
+    /// overdue once their due date is before `today`.
+    pub fn is_overdue(&self, now: &DateTime<Utc>, today: chrono::NaiveDate) -> bool {
+        let Some(due) = &self.due else {
+            return false;
+        };
+        match due.exact_datetime() {
+            Some(exact) => exact < *now,
+            None => due.date_naive().is_some_and(|d| d < today),
+        }
+    }
+
+    /// Returns the JSON Schema describing a list of serialized [`Task`]s, as printed by
+    /// `list --format json` with project and section names and subtasks added.
+    ///
+    /// This is kept by hand in sync with the serde attributes of [`Task`] and its nested types.
+    pub fn json_schema() -> serde_json::Value {
+        let string = serde_json::json!({"type": "string"});
+        let optional_string = serde_json::json!({"type": ["string", "null"]});
+        serde_json::json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "Tasks",
+            "type": "array",
+            "items": {"$ref": "#/$defs/ListedTask"},
+            "$defs": {
+                "ListedTask": {
+                    "allOf": [{"$ref": "#/$defs/Task"}],
+                    "properties": {
+                        "project_name": optional_string,
+                        "section_name": optional_string,
+                        "subtasks": {"type": "array", "items": {"$ref": "#/$defs/ListedTask"}},
+                    },
+                    "required": ["project_name", "section_name", "subtasks"],
+                },
+                "Task": {
+                    "type": "object",
+                    "properties": {
+                        "id": string,
+                        "user_id": optional_string,
+                        "project_id": string,
+                        "section_id": optional_string,
+                        "content": string,
+                        "description": string,
+                        "is_completed": {"type": "boolean"},
+                        "labels": {"type": "array", "items": string},
+                        "parent_id": optional_string,
+                        "order": {"type": "integer"},
+                        "priority": {
+                            "description": "1 is the lowest (p4), 4 the most urgent (p1).",
+                            "type": "integer",
+                            "enum": [1, 2, 3, 4],
+                        },
+                        "due": {"oneOf": [{"$ref": "#/$defs/DueDate"}, {"type": "null"}]},
+                        "deadline": {"oneOf": [{"$ref": "#/$defs/Deadline"}, {"type": "null"}]},
+                        "duration": {"oneOf": [{"$ref": "#/$defs/Duration"}, {"type": "null"}]},
+                        "url": {"type": "string", "format": "uri"},
+                        "comment_count": {"type": "integer", "minimum": 0},
+                        "creator_id": string,
+                        "assignee_id": optional_string,
+                        "assigner_id": optional_string,
+                        "created_at": {"type": "string", "format": "date-time"},
+                        "is_deleted": {"type": "boolean"},
+                        "completed_at": optional_string,
+                        "updated_at": optional_string,
+                        "day_order": {"type": ["integer", "null"]},
+                        "is_collapsed": {"type": "boolean"},
+                    },
+                    "required": [
+                        "id", "project_id", "content", "description", "is_completed", "labels",
+                        "order", "priority", "url", "comment_count", "creator_id", "created_at",
+                        "is_deleted", "is_collapsed",
+                    ],
+                },
+                "DueDate": {
+                    "type": "object",
+                    "properties": {
+                        "string": string,
+                        "date": {
+                            "description": "YYYY-MM-DD, YYYY-MM-DDTHH:MM:SS or RFC 3339.",
+                            "type": "string",
+                        },
+                        "timezone": optional_string,
+                        "lang": string,
+                        "is_recurring": {"type": "boolean"},
+                    },
+                    "required": ["string", "date", "lang", "is_recurring"],
+                },
+                "Deadline": {
+                    "type": "object",
+                    "properties": {
+                        "date": {"type": "string", "format": "date"},
+                        "lang": string,
+                    },
+                },
+                "Duration": {
+                    "type": "object",
+                    "properties": {
+                        "amount": {"type": "integer", "minimum": 1},
+                        "unit": {"type": "string", "enum": ["minute", "day"]},
+                    },
+                },
+            },
+        })
+    }
+}
+
 impl Treeable for Task {
     type ID = TaskID;
 
@@ -161,6 +289,26 @@ pub enum Priority {
     Urgent = 4,
 }
 
+impl Priority {
+    /// Returns the next more urgent priority, staying at [`Priority::Urgent`].
+    pub fn raise(self) -> Priority {
+        match self {
+            Priority::Normal => Priority::High,
+            Priority::High => Priority::VeryHigh,
+            Priority::VeryHigh | Priority::Urgent => Priority::Urgent,
+        }
+    }
+
+    /// Returns the next less urgent priority, staying at [`Priority::Normal`].
+    pub fn lower(self) -> Priority {
+        match self {
+            Priority::Urgent => Priority::VeryHigh,
+            Priority::VeryHigh => Priority::High,
+            Priority::High | Priority::Normal => Priority::Normal,
+        }
+    }
+}
+
 impl Display for Priority {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // The priority display is reversed as in the actual desktop client compared to the API.
@@ -260,9 +408,10 @@ impl DueDate {
     }
 }
 
-/// Formats a [`DueDate`] using the given [`DateTime`], by coloring the output based on if it's
-/// too late or too soon.
-pub struct DueDateFormatter<'a>(pub &'a DueDate, pub &'a DateTime<Utc>);
+/// Formats a [`DueDate`] relative to the current time of the [`Config`], by coloring the output
+/// based on if it's too late or too soon. Due dates within [`Config::due_soon`] are highlighted as
+/// coming up soon, and in plain mode recurring dates are marked with text instead of an emoji.
+pub struct DueDateFormatter<'a>(pub &'a DueDate, pub &'a Config);
 
 /// Deadline object from the Todoist API.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
@@ -360,63 +509,52 @@ impl Display for DurationUnit {
 
 impl Display for DueDateFormatter<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.0.is_recurring {
-            write!(
-                f,
-                "{}",
-                "[REPEAT] ".if_supports_color(Stream::Stdout, |_| "🔁 ")
-            )?;
-        }
-        if let Some(exact) = self.0.exact_datetime() {
-            if exact >= *self.1 {
-                write!(
-                    f,
-                    "{}",
-                    self.0
-                        .string
-                        .if_supports_color(Stream::Stdout, |text| text.bright_green())
-                )
+        let DueDateFormatter(due_date, config) = self;
+        let now = config.override_time.unwrap_or_else(Utc::now);
+        if due_date.is_recurring {
+            if config.use_plain() {
+                write!(f, "[repeat] ")?;
             } else {
                 write!(
                     f,
                     "{}",
-                    self.0
-                        .string
-                        .if_supports_color(Stream::Stdout, |text| text.bright_red())
-                )
+                    "[REPEAT] ".if_supports_color(Stream::Stdout, |_| "🔁 ")
+                )?;
             }
-        } else if let Some(date) = self.0.date_naive() {
-            if date >= self.1.date_naive() {
-                write!(
-                    f,
-                    "{}",
-                    self.0
-                        .string
-                        .if_supports_color(Stream::Stdout, |text| text.bright_green())
-                )
-            } else {
-                write!(
-                    f,
-                    "{}",
-                    self.0
-                        .string
-                        .if_supports_color(Stream::Stdout, |text| text.bright_red())
+        }
+        // Due dates without a time stay due for the whole day, but are coming up from its start.
+        let due = match due_date.exact_datetime() {
+            Some(exact) => Some((exact >= now, exact.with_timezone(&Utc))),
+            None => due_date.date_naive().map(|date| {
+                (
+                    date >= now.date_naive(),
+                    date.and_time(NaiveTime::MIN).and_utc(),
                 )
-            }
-        } else {
-            write!(
+            }),
+        };
+        let text = &due_date.string;
+        match due {
+            Some((false, _)) => write!(
+                f,
+                "{}",
+                text.if_supports_color(Stream::Stdout, |text| text.bright_red())
+            ),
+            Some((true, at)) if config.due_soon().is_some_and(|soon| at - now <= soon) => write!(
+                f,
+                "{}",
+                text.if_supports_color(Stream::Stdout, |text| text.bright_yellow())
+            ),
+            _ => write!(
                 f,
                 "{}",
-                self.0
-                    .string
-                    .if_supports_color(Stream::Stdout, |text| text.bright_green())
-            )
+                text.if_supports_color(Stream::Stdout, |text| text.bright_green())
+            ),
         }
     }
 }
 
 /// Human representation of the due date.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum TaskDue {
     /// Human readable representation of the date.
     #[serde(rename = "due_string")]
@@ -454,9 +592,9 @@ pub struct CreateTask {
     /// Sets the [`Task::priority`] on the new [`Task`].
     #[serde(skip_serializing_if = "Option::is_none")]
     pub priority: Option<Priority>,
-    /// Sets the assignee_id on the new [`Task`].
+    /// Sets the [`Task::assignee_id`] on the new [`Task`].
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub assignee_id: Option<u32>,
+    pub assignee_id: Option<UserID>,
     /// Sets the [`Task::due`] on the new [`Task`].
     #[serde(flatten)]
     pub due: Option<TaskDue>,
@@ -474,10 +612,60 @@ pub struct CreateTask {
     pub duration_unit: Option<DurationUnit>,
 }
 
+/// FieldUpdate describes how a nullable field should be changed in an update command.
+///
+/// Unlike an [`Option`], this can differentiate between leaving a field untouched and explicitly
+/// clearing it, which gets sent as `null` to the API.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum FieldUpdate<T> {
+    /// Leaves the field as is. The field will not be serialized at all.
+    #[default]
+    Keep,
+    /// Clears the field by sending `null`.
+    Clear,
+    /// Overwrites the field with the given value.
+    Set(T),
+}
+
+impl<T> FieldUpdate<T> {
+    /// Returns true if the field should not be changed.
+    pub fn is_keep(&self) -> bool {
+        matches!(self, FieldUpdate::Keep)
+    }
+}
+
+impl<T> From<Option<T>> for FieldUpdate<T> {
+    /// Maps [`None`] to [`FieldUpdate::Keep`].
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(v) => FieldUpdate::Set(v),
+            None => FieldUpdate::Keep,
+        }
+    }
+}
+
+impl<T: Serialize> Serialize for FieldUpdate<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            FieldUpdate::Set(v) => v.serialize(serializer),
+            FieldUpdate::Keep | FieldUpdate::Clear => serializer.serialize_none(),
+        }
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for FieldUpdate<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match Option::<T>::deserialize(deserializer)? {
+            Some(v) => FieldUpdate::Set(v),
+            None => FieldUpdate::Clear,
+        })
+    }
+}
+
 /// Command used with [`super::Gateway::update`] to update a [`Task`].
 ///
 /// Each field is optional, so if something exists, that part of the [`Task`] will get overwritten.
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct UpdateTask {
     /// Overwrites [`Task::content`] if set.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -497,21 +685,48 @@ pub struct UpdateTask {
     /// If due is [TaskDue::String], this two-letter code optionally specifies the language if it's not english.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub due_lang: Option<String>,
-    /// Overwrites [`Task::assignee`] if set.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub assignee: Option<UserID>,
-    /// Sets the deadline on the task.
-    #[serde(rename = "deadline_date", skip_serializing_if = "Option::is_none")]
-    pub deadline_date: Option<String>,
+    /// Overwrites [`Task::assignee_id`] if set, or unassigns the task if cleared.
+    #[serde(
+        rename = "assignee_id",
+        default,
+        skip_serializing_if = "FieldUpdate::is_keep"
+    )]
+    pub assignee: FieldUpdate<UserID>,
+    /// Sets the deadline on the task, or removes it if cleared.
+    #[serde(
+        rename = "deadline_date",
+        default,
+        skip_serializing_if = "FieldUpdate::is_keep"
+    )]
+    pub deadline_date: FieldUpdate<String>,
     /// Language for deadline.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub deadline_lang: Option<String>,
-    /// Sets the duration on the task.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub duration: Option<u32>,
-    /// Unit of time for duration.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub duration_unit: Option<DurationUnit>,
+    /// Sets the duration on the task, or removes it if cleared.
+    #[serde(default, skip_serializing_if = "FieldUpdate::is_keep")]
+    pub duration: FieldUpdate<u32>,
+    /// Unit of time for duration. Has to be cleared together with the duration.
+    #[serde(default, skip_serializing_if = "FieldUpdate::is_keep")]
+    pub duration_unit: FieldUpdate<DurationUnit>,
+}
+
+impl UpdateTask {
+    /// Due string that makes the API remove the due date of a task.
+    pub const NO_DUE: &'static str = "no date";
+}
+
+#[cfg(test)]
+impl DueDate {
+    /// Creates a non-recurring due date, where the date is also used as the human-readable form.
+    pub fn on(date: &str) -> DueDate {
+        DueDate {
+            string: date.to_string(),
+            date: date.to_string(),
+            timezone: None,
+            lang: "en".to_string(),
+            is_recurring: false,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -560,3 +775,105 @@ pub struct CompletedTasksResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub next_cursor: Option<String>,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn json_schema_matches_task() {
+        let schema = Task::json_schema();
+        let properties = schema["$defs"]["Task"]["properties"].as_object().unwrap();
+        let mut task = Task::new("1", "task");
+        task.due = Some(DueDate {
+            string: "tomorrow".to_string(),
+            ..DueDate::on("2025-10-06")
+        });
+        let serialized = serde_json::to_value(&task).unwrap();
+        let fields = serialized.as_object().unwrap();
+        assert_eq!(
+            fields.keys().collect::<Vec<_>>(),
+            properties.keys().collect::<Vec<_>>()
+        );
+        let due = schema["$defs"]["DueDate"]["properties"]
+            .as_object()
+            .unwrap();
+        assert_eq!(
+            fields["due"]
+                .as_object()
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>(),
+            due.keys().collect::<Vec<_>>()
+        );
+        for required in schema["$defs"]["Task"]["required"].as_array().unwrap() {
+            assert!(!fields[required.as_str().unwrap()].is_null());
+        }
+    }
+
+    #[test]
+    fn raises_and_lowers_priority() {
+        assert_eq!(Priority::Normal.raise(), Priority::High);
+        assert_eq!(Priority::VeryHigh.raise(), Priority::Urgent);
+        assert_eq!(Priority::Urgent.raise(), Priority::Urgent);
+        assert_eq!(Priority::Urgent.lower(), Priority::VeryHigh);
+        assert_eq!(Priority::High.lower(), Priority::Normal);
+        assert_eq!(Priority::Normal.lower(), Priority::Normal);
+    }
+
+    #[test]
+    fn update_clears_fields() {
+        let update = UpdateTask {
+            due: Some(TaskDue::String(UpdateTask::NO_DUE.to_string())),
+            deadline_date: FieldUpdate::Clear,
+            duration: FieldUpdate::Clear,
+            duration_unit: FieldUpdate::Clear,
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&update).unwrap(),
+            serde_json::json!({
+                "due_string": "no date",
+                "deadline_date": null,
+                "duration": null,
+                "duration_unit": null
+            })
+        );
+        let update = UpdateTask {
+            deadline_date: FieldUpdate::Set("2025-10-10".to_string()),
+            duration: FieldUpdate::Set(30),
+            duration_unit: FieldUpdate::Set(DurationUnit::Minute),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&update).unwrap(),
+            serde_json::json!({
+                "deadline_date": "2025-10-10",
+                "duration": 30,
+                "duration_unit": "minute"
+            })
+        );
+    }
+
+    #[test]
+    fn update_assignee_serialization() {
+        let update = UpdateTask::default();
+        assert_eq!(serde_json::to_string(&update).unwrap(), "{}");
+        let update = UpdateTask {
+            assignee: FieldUpdate::Clear,
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_string(&update).unwrap(),
+            r#"{"assignee_id":null}"#
+        );
+        let update = UpdateTask {
+            assignee: FieldUpdate::Set("42".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_string(&update).unwrap(),
+            r#"{"assignee_id":"42"}"#
+        );
+    }
+}
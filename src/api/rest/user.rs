@@ -0,0 +1,89 @@
+use owo_colors::{OwoColorize, Stream};
+use serde::{Deserialize, Serialize};
+
+use super::UserID;
+
+/// User is the account that the API token belongs to.
+///
+/// Taken from the [Developer Documentation](https://developer.todoist.com/api/v1/#tag/User).
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct User {
+    /// The unique ID of the user.
+    pub id: UserID,
+    /// The full name of the user.
+    pub full_name: String,
+    /// The email address of the user.
+    pub email: String,
+    /// Timezone settings of the user.
+    #[serde(default)]
+    pub tz_info: Option<TimezoneInfo>,
+}
+
+impl User {
+    /// Returns the name of the timezone the user has set up, like "Europe/Berlin".
+    pub fn timezone(&self) -> Option<&str> {
+        self.tz_info.as_ref().map(|tz| tz.timezone.as_str())
+    }
+}
+
+impl std::fmt::Display for User {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "ID: {}",
+            self.id
+                .if_supports_color(Stream::Stdout, |text| text.bright_yellow())
+        )?;
+        writeln!(f, "Name: {}", self.full_name)?;
+        write!(f, "Email: {}", self.email)?;
+        if let Some(timezone) = self.timezone() {
+            write!(f, "\nTimezone: {timezone}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Timezone settings of a [`User`].
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct TimezoneInfo {
+    /// Name of the timezone, like "Europe/Berlin".
+    pub timezone: String,
+}
+
+/// Collaborator is a user that has access to a shared [`super::Project`].
+///
+/// Taken from the [Developer Documentation](https://developer.todoist.com/api/v1/#tag/Projects).
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct Collaborator {
+    /// The unique ID of the user.
+    pub id: UserID,
+    /// The full name of the user.
+    pub name: String,
+    /// The email address of the user.
+    pub email: String,
+}
+
+impl std::fmt::Display for Collaborator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} <{}>",
+            self.name,
+            self.email
+                .if_supports_color(Stream::Stdout, |text| text.bright_black())
+        )
+    }
+}
+
+#[cfg(test)]
+impl Collaborator {
+    /// This is initializer is used for tests, as in general the tool relies on the API and not
+    /// local state.
+    pub fn new(id: &str, name: &str) -> Collaborator {
+        Collaborator {
+            id: id.to_string(),
+            name: name.to_string(),
+            email: format!("{}@example.com", name.to_lowercase()),
+        }
+    }
+}
@@ -34,6 +34,9 @@ pub struct Tree<T: Treeable> {
     pub subitems: Vec<Tree<T>>,
     /// How deep we are in this tree, useful for representation.
     pub depth: usize,
+    /// Set if the item referenced a parent that could not be found, so it was promoted to the
+    /// top level of the tree instead.
+    pub orphaned: bool,
 }
 
 impl<T: Treeable> Deref for Tree<T> {
@@ -61,6 +64,7 @@ impl<T: Treeable + std::cmp::PartialEq> PartialOrd for Tree<T> {
 struct TreeBuilder<Treeable> {
     item: Treeable,
     parent: Option<()>,
+    orphaned: bool,
     subitems: Vec<Rc<RefCell<TreeBuilder<Treeable>>>>,
 }
 
@@ -80,6 +84,7 @@ impl<T: Treeable> TreeBuilder<T> {
             item: self.item,
             subitems,
             depth,
+            orphaned: self.orphaned,
         }
     }
 }
@@ -91,6 +96,7 @@ impl<T: Treeable + std::cmp::Eq> Tree<T> {
             item,
             subitems: vec![],
             depth: 0,
+            orphaned: false,
         }
     }
     /// Synthesizes a Tree out of a list of [`Treeable`] items.
@@ -102,7 +108,9 @@ impl<T: Treeable + std::cmp::Eq> Tree<T> {
     ///
     /// There is a case where a filtered todoist API will return only the subtasks and not
     /// its parents. Currently solved it by resetting parents of tasks that are not in the initial vector.
-    /// This is curiously also what the Todoist Client does.
+    /// This is curiously also what the Todoist Client does. Such items are marked as
+    /// [`Tree::orphaned`]. The same happens to items whose parents can never be attached (e.g.
+    /// circular references), so no item is ever lost.
     ///
     /// The output from a whole tree can be used with the [`TreeFlattenExt::keep_trees`] method to get a clean tree.
     pub fn from_items(items: Vec<T>) -> Result<Vec<Tree<T>>> {
@@ -111,14 +119,17 @@ impl<T: Treeable + std::cmp::Eq> Tree<T> {
         let (top_level_items, mut subitems): (VecDeque<_>, VecDeque<_>) = items
             .into_iter()
             .map(|mut item| {
+                let mut orphaned = false;
                 if let Some(parent) = item.parent_id()
                     && !ids.contains(&parent)
                 {
                     item.reset_parent();
+                    orphaned = true;
                 }
                 Rc::new(RefCell::new(TreeBuilder {
                     item,
                     parent: None,
+                    orphaned,
                     subitems: vec![],
                 }))
             })
@@ -130,8 +141,21 @@ impl<T: Treeable + std::cmp::Eq> Tree<T> {
             .map(|item| (item.borrow().item.id(), item.clone()))
             .collect();
 
+        // Counts how many items were put back in a row without finding their parent
+        let mut stalled = 0;
         while !subitems.is_empty() {
             let subitem = subitems.pop_front().unwrap();
+            if stalled > subitems.len() {
+                // None of the remaining items can be attached, promote one to the top level.
+                stalled = 0;
+                {
+                    let mut builder = subitem.borrow_mut();
+                    builder.item.reset_parent();
+                    builder.orphaned = true;
+                }
+                items.insert(subitem.borrow().item.id(), subitem.clone());
+                continue;
+            }
             let parent = items.entry(
                 subitem
                     .borrow()
@@ -141,8 +165,10 @@ impl<T: Treeable + std::cmp::Eq> Tree<T> {
             );
             if let Entry::Vacant(_) = parent {
                 subitems.push_back(subitem);
+                stalled += 1;
                 continue;
             }
+            stalled = 0;
             // Get the parent, and add our entry
             parent.and_modify(|entry| {
                 subitem.borrow_mut().parent = Some(());
@@ -192,6 +218,15 @@ impl<T: Treeable + std::cmp::Eq> Tree<T> {
         None
     }
 
+    /// Moves this tree to the given depth, together with all of its subitems. Used to show a
+    /// subtree as if it was at the top level.
+    pub fn set_depth(&mut self, depth: usize) {
+        self.depth = depth;
+        for item in &mut self.subitems {
+            item.set_depth(depth + 1);
+        }
+    }
+
     /// Tries to find the item with the given ID in this tree, mutably.
     pub fn find_mut(&mut self, id: &<T as Treeable>::ID) -> Option<&mut Tree<T>> {
         if self.item.id() == *id {
@@ -364,7 +399,44 @@ mod tests {
         let trees = Tree::from_items(tasks).unwrap();
         assert_eq!(trees.len(), 1);
         assert_eq!(trees[0].item.parent_id, None);
+        assert!(trees[0].orphaned);
         assert_eq!(trees[0].subitems[0].item.id, "3");
+        assert!(!trees[0].subitems[0].orphaned);
+    }
+
+    #[test]
+    fn task_tree_missing_parent_keeps_child() {
+        let tasks = vec![
+            Task::new("1", "one"),
+            Task {
+                parent_id: Some("404".to_string()),
+                ..Task::new("2", "two")
+            },
+        ];
+        let trees = Tree::from_items(tasks).unwrap();
+        assert_eq!(trees.len(), 2);
+        let child = trees.find(&"2".to_string()).unwrap();
+        assert_eq!(child.depth, 0);
+        assert!(child.orphaned);
+        assert!(!trees.find(&"1".to_string()).unwrap().orphaned);
+    }
+
+    #[test]
+    fn task_tree_circular_parents() {
+        let tasks = vec![
+            Task {
+                parent_id: Some("2".to_string()),
+                ..Task::new("1", "one")
+            },
+            Task {
+                parent_id: Some("1".to_string()),
+                ..Task::new("2", "two")
+            },
+        ];
+        let trees = Tree::from_items(tasks).unwrap();
+        assert_eq!(trees.flat_tree().len(), 2);
+        assert_eq!(trees.len(), 1);
+        assert!(trees[0].orphaned);
     }
 
     #[test]
@@ -0,0 +1,21 @@
+use std::{fs, io::ErrorKind};
+
+use color_eyre::{Result, eyre::WrapErr};
+
+use crate::{config::Config, output::info};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {}
+
+/// Removes the cached projects, sections and labels of all tokens.
+pub fn clear(_params: Params, cfg: &Config) -> Result<()> {
+    let dir = cfg.cache_dir()?;
+    match fs::remove_dir_all(&dir) {
+        Ok(()) => info!("Cache cleared"),
+        Err(e) if e.kind() == ErrorKind::NotFound => info!("Cache is already empty"),
+        Err(e) => {
+            return Err(e).wrap_err_with(|| format!("unable to remove {}", dir.display()));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,2 @@
+//! Works with the disk cache of projects, sections and labels, see [`crate::api::rest::DiskCache`].
+pub mod clear;
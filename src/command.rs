@@ -1,12 +1,21 @@
 use std::path::PathBuf;
 
 use crate::{
-    config::Config,
-    labels, projects, sections,
-    tasks::{add, close, comment, completed, create, edit, list, view},
+    api::rest::{DryRunStopped, Gateway},
+    cache,
+    config::{self, Config},
+    interactive, labels, output,
+    output::info,
+    projects, sections,
+    tasks::{
+        add, bump, close, comment, comments, completed, create, duplicate, edit, import, list,
+        move_task, reopen, reorder, reschedule, show, stats, view,
+    },
+    templates, user,
 };
+use chrono::{DateTime, Utc};
 use clap::{Args, Parser, Subcommand};
-use color_eyre::Result;
+use color_eyre::{Result, eyre::eyre};
 
 /// Args are the main entry point struct of the CLI app.
 #[derive(Parser, Debug)]
@@ -15,6 +24,30 @@ pub struct Arguments {
     /// Overrides the config directory location.
     #[arg(long = "config_prefix")]
     config_prefix: Option<PathBuf>,
+    /// Uses the given config file instead of the default one. Can also be set with the
+    /// DOIST_CONFIG environment variable.
+    #[arg(long = "config", global = true)]
+    config: Option<PathBuf>,
+    /// Pretends the current time is the given RFC 3339 timestamp, e.g. "2025-10-10T12:00:00Z".
+    /// Makes the date-dependent output reproducible for tests and demos.
+    #[arg(long = "now", global = true, hide = true)]
+    now: Option<DateTime<Utc>>,
+    /// Prints the changes that would be made as JSON instead of sending them to Todoist.
+    #[arg(long = "dry-run", global = true)]
+    dry_run: bool,
+    /// Only prints errors, useful for scripts that just check whether a command succeeded.
+    #[arg(short = 'q', long = "quiet", global = true, conflicts_with = "verbose")]
+    quiet: bool,
+    /// Also prints the requests that are sent to the Todoist API to stderr.
+    #[arg(short = 'v', long = "verbose", global = true)]
+    verbose: bool,
+    /// Fetches projects, sections and labels from Todoist even if the disk cache has them.
+    #[arg(long = "no-cache", global = true)]
+    no_cache: bool,
+    /// Uses the given API token instead of the one in the config. Can also be set with the
+    /// DOIST_TOKEN environment variable.
+    #[arg(long = "token", global = true, value_name = "TOKEN")]
+    api_token: Option<String>,
 
     #[command(subcommand)]
     command: Option<Commands>,
@@ -26,12 +59,22 @@ pub struct Arguments {
 #[derive(Subcommand, Debug)]
 enum Commands {
     /// Authenticates with the Todoist API.
+    #[command(visible_alias = "login")]
     Auth {
         /// The Todoist API token.
         /// This can be taken from the Todoist client by going into
         /// Settings -> Integrations -> API token
-        token: String,
+        #[arg(required_unless_present = "stdin")]
+        token: Option<String>,
+        /// Reads the token from stdin instead, so it doesn't show up in the shell history.
+        #[arg(long = "stdin", conflicts_with = "token")]
+        stdin: bool,
     },
+    /// Manages task templates, which are defined in the config.
+    #[command(visible_aliases = ["template", "t"])]
+    Templates(TemplateArgs),
+    /// Manages the disk cache of projects, sections and labels.
+    Cache(CacheArgs),
     /// Authenticated commands are commands that require a token to be set up via the Auth command
     /// before executing.
     #[command(flatten)]
@@ -49,24 +92,56 @@ enum AuthCommands {
     /// Lists tasks. This is the default if no subcommand is specified.
     #[command(visible_alias = "l")]
     List(list::Params),
-    /// Edits a task.
+    /// Edits a task, or all tasks matching --filter.
     #[command(visible_alias = "e")]
     Edit(edit::Params),
     /// Closes a task.
     #[command(visible_alias = "c")]
     Close(close::Params),
+    /// Creates copies of a task. Subtasks and comments are not copied.
+    #[command(visible_alias = "dup")]
+    Duplicate(duplicate::Params),
+    /// Creates the tasks of a JSON or CSV file.
+    Import(import::Params),
+    /// Puts tasks of the same project, section and parent task into the given order.
+    Reorder(reorder::Params),
+    /// Moves a task one place up, swapping it with the task above.
+    Bump(bump::Params),
+    /// Moves a task one place down, swapping it with the task below.
+    Drop(bump::Params),
+    /// Moves a task into another project and/or section.
+    #[command(visible_alias = "m")]
+    Move(move_task::Params),
+    /// Reopens a closed task. Tasks closed with `close --complete` keep the due date they were
+    /// completed with, so recurring tasks won't get their original due date back.
+    #[command(visible_alias = "ro")]
+    Reopen(reopen::Params),
+    /// Moves overdue tasks to today or spreads them across the upcoming days.
+    #[command(visible_alias = "rs")]
+    Reschedule(reschedule::Params),
     /// View details of a single task.
     #[command(visible_alias = "v")]
     View(view::Params),
+    /// Prints the details of a task by its ID, without any interaction.
+    Show(show::Params),
     /// Add a comment on a task.
     #[command(visible_alias = "C")]
     Comment(comment::Params),
+    /// Lists the comments of a task or project.
+    #[command(visible_alias = "cs")]
+    Comments(comments::Params),
     /// Lists completed tasks by completion date (default, up to 3 months) or due date (--by-due-date, up to 6 weeks).
     #[command(visible_alias = "comp")]
     Completed(completed::Params),
+    /// Shows statistics about tasks, like the completion streak of a recurring task.
+    Stats(stats::Params),
+    /// Shows the account that the API token belongs to.
+    Whoami(user::whoami::Params),
+    /// Fetches projects, sections and labels to warm the cache and check that the token works.
+    Sync(user::sync::Params),
 
     /// Manages projects.
-    #[command(visible_alias = "p")]
+    #[command(visible_aliases = ["p", "project"])]
     Projects(ProjectArgs),
     /// Manages labels.
     #[command(visible_alias = "lbl")]
@@ -93,12 +168,21 @@ enum ProjectCommands {
     /// Add a comment on a project.
     #[command(visible_alias = "C")]
     Comment(projects::comment::Params),
-    /// Adds (creates) a new project.
-    #[command(visible_alias = "a")]
+    /// Adds (creates) a new project, optionally from a project template.
+    #[command(visible_aliases = ["a", "create"])]
     Add(projects::add::Params),
     /// Deletes a project
     #[command(visible_alias = "d")]
     Delete(projects::delete::Params),
+    /// Renames or restyles a project.
+    #[command(visible_alias = "e")]
+    Edit(projects::edit::Params),
+    /// Archives a project, hiding it from the project list.
+    Archive(projects::archive::Params),
+    /// Restores an archived project.
+    Unarchive(projects::unarchive::Params),
+    /// Shows active, overdue and recently completed task counts for each project.
+    Stats(projects::stats::Params),
 
     /// Manages sections.
     #[command(visible_alias = "s")]
@@ -125,6 +209,40 @@ enum LabelCommands {
     /// Deletes a label.
     #[command(visible_alias = "d")]
     Delete(labels::delete::Params),
+    /// Renames a label or changes its color.
+    #[command(visible_aliases = ["e", "rename"])]
+    Edit(labels::edit::Params),
+    /// Finds tasks that use labels that no longer exist and optionally removes them.
+    #[command(visible_alias = "doctor")]
+    Prune(labels::prune::Params),
+}
+
+#[derive(Args, Debug)]
+#[command(args_conflicts_with_subcommands = true)]
+struct TemplateArgs {
+    #[command(subcommand)]
+    command: Option<TemplateCommands>,
+    #[command(flatten)]
+    params: templates::list::Params,
+}
+
+#[derive(Subcommand, Debug)]
+enum TemplateCommands {
+    /// Lists all configured templates.
+    #[command(visible_alias = "l")]
+    List(templates::list::Params),
+}
+
+#[derive(Args, Debug)]
+struct CacheArgs {
+    #[command(subcommand)]
+    command: CacheCommands,
+}
+
+#[derive(Subcommand, Debug)]
+enum CacheCommands {
+    /// Removes everything from the disk cache.
+    Clear(cache::clear::Params),
 }
 
 #[derive(Args, Debug)]
@@ -147,69 +265,74 @@ enum SectionCommands {
     /// Deletes a section in a project.
     #[command(visible_alias = "d")]
     Delete(sections::delete::Params),
+    /// Renames a section.
+    #[command(visible_alias = "r")]
+    Rename(sections::rename::Params),
 }
 
 impl Arguments {
     /// Runs the CLI app.
     pub async fn exec(self) -> Result<()> {
-        let mut cfg = match self.config_prefix {
-            Some(p) => Config::load_prefix(&p),
-            None => Config::load(),
+        let file = self.config.or_else(|| {
+            std::env::var_os(config::CONFIG_ENV)
+                .filter(|f| !f.is_empty())
+                .map(PathBuf::from)
+        });
+        let mut cfg = match (file, self.config_prefix) {
+            // Authenticating is what creates the config file in the first place.
+            (Some(f), _) => {
+                Config::load_file(&f, matches!(self.command, Some(Commands::Auth { .. })))
+            }
+            (None, Some(p)) => Config::load_prefix(&p),
+            (None, None) => Config::load(),
         }?;
+        output::set_level(match (self.quiet, self.verbose) {
+            (true, _) => output::Level::Quiet,
+            (false, true) => output::Level::Verbose,
+            (false, false) => output::Level::Normal,
+        });
+        // Not applied when authenticating, as that saves the config and would persist the time.
+        if let Some(now) = self.now
+            && !matches!(self.command, Some(Commands::Auth { .. }))
+        {
+            cfg.override_time = Some(now);
+        }
+        cfg.dry_run = self.dry_run;
+        cfg.no_cache = self.no_cache;
+        cfg.token_override = self.api_token.or_else(|| {
+            std::env::var(config::TOKEN_ENV)
+                .ok()
+                .filter(|t| !t.is_empty())
+        });
         match self.command {
             Some(command) => match command {
-                Commands::Auth { token } => {
+                Commands::Auth { token, .. } => {
+                    let token = match token {
+                        Some(token) => token,
+                        None => interactive::text_or_stdin("-".to_owned())?
+                            .trim()
+                            .to_owned(),
+                    };
+                    if token.trim().is_empty() {
+                        return Err(eyre!("the token must not be empty"));
+                    }
                     cfg.token = Some(token);
                     cfg.save()?;
-                    println!("Token successfully saved")
+                    info!("Token successfully saved")
                 }
+                Commands::Templates(t) => match t.command {
+                    Some(TemplateCommands::List(p)) => templates::list::list(p, &cfg)?,
+                    None => templates::list::list(t.params, &cfg)?,
+                },
+                Commands::Cache(c) => match c.command {
+                    CacheCommands::Clear(p) => cache::clear::clear(p, &cfg)?,
+                },
                 Commands::Authenticated(command) => {
                     let gw = cfg.gateway()?;
-                    match *command {
-                        AuthCommands::Add(p) => add::add(p, &gw, &cfg).await?,
-                        AuthCommands::Create(p) => create::create(p, &gw, &cfg).await?,
-                        AuthCommands::List(p) => list::list(p, &gw, &cfg).await?,
-                        AuthCommands::Edit(p) => edit::edit(p, &gw, &cfg).await?,
-                        AuthCommands::Close(p) => close::close(p, &gw, &cfg).await?,
-                        AuthCommands::View(p) => view::view(p, &gw, &cfg).await?,
-                        AuthCommands::Comment(p) => comment::comment(p, &gw, &cfg).await?,
-                        AuthCommands::Completed(p) => completed::completed(p, &gw, &cfg).await?,
-                        AuthCommands::Projects(p) => match p.command {
-                            Some(p) => match p {
-                                ProjectCommands::List(p) => projects::list::list(p, &gw).await?,
-                                ProjectCommands::View(p) => projects::view::view(p, &gw).await?,
-                                ProjectCommands::Comment(p) => {
-                                    projects::comment::comment(p, &gw).await?
-                                }
-                                ProjectCommands::Add(p) => projects::add::add(p, &gw).await?,
-                                ProjectCommands::Delete(p) => {
-                                    projects::delete::delete(p, &gw).await?
-                                }
-                                ProjectCommands::Sections(s) => match s.command {
-                                    Some(s) => match s {
-                                        SectionCommands::List(p) => {
-                                            sections::list::list(p, &gw).await?
-                                        }
-                                        SectionCommands::Add(p) => {
-                                            sections::add::add(p, &gw).await?
-                                        }
-                                        SectionCommands::Delete(p) => {
-                                            sections::delete::delete(p, &gw).await?
-                                        }
-                                    },
-                                    None => sections::list::list(s.params, &gw).await?,
-                                },
-                            },
-                            None => projects::list::list(p.params, &gw).await?,
-                        },
-                        AuthCommands::Labels(p) => match p.command {
-                            Some(p) => match p {
-                                LabelCommands::List(p) => labels::list::list(p, &gw).await?,
-                                LabelCommands::Add(p) => labels::add::add(p, &gw).await?,
-                                LabelCommands::Delete(p) => labels::delete::delete(p, &gw).await?,
-                            },
-                            None => labels::list::list(p.params, &gw).await?,
-                        },
+                    match exec_authenticated(*command, &gw, &cfg).await {
+                        // The change that stopped the dry run was printed already.
+                        Err(e) if e.chain().any(|e| e.is::<DryRunStopped>()) => {}
+                        result => result?,
                     }
                 }
             },
@@ -220,6 +343,68 @@ impl Arguments {
         Ok(())
     }
 }
+
+/// Runs a command that needs to talk to the API.
+async fn exec_authenticated(command: AuthCommands, gw: &Gateway, cfg: &Config) -> Result<()> {
+    match command {
+        AuthCommands::Add(p) => add::add(p, gw, cfg).await?,
+        AuthCommands::Create(p) => create::create(p, gw, cfg).await?,
+        AuthCommands::List(p) => list::list(p, gw, cfg).await?,
+        AuthCommands::Edit(p) => edit::edit(p, gw, cfg).await?,
+        AuthCommands::Close(p) => close::close(p, gw, cfg).await?,
+        AuthCommands::Duplicate(p) => duplicate::duplicate(p, gw, cfg).await?,
+        AuthCommands::Import(p) => import::import(p, gw, cfg).await?,
+        AuthCommands::Reorder(p) => reorder::reorder(p, gw).await?,
+        AuthCommands::Bump(p) => bump::bump(p, bump::Direction::Up, gw, cfg).await?,
+        AuthCommands::Drop(p) => bump::bump(p, bump::Direction::Down, gw, cfg).await?,
+        AuthCommands::Move(p) => move_task::move_task(p, gw, cfg).await?,
+        AuthCommands::Reopen(p) => reopen::reopen(p, gw, cfg).await?,
+        AuthCommands::Reschedule(p) => reschedule::reschedule(p, gw, cfg).await?,
+        AuthCommands::View(p) => view::view(p, gw, cfg).await?,
+        AuthCommands::Show(p) => show::show(p, gw, cfg).await?,
+        AuthCommands::Comment(p) => comment::comment(p, gw, cfg).await?,
+        AuthCommands::Comments(p) => comments::comments(p, gw, cfg).await?,
+        AuthCommands::Completed(p) => completed::completed(p, gw, cfg).await?,
+        AuthCommands::Stats(p) => stats::stats(p, gw, cfg).await?,
+        AuthCommands::Whoami(p) => user::whoami::whoami(p, gw).await?,
+        AuthCommands::Sync(p) => user::sync::sync(p, gw).await?,
+        AuthCommands::Projects(p) => match p.command {
+            Some(p) => match p {
+                ProjectCommands::List(p) => projects::list::list(p, gw, cfg).await?,
+                ProjectCommands::View(p) => projects::view::view(p, gw, cfg).await?,
+                ProjectCommands::Comment(p) => projects::comment::comment(p, gw, cfg).await?,
+                ProjectCommands::Add(p) => projects::add::add(p, gw, cfg).await?,
+                ProjectCommands::Delete(p) => projects::delete::delete(p, gw, cfg).await?,
+                ProjectCommands::Edit(p) => projects::edit::edit(p, gw, cfg).await?,
+                ProjectCommands::Archive(p) => projects::archive::archive(p, gw, cfg).await?,
+                ProjectCommands::Unarchive(p) => projects::unarchive::unarchive(p, gw, cfg).await?,
+                ProjectCommands::Stats(p) => projects::stats::stats(p, gw, cfg).await?,
+                ProjectCommands::Sections(s) => match s.command {
+                    Some(s) => match s {
+                        SectionCommands::List(p) => sections::list::list(p, gw, cfg).await?,
+                        SectionCommands::Add(p) => sections::add::add(p, gw, cfg).await?,
+                        SectionCommands::Delete(p) => sections::delete::delete(p, gw, cfg).await?,
+                        SectionCommands::Rename(p) => sections::rename::rename(p, gw).await?,
+                    },
+                    None => sections::list::list(s.params, gw, cfg).await?,
+                },
+            },
+            None => projects::list::list(p.params, gw, cfg).await?,
+        },
+        AuthCommands::Labels(p) => match p.command {
+            Some(p) => match p {
+                LabelCommands::List(p) => labels::list::list(p, gw).await?,
+                LabelCommands::Add(p) => labels::add::add(p, gw).await?,
+                LabelCommands::Delete(p) => labels::delete::delete(p, gw, cfg).await?,
+                LabelCommands::Edit(p) => labels::edit::edit(p, gw).await?,
+                LabelCommands::Prune(p) => labels::prune::prune(p, gw).await?,
+            },
+            None => labels::list::list(p.params, gw).await?,
+        },
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
     use crate::Arguments;
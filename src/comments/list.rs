@@ -1,9 +1,8 @@
-use crate::api::rest::{Comment, FullComment};
+use crate::api::rest::{Collaborator, Comment, CommentThread};
+use crate::output::info;
 
-pub fn list(comments: &[Comment]) {
-    println!("Comments:");
-    for comment in comments {
-        println!("-----");
-        println!("{}", FullComment(comment));
-    }
+/// Prints the comments as a thread, resolving posters to names among the collaborators.
+pub fn list(comments: &[Comment], collaborators: &[Collaborator]) {
+    info!("Comments:");
+    info!("{}", CommentThread(comments, collaborators));
 }
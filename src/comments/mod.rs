@@ -3,3 +3,21 @@
 mod list;
 
 pub use list::list;
+
+use crate::api::rest::{Collaborator, Gateway, Project};
+
+/// Fetches the collaborators of a shared project, so comment posters can be shown by name.
+///
+/// Unshared projects have no other posters, and failing to fetch only costs the names.
+pub async fn collaborators(project: Option<&Project>, gw: &Gateway) -> Vec<Collaborator> {
+    match project {
+        Some(project) if project.is_shared => match gw.collaborators(&project.id).await {
+            Ok(collaborators) => collaborators,
+            Err(e) => {
+                eprintln!("collaborators unavailable: {e:#}");
+                Vec::new()
+            }
+        },
+        _ => Vec::new(),
+    }
+}
@@ -1,18 +1,20 @@
 //! Describes everything related to configuration of the binary.
 use std::{
+    collections::BTreeMap,
     fs,
+    io::{IsTerminal, Write},
     path::{Path, PathBuf},
 };
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
 use color_eyre::{Result, eyre::eyre};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::api::rest::{Gateway, TODOIST_API_URL};
+use crate::api::rest::{DiskCache, Gateway, GatewayOptions, TODOIST_API_URL};
 
 /// Stores configuration used by the application.
-#[derive(Serialize, Deserialize, Default)]
+#[derive(Serialize, Deserialize, Default, Clone)]
 pub struct Config {
     /// The auth token that will be used to work with the Todoist API.
     /// The API Token can be found in the [Todoist settings](https://todoist.com/app/settings/integrations).
@@ -22,16 +24,243 @@ pub struct Config {
     /// `DEFAULT_FILTER` if none specifed.
     #[serde(default = "default_filter")]
     pub default_filter: String,
+    /// Name or ID of the project that `add` puts new tasks into if no project is given. Without
+    /// it, new tasks go to the Inbox.
+    #[serde(default)]
+    pub default_project: Option<String>,
+    /// Names of the labels that `add` puts on every new task, next to the ones that are given.
+    #[serde(default)]
+    pub default_labels: Vec<String>,
     /// Can override the API URL used by all commands. Mostly used for testing, but go crazy!
     #[serde(default = "default_url")]
     pub url: Option<url::Url>,
+    /// Seconds to wait for a response from the API before giving up. Defaults to 30.
+    #[serde(default)]
+    pub request_timeout_secs: Option<u64>,
+    /// How often requests that failed because of network or server errors are retried. Defaults
+    /// to 3.
+    #[serde(default)]
+    pub max_retries: Option<u32>,
     /// Override the current time for various display options in the CLI.
     #[serde(default)]
     pub override_time: Option<DateTime<Utc>>,
+    /// Timezone used to bucket tasks into days, e.g. "Asia/Seoul". Defaults to the local timezone.
+    #[serde(default)]
+    pub timezone: Option<chrono_tz::Tz>,
+    /// Makes task and project IDs clickable links to the Todoist web app in terminals that
+    /// support it.
+    #[serde(default)]
+    pub hyperlinks: bool,
+    /// Renders the Markdown of task descriptions with terminal styles when viewing tasks.
+    #[serde(default)]
+    pub render_markdown: bool,
+    /// Keeps projects, sections and labels on disk between runs, so most commands don't have to
+    /// fetch them every time. Changes made in other apps only show up once the cache expires.
+    #[serde(default)]
+    pub disk_cache: bool,
+    /// Seconds that projects, sections and labels are kept in the disk cache. Defaults to an
+    /// hour.
+    #[serde(default)]
+    pub disk_cache_ttl_secs: Option<u64>,
+    /// Configures the badge that shows the age of tasks that were created a while ago.
+    #[serde(default)]
+    pub age_badge: AgeBadge,
+    /// Hours before a task is due in which its due date is highlighted as coming up soon. Not
+    /// highlighted by default.
+    #[serde(default)]
+    pub due_soon_hours: Option<u32>,
+    /// Uses plain ASCII markers like "[done]" instead of emoji in task lists, for terminals that
+    /// can't display them. Also enabled by setting the NO_EMOJI environment variable.
+    #[serde(default)]
+    pub plain: bool,
+    /// Customizes the look of interactive prompts.
+    #[serde(default)]
+    pub theme: Theme,
+    /// Characters used to draw the subtask tree in task lists.
+    #[serde(default)]
+    pub tree_style: TreeStyle,
+    /// The date range used by `completed` when no range flags are given. Defaults to today.
+    #[serde(default)]
+    pub completed_default_range: Option<CompletedRange>,
+    /// The first day of the week for `--this-week` and `--last-week`. Defaults to Monday.
+    #[serde(default)]
+    pub week_start: WeekStart,
+    /// Named task templates that can be used with `doist add --template`.
+    #[serde(default)]
+    pub templates: BTreeMap<String, TaskTemplate>,
+    /// Named project templates that can be used with `doist projects add --template`.
+    #[serde(default)]
+    pub project_templates: BTreeMap<String, ProjectTemplate>,
 
+    /// Prints the changes commands would make instead of sending them. Set with `--dry-run`.
+    #[serde(skip)]
+    pub dry_run: bool,
+    /// Bypasses the disk cache for a single run. Set with `--no-cache`.
+    #[serde(skip)]
+    pub no_cache: bool,
+    /// Uses this token instead of [`Config::token`] for a single run. Set with `--token` or the
+    /// `DOIST_TOKEN` environment variable.
+    #[serde(skip)]
+    pub token_override: Option<String>,
     /// Sets a particular config location prefix. Mostly used for testing.
     #[serde(skip)]
     pub prefix: Option<PathBuf>,
+    /// Sets an explicit config file, which takes precedence over [`Config::prefix`].
+    #[serde(skip)]
+    pub file: Option<PathBuf>,
+}
+
+/// Date range keywords that can be used as the default range for listing completed tasks.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum CompletedRange {
+    /// Tasks completed today.
+    #[default]
+    Today,
+    /// Tasks completed yesterday.
+    Yesterday,
+    /// Tasks completed this week (start of the week to today).
+    ThisWeek,
+    /// Tasks completed last week (a full week, see [`WeekStart`]).
+    LastWeek,
+    /// Tasks completed this month (1st to today).
+    ThisMonth,
+}
+
+/// The first day of the week, used for week based date ranges.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum WeekStart {
+    /// Weeks start on Monday, as in ISO 8601.
+    #[default]
+    Monday,
+    /// Weeks start on Sunday, as is common in the US.
+    Sunday,
+}
+
+impl WeekStart {
+    /// Returns the first day of the week that contains the given date.
+    pub fn first_day(self, date: NaiveDate) -> NaiveDate {
+        let days = match self {
+            WeekStart::Monday => date.weekday().num_days_from_monday(),
+            WeekStart::Sunday => date.weekday().num_days_from_sunday(),
+        };
+        date - chrono::Days::new(days.into())
+    }
+}
+
+/// TaskTemplate describes a task with its subtasks that can be created repeatedly.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct TaskTemplate {
+    /// Name of the task. Can be left out if a name is always given when adding the task.
+    #[serde(default)]
+    pub content: Option<String>,
+    /// Description of the task.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Names of the labels of the task.
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// Priority of the task, from 1 (urgent) to 4 (normal).
+    #[serde(default)]
+    pub priority: Option<usize>,
+    /// Name of the project the task is added to, fuzzy matched like `--project`.
+    #[serde(default)]
+    pub project: Option<String>,
+    /// Human-readable due date of the task, like "every friday".
+    #[serde(default)]
+    pub due: Option<String>,
+    /// Names of the subtasks that are created under the task.
+    #[serde(default)]
+    pub subtasks: Vec<String>,
+}
+
+/// ProjectTemplate describes the layout of a project that can be created repeatedly.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProjectTemplate {
+    /// Name of the project. Can be left out if a name is always given when adding the project.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Color of the project icon.
+    #[serde(default)]
+    pub color: Option<String>,
+    /// Names of the sections that are created in the project, in order.
+    #[serde(default)]
+    pub sections: Vec<String>,
+    /// Names of the tasks that are added to the new project.
+    #[serde(default)]
+    pub tasks: Vec<String>,
+}
+
+/// AgeBadge configures the badge that shows how many days ago a task was created.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct AgeBadge {
+    /// Shows the badge on old tasks. Enabled by default.
+    pub enabled: bool,
+    /// Minimum age in days of tasks that get the badge. Defaults to 7.
+    pub threshold_days: u32,
+    /// Text shown after the amount of days, like "d ago". Defaults to "일 전", or "d ago" in
+    /// plain mode.
+    pub suffix: Option<String>,
+}
+
+impl Default for AgeBadge {
+    fn default() -> Self {
+        AgeBadge {
+            enabled: true,
+            threshold_days: 7,
+            suffix: None,
+        }
+    }
+}
+
+impl AgeBadge {
+    /// Returns the configured suffix, or the default one for the given mode.
+    pub fn suffix(&self, plain: bool) -> &str {
+        match &self.suffix {
+            Some(suffix) => suffix,
+            None if plain => "d ago",
+            None => "일 전",
+        }
+    }
+}
+
+/// Theme configures how interactive prompts are displayed.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Theme {
+    /// The overall style of the prompts.
+    #[serde(default)]
+    pub style: ThemeStyle,
+    /// Replaces the symbol shown in front of prompts. Only used with the colorful style.
+    #[serde(default)]
+    pub prompt_prefix: Option<String>,
+    /// Style of the matched characters when fuzzy searching, in dotted notation like
+    /// "yellow.bold". Only used with the colorful style.
+    #[serde(default)]
+    pub highlight: Option<String>,
+}
+
+/// The overall style of interactive prompts.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeStyle {
+    /// Colored prompts with symbols.
+    #[default]
+    Colorful,
+    /// Minimal prompts without colors, for terminals that can't display them.
+    Plain,
+}
+
+/// Characters used to draw the branches of the subtask tree.
+#[derive(Serialize, Deserialize, clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TreeStyle {
+    /// Box-drawing characters, like `├─` and `└─`.
+    #[default]
+    Unicode,
+    /// Plain ASCII characters, like `|-` and `` `- ``, for terminals without Unicode support.
+    Ascii,
 }
 
 /// Returns the default URL to be used for calling the Todoist API.
@@ -46,6 +275,24 @@ fn default_filter() -> String {
     DEFAULT_FILTER.to_string()
 }
 
+/// Looks up a template by name, listing the available templates if it doesn't exist.
+fn find_template<'a, T>(templates: &'a BTreeMap<String, T>, name: &str) -> Result<&'a T> {
+    templates.get(name).ok_or_else(|| {
+        if templates.is_empty() {
+            eyre!("unknown template {name}, no templates are configured")
+        } else {
+            eyre!(
+                "unknown template {name}, available templates: {}",
+                templates
+                    .keys()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        }
+    })
+}
+
 /// Describes errors that occur when loading from configuration storage.
 #[derive(Error, Debug)]
 pub enum ConfigError {
@@ -61,18 +308,36 @@ pub enum ConfigError {
     /// For errors that happen during saving of the config file.
     #[error("unable to save config file")]
     SaveFormat(#[from] toml::ser::Error),
+    /// For errors that happen when the config file contains invalid values.
+    #[error("invalid config file")]
+    LoadFormat(#[from] toml::de::Error),
 }
 
 /// Defines the configuration filename inside the config directory.
 const CONFIG_FILE: &str = "config.toml";
 
+/// Defines the name of the cache directory inside the config directory.
+const CACHE_DIR: &str = "cache";
+
 /// The name of the directories where configuration is stored.
 const XDG_PREFIX: &str = "doist";
 
+/// Environment variable that can point to a config file to use instead of the default one.
+pub const CONFIG_ENV: &str = "DOIST_CONFIG";
+
+/// Environment variable that holds an API token to use instead of [`Config::token`].
+pub const TOKEN_ENV: &str = "DOIST_TOKEN";
+
+/// Environment variable that enables [`Config::plain`] when set to a non-empty value.
+const NO_EMOJI_ENV: &str = "NO_EMOJI";
+
 impl Config {
     #[cfg(windows)]
     fn config_dir(prefix: Option<&Path>) -> Result<PathBuf, ConfigError> {
-        dirs::config_dir()
+        std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|p| p.is_absolute())
+            .or_else(dirs::config_dir)
             .map(|mut path| {
                 path.push(prefix.and_then(|p| p.to_str()).unwrap_or(XDG_PREFIX));
                 path
@@ -101,6 +366,16 @@ impl Config {
         Ok(path)
     }
 
+    /// Returns the directory of the disk cache, which is next to the config file.
+    pub fn cache_dir(&self) -> Result<PathBuf, ConfigError> {
+        let mut path = match self.file.as_deref().and_then(Path::parent) {
+            Some(dir) => dir.to_owned(),
+            None => Self::config_dir(self.prefix.as_deref())?,
+        };
+        path.push(CACHE_DIR);
+        Ok(path)
+    }
+
     /// Load configuration from storage, if it exists.
     ///
     /// Tries to load configuration from storage, but If configuration does not exist, it will
@@ -121,26 +396,45 @@ impl Config {
         Ok(cfg)
     }
 
-    fn load_from(file: &PathBuf) -> Result<Config, ConfigError> {
+    /// Load configuration from an explicitly given file.
+    ///
+    /// The file has to exist, unless `create` is set, in which case a missing file is treated as
+    /// an empty configuration that gets created on [`Config::save`].
+    pub fn load_file(file: &Path, create: bool) -> Result<Config, ConfigError> {
+        if !create && !file.is_file() {
+            return Err(ConfigError::File {
+                file: file.to_owned(),
+                io: Some(std::io::ErrorKind::NotFound.into()),
+            });
+        }
+        let mut cfg = Self::load_from(file)?;
+        cfg.file = Some(file.to_owned());
+        Ok(cfg)
+    }
+
+    fn load_from(file: &Path) -> Result<Config, ConfigError> {
         let data = match fs::read_to_string(file) {
             Ok(d) => d,
             Err(io) => match io.kind() {
                 std::io::ErrorKind::NotFound => "".to_string(),
                 _ => {
                     return Err(ConfigError::File {
-                        file: file.clone(),
+                        file: file.to_owned(),
                         io: Some(io),
                     })?;
                 }
             },
         };
-        let config = toml::from_str(&data).unwrap();
+        let config = toml::from_str(&data)?;
         Ok(config)
     }
 
     /// Saves the current configuration to storage.
     pub fn save(&self) -> Result<(), ConfigError> {
-        let file = Self::config_file(self.prefix.as_deref())?;
+        let file = match &self.file {
+            Some(file) => file.clone(),
+            None => Self::config_file(self.prefix.as_deref())?,
+        };
         file.parent()
             .map(fs::create_dir_all)
             .transpose()
@@ -149,19 +443,212 @@ impl Config {
                 io: Some(io),
             })?;
         let data = toml::to_string(self)?;
-        fs::write(&file, data).map_err(|io| ConfigError::File { file, io: Some(io) })?;
+        write_private(&file, &data).map_err(|io| ConfigError::File { file, io: Some(io) })?;
         Ok(())
     }
 
+    /// Returns the calendar date of the given time in the configured timezone, falling back to
+    /// the local timezone if none is configured.
+    pub fn local_date<T: TimeZone>(&self, dt: &DateTime<T>) -> NaiveDate {
+        self.local_datetime(dt).date()
+    }
+
+    /// Returns the wall clock time of the given time in the configured timezone, falling back to
+    /// the local timezone if none is configured.
+    pub fn local_datetime<T: TimeZone>(&self, dt: &DateTime<T>) -> NaiveDateTime {
+        match self.timezone {
+            Some(tz) => dt.with_timezone(&tz).naive_local(),
+            None => dt.with_timezone(&Local).naive_local(),
+        }
+    }
+
+    /// Converts a wall clock time in the configured timezone, falling back to the local timezone
+    /// if none is configured, to UTC. Fails for times skipped by a DST change, as they don't
+    /// exist.
+    pub fn utc_datetime(&self, dt: &NaiveDateTime) -> Result<DateTime<Utc>> {
+        let utc = match self.timezone {
+            Some(tz) => tz
+                .from_local_datetime(dt)
+                .earliest()
+                .map(|dt| dt.with_timezone(&Utc)),
+            None => Local
+                .from_local_datetime(dt)
+                .earliest()
+                .map(|dt| dt.with_timezone(&Utc)),
+        };
+        utc.ok_or_else(|| {
+            eyre!("{dt} does not exist in the timezone, as it is skipped by a DST change")
+        })
+    }
+
+    /// Returns the task template with the given name.
+    pub fn template(&self, name: &str) -> Result<&TaskTemplate> {
+        find_template(&self.templates, name)
+    }
+
+    /// Returns the project template with the given name.
+    pub fn project_template(&self, name: &str) -> Result<&ProjectTemplate> {
+        find_template(&self.project_templates, name)
+    }
+
+    /// Returns true if hyperlinks should be rendered, which requires them to be enabled and
+    /// stdout to be a terminal.
+    pub fn use_hyperlinks(&self) -> bool {
+        self.hyperlinks && std::io::stdout().is_terminal()
+    }
+
+    /// Returns the window before a due date in which it is highlighted as coming up soon, if
+    /// configured.
+    pub fn due_soon(&self) -> Option<chrono::TimeDelta> {
+        self.due_soon_hours
+            .map(|hours| chrono::TimeDelta::hours(hours.into()))
+    }
+
+    /// Returns true if ASCII markers should be used instead of emoji.
+    pub fn use_plain(&self) -> bool {
+        self.plain || std::env::var_os(NO_EMOJI_ENV).is_some_and(|v| !v.is_empty())
+    }
+
+    /// Returns true if Markdown should be rendered, which requires it to be enabled and stdout to
+    /// be a terminal. Otherwise the raw text is shown.
+    pub fn use_markdown(&self) -> bool {
+        self.render_markdown && std::io::stdout().is_terminal()
+    }
+
     /// Returns a fully initialized gateway if the config is valid, or otherwise informs about
     /// potential issues with the configuration.
     pub fn gateway(&self) -> Result<Gateway> {
-        let token = self.token.as_deref().ok_or_else(|| {
-            eyre!("No token in config specified. Use `doist auth` to register your token.")
-        })?;
-        Ok(Gateway::new(
+        let token = self
+            .token_override
+            .as_deref()
+            .or(self.token.as_deref())
+            .ok_or_else(|| {
+                eyre!("No token in config specified. Use `doist auth` to register your token.")
+            })?;
+        let gw = Gateway::with_options(
             token,
             &self.url.clone().unwrap_or_else(|| default_url().unwrap()),
-        ))
+            self.gateway_options(),
+        );
+        if !self.disk_cache || self.no_cache {
+            return Ok(gw);
+        }
+        let ttl = self
+            .disk_cache_ttl_secs
+            .map(std::time::Duration::from_secs)
+            .unwrap_or(DiskCache::DEFAULT_TTL);
+        Ok(gw.with_disk_cache(DiskCache::new(&self.cache_dir()?, token, ttl)))
+    }
+
+    /// Returns the options of the gateway, using the defaults for anything that isn't configured.
+    fn gateway_options(&self) -> GatewayOptions {
+        let default = GatewayOptions::default();
+        GatewayOptions {
+            timeout: self
+                .request_timeout_secs
+                .map(std::time::Duration::from_secs)
+                .unwrap_or(default.timeout),
+            max_retries: self.max_retries.unwrap_or(default.max_retries),
+            dry_run: self.dry_run,
+        }
+    }
+}
+
+/// Writes the config so only the current user can read it, as it contains the API token.
+fn write_private(file: &Path, data: &str) -> std::io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut f = options.open(file)?;
+    // The mode only applies to new files, so configs written by older versions are fixed here.
+    #[cfg(unix)]
+    f.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))?;
+    f.write_all(data.as_bytes())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn gateway_options() {
+        assert_eq!(
+            Config::default().gateway_options(),
+            GatewayOptions::default()
+        );
+        let cfg: Config = toml::from_str("request_timeout_secs = 90\nmax_retries = 0").unwrap();
+        assert_eq!(
+            cfg.gateway_options(),
+            GatewayOptions {
+                timeout: std::time::Duration::from_secs(90),
+                max_retries: 0,
+                dry_run: false,
+            }
+        );
+    }
+
+    #[test]
+    fn completed_default_range() {
+        let cfg: Config = toml::from_str(r#"completed_default_range = "this-week""#).unwrap();
+        assert_eq!(cfg.completed_default_range, Some(CompletedRange::ThisWeek));
+        let cfg: Config = toml::from_str("").unwrap();
+        assert_eq!(cfg.completed_default_range, None);
+        assert!(toml::from_str::<Config>(r#"completed_default_range = "fortnight""#).is_err());
+    }
+
+    #[test]
+    fn theme() {
+        let cfg: Config = toml::from_str("").unwrap();
+        assert_eq!(cfg.theme, Theme::default());
+        assert_eq!(cfg.theme.style, ThemeStyle::Colorful);
+        let cfg: Config = toml::from_str(
+            r#"
+            [theme]
+            style = "plain"
+            prompt_prefix = ">"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(cfg.theme.style, ThemeStyle::Plain);
+        assert_eq!(cfg.theme.prompt_prefix.as_deref(), Some(">"));
+        assert_eq!(cfg.theme.highlight, None);
+    }
+
+    #[test]
+    fn templates() {
+        let cfg: Config = toml::from_str(
+            r#"
+            [templates.weekly-review]
+            content = "Weekly review"
+            labels = ["review"]
+            priority = 2
+            due = "every friday"
+            subtasks = ["Clear inbox", "Plan next week"]
+            "#,
+        )
+        .unwrap();
+        let template = cfg.template("weekly-review").unwrap();
+        assert_eq!(template.content.as_deref(), Some("Weekly review"));
+        assert_eq!(template.priority, Some(2));
+        assert_eq!(template.subtasks.len(), 2);
+        assert_eq!(template.project, None);
+        assert_eq!(template.due.as_deref(), Some("every friday"));
+        let err = cfg.template("daily").unwrap_err().to_string();
+        assert!(err.contains("available templates: weekly-review"));
+        assert!(cfg.project_template("weekly-review").is_err());
+    }
+
+    #[test]
+    fn load_file() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let file = dir.path().join("doist").join("custom.toml");
+        assert!(Config::load_file(&file, false).is_err());
+        let mut cfg = Config::load_file(&file, true).unwrap();
+        assert_eq!(cfg.default_filter, DEFAULT_FILTER);
+        cfg.token = Some("TOKEN".to_string());
+        cfg.save().unwrap();
+        let cfg = Config::load_file(&file, false).unwrap();
+        assert_eq!(cfg.token.as_deref(), Some("TOKEN"));
     }
 }
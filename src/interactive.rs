@@ -1,20 +1,58 @@
+use chrono::{Datelike, Days, Months, NaiveDate};
 use clap::{Arg, ArgAction, Args, FromArgMatches};
+use dialoguer::console::{Key, Term};
 use fuzzy_matcher::FuzzyMatcher;
 use fuzzy_matcher::skim::SkimMatcherV2;
-use owo_colors::OwoColorize;
-use std::iter;
+use owo_colors::{OwoColorize, Stream};
+use std::{io::IsTerminal, iter};
 
 use crate::api::rest::{
     Label, LabelID, Priority, Project, ProjectID, Section, SectionID, Task, TaskID,
 };
+use crate::config::{Theme, ThemeStyle, WeekStart};
 use color_eyre::{Result, eyre::WrapErr, eyre::eyre};
 
-#[derive(Debug, Default)]
+/// Returns the dialoguer theme for the configured prompt theme.
+pub fn dialoguer_theme(config: &Theme) -> Box<dyn dialoguer::theme::Theme> {
+    match config.style {
+        ThemeStyle::Plain => Box::new(dialoguer::theme::SimpleTheme),
+        ThemeStyle::Colorful => {
+            let mut theme = dialoguer::theme::ColorfulTheme {
+                fuzzy_match_highlight_style: config
+                    .highlight
+                    .as_deref()
+                    .map(dialoguer::console::Style::from_dotted_str)
+                    .unwrap_or_else(|| dialoguer::console::Style::new().yellow().bold())
+                    .for_stderr(),
+                active_item_style: dialoguer::console::Style::new().for_stderr(),
+                ..Default::default()
+            };
+            if let Some(prefix) = &config.prompt_prefix {
+                theme.prompt_prefix = dialoguer::console::style(prefix.clone())
+                    .for_stderr()
+                    .yellow();
+            }
+            Box::new(theme)
+        }
+    }
+}
+
+#[derive(Debug)]
 pub struct Selection<T: FuzzSelect> {
     name: Option<String>,
     id: Option<T::ID>,
 }
 
+// Not derived, as that would require the selected type itself to implement Default.
+impl<T: FuzzSelect> Default for Selection<T> {
+    fn default() -> Self {
+        Self {
+            name: None,
+            id: None,
+        }
+    }
+}
+
 macro_rules! selection {
     ($select_type:ty, $select_name:literal, $long:literal, $short:literal, $select_id:literal, $select_help:literal, $select_id_help:literal) => {
         impl FromArgMatches for Selection<$select_type> {
@@ -104,6 +142,10 @@ selection!(
 );
 
 impl<T: FuzzSelect + std::fmt::Display> Selection<T> {
+    /// Returns true if neither a name nor an ID was given.
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.id.is_none()
+    }
     pub fn optional<'a>(&self, items: &'a [T]) -> Result<Option<&'a T>> {
         let name = match &self.name {
             Some(name) => name,
@@ -116,31 +158,24 @@ impl<T: FuzzSelect + std::fmt::Display> Selection<T> {
         };
         Ok(Some(fuzz_select(items, name)?))
     }
-    pub fn mandatory<'a>(&self, items: &'a [T]) -> Result<&'a T> {
+    pub fn mandatory<'a>(&self, items: &'a [T], theme: &Theme) -> Result<&'a T> {
         let selection = Self::optional(self, items)?;
         match selection {
             Some(s) => Ok(s),
-            None => Ok(select("select item", items)?
+            None => Ok(select("select item", items, theme)?
                 .map(|i| &items[i])
                 .ok_or_else(|| eyre!("no selection made"))?),
         }
     }
 }
 
-pub fn select<T: ToString>(prompt: &str, items: &[T]) -> Result<Option<usize>> {
-    let result = dialoguer::FuzzySelect::with_theme(&dialoguer::theme::ColorfulTheme {
-        fuzzy_match_highlight_style: dialoguer::console::Style::new()
-            .for_stderr()
-            .yellow()
-            .bold(),
-        active_item_style: dialoguer::console::Style::new().for_stderr(),
-        ..Default::default()
-    })
-    .items(items)
-    .with_prompt(prompt)
-    .default(0)
-    .interact_opt()
-    .wrap_err("Unable to make a selection")?;
+pub fn select<T: ToString>(prompt: &str, items: &[T], theme: &Theme) -> Result<Option<usize>> {
+    let result = dialoguer::FuzzySelect::with_theme(dialoguer_theme(theme).as_ref())
+        .items(items)
+        .with_prompt(prompt)
+        .default(0)
+        .interact_opt()
+        .wrap_err("Unable to make a selection")?;
     Ok(result)
 }
 
@@ -208,8 +243,23 @@ impl FuzzSelect for Task {
     }
 }
 
-pub fn input_content(content: &str) -> Result<String> {
-    dialoguer::Input::new()
+/// Asks a yes/no question, defaulting to no. Fails instead of waiting for an answer if stdin isn't
+/// a terminal, so callers should hint at how to skip the question.
+pub fn confirm(prompt: &str, theme: &Theme) -> Result<bool> {
+    if !std::io::stdin().is_terminal() {
+        return Err(eyre!(
+            "unable to ask for confirmation, stdin is not a terminal"
+        ));
+    }
+    dialoguer::Confirm::with_theme(dialoguer_theme(theme).as_ref())
+        .with_prompt(prompt)
+        .default(false)
+        .interact()
+        .wrap_err("Unable to ask for confirmation")
+}
+
+pub fn input_content(content: &str, theme: &Theme) -> Result<String> {
+    dialoguer::Input::with_theme(dialoguer_theme(theme).as_ref())
         .with_prompt("Task Name")
         .allow_empty(false)
         .validate_with(|input: &String| -> Result<(), &str> {
@@ -224,8 +274,26 @@ pub fn input_content(content: &str) -> Result<String> {
         .wrap_err("No input made")
 }
 
-pub fn input_optional(prompt: &str, default: Option<String>) -> Result<Option<String>> {
-    match dialoguer::Input::<'_, String>::new()
+/// Returns the given text, or reads it from stdin if it is `-`, e.g. to pipe in multi-line
+/// Markdown.
+pub fn text_or_stdin(text: String) -> Result<String> {
+    if text != "-" {
+        return Ok(text);
+    }
+    let text = std::io::read_to_string(std::io::stdin()).wrap_err("unable to read stdin")?;
+    let text = text.trim_end();
+    if text.is_empty() {
+        return Err(eyre!("no text was given on stdin"));
+    }
+    Ok(text.to_owned())
+}
+
+pub fn input_optional(
+    prompt: &str,
+    default: Option<String>,
+    theme: &Theme,
+) -> Result<Option<String>> {
+    match dialoguer::Input::<'_, String>::with_theme(dialoguer_theme(theme).as_ref())
         .with_prompt(prompt)
         .allow_empty(true)
         .with_initial_text(default.unwrap_or("".to_owned()))
@@ -241,17 +309,22 @@ pub fn input_optional(prompt: &str, default: Option<String>) -> Result<Option<St
 pub fn input_project(
     projects: &[Project],
     sections: &[Section],
+    theme: &Theme,
 ) -> Result<Option<(ProjectID, Option<SectionID>)>> {
-    match select("Select Project", projects)? {
+    match select("Select Project", projects, theme)? {
         Some(p) => Ok(Some((
             projects[p].id.clone(),
-            input_section(&projects[p].id, sections)?,
+            input_section(&projects[p].id, sections, theme)?,
         ))),
         None => Ok(None),
     }
 }
 
-pub fn input_section(project: &ProjectID, sections: &[Section]) -> Result<Option<SectionID>> {
+pub fn input_section(
+    project: &ProjectID,
+    sections: &[Section],
+    theme: &Theme,
+) -> Result<Option<SectionID>> {
     let sections: Vec<_> = sections
         .iter()
         .filter(|s| s.project_id == *project)
@@ -262,28 +335,138 @@ pub fn input_section(project: &ProjectID, sections: &[Section]) -> Result<Option
     let section_names = iter::once("None".bold().to_string())
         .chain(sections.iter().map(|s| s.to_string()))
         .collect::<Vec<_>>();
-    match select("Select Section", &section_names)? {
+    match select("Select Section", &section_names, theme)? {
         Some(0) => Ok(None),
         Some(s) => Ok(Some(sections[s - 1].id.clone())),
         None => Ok(None),
     }
 }
 
-pub fn input_priority() -> Result<Option<Priority>> {
+pub fn input_priority(theme: &Theme) -> Result<Option<Priority>> {
     let items = [
         Priority::Normal,
         Priority::High,
         Priority::VeryHigh,
         Priority::Urgent,
     ];
-    let selection = select("Priority", &items)?;
+    let selection = select("Priority", &items, theme)?;
     Ok(selection.map(|s| items[s]))
 }
 
+/// Shows a month calendar on the terminal to pick a date with the arrow keys, starting at the
+/// given date. PageUp/PageDown (or `<`/`>`) switch months, Enter picks and Escape cancels.
+pub fn pick_date(
+    prompt: &str,
+    initial: NaiveDate,
+    week_start: WeekStart,
+) -> Result<Option<NaiveDate>> {
+    let term = Term::stderr();
+    let mut selected = initial;
+    let mut drawn = 0;
+    term.hide_cursor().wrap_err("unable to use terminal")?;
+    let result = loop {
+        term.clear_last_lines(drawn)
+            .wrap_err("unable to use terminal")?;
+        let mut lines = vec![format!(
+            "{} {}",
+            prompt.bold(),
+            "(arrows to move, PgUp/PgDn for months, Enter to pick, Esc to cancel)".dimmed()
+        )];
+        lines.extend(month_grid(selected, week_start));
+        for line in &lines {
+            term.write_line(line).wrap_err("unable to use terminal")?;
+        }
+        drawn = lines.len();
+        selected = match term.read_key().wrap_err("unable to read key")? {
+            Key::Enter => break Some(selected),
+            Key::Escape => break None,
+            Key::ArrowLeft => selected - Days::new(1),
+            Key::ArrowRight => selected + Days::new(1),
+            Key::ArrowUp => selected - Days::new(7),
+            Key::ArrowDown => selected + Days::new(7),
+            Key::PageUp | Key::Char('<') => selected - Months::new(1),
+            Key::PageDown | Key::Char('>') => selected + Months::new(1),
+            _ => selected,
+        };
+    };
+    term.clear_last_lines(drawn)
+        .wrap_err("unable to use terminal")?;
+    term.show_cursor().wrap_err("unable to use terminal")?;
+    Ok(result)
+}
+
+/// Renders the month of the selected date as a grid of weeks starting on the given day, with the
+/// selected day highlighted.
+fn month_grid(selected: NaiveDate, week_start: WeekStart) -> Vec<String> {
+    let first = selected.with_day(1).unwrap();
+    let days_in_month = (first + Months::new(1) - first).num_days() as u32;
+    let header = match week_start {
+        WeekStart::Monday => "Mo Tu We Th Fr Sa Su",
+        WeekStart::Sunday => "Su Mo Tu We Th Fr Sa",
+    };
+    let mut lines = vec![
+        format!("{:^20}", first.format("%B %Y").to_string()),
+        header.to_string(),
+    ];
+    let mut week = "   ".repeat((first - week_start.first_day(first)).num_days() as usize);
+    for day in 1..=days_in_month {
+        let text = format!("{day:>2}");
+        if day == selected.day() {
+            week.push_str(&format!(
+                "{}",
+                text.if_supports_color(Stream::Stderr, |t| t.reversed())
+            ));
+        } else {
+            week.push_str(&text);
+        }
+        let next = first.with_day(day).unwrap() + Days::new(1);
+        if week_start.first_day(next) == next {
+            lines.push(week.trim_end().to_string());
+            week = String::new();
+        } else {
+            week.push(' ');
+        }
+    }
+    if !week.trim().is_empty() {
+        lines.push(week.trim_end().to_string());
+    }
+    lines
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn renders_month_grid() {
+        let date = NaiveDate::from_ymd_opt(2025, 10, 8).unwrap();
+        let grid = month_grid(date, WeekStart::Monday);
+        assert_eq!(
+            grid,
+            vec![
+                "    October 2025    ",
+                "Mo Tu We Th Fr Sa Su",
+                "       1  2  3  4  5",
+                " 6  7  8  9 10 11 12",
+                "13 14 15 16 17 18 19",
+                "20 21 22 23 24 25 26",
+                "27 28 29 30 31",
+            ]
+        );
+        assert_eq!(
+            month_grid(date, WeekStart::Sunday),
+            vec![
+                "    October 2025    ",
+                "Su Mo Tu We Th Fr Sa",
+                "          1  2  3  4",
+                " 5  6  7  8  9 10 11",
+                "12 13 14 15 16 17 18",
+                "19 20 21 22 23 24 25",
+                "26 27 28 29 30 31",
+            ]
+        );
+    }
+
     type Selectable<'a> = (i32, &'a str);
 
     impl FuzzSelect for Selectable<'_> {
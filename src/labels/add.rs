@@ -1,4 +1,5 @@
 use crate::api::rest::{CreateLabel, Gateway};
+use crate::output::info;
 use color_eyre::Result;
 
 #[derive(clap::Parser, Debug)]
@@ -14,6 +15,6 @@ pub async fn add(params: Params, gw: &Gateway) -> Result<()> {
             ..Default::default()
         })
         .await?;
-    println!("created label: {}", &label);
+    info!("created label: {}", &label);
     Ok(())
 }
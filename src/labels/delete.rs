@@ -1,5 +1,8 @@
-use crate::api::rest::Gateway;
-use color_eyre::{Result, eyre::eyre};
+use crate::{api::rest::Gateway, config::Config, interactive, output::info};
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, eyre},
+};
 
 use super::{LabelSelect, label::Selection};
 
@@ -7,18 +10,37 @@ use super::{LabelSelect, label::Selection};
 pub struct Params {
     #[clap(flatten)]
     labels: LabelSelect,
+    /// Deletes the labels without asking for confirmation.
+    #[arg(long = "force")]
+    force: bool,
 }
 
-pub async fn delete(params: Params, gw: &Gateway) -> Result<()> {
+pub async fn delete(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
     let labels = params
         .labels
-        .labels(&gw.labels().await?, Selection::MustChoose)?;
+        .labels(&gw.labels().await?, Selection::MustChoose, &cfg.theme)?;
     if labels.is_empty() {
         return Err(eyre!("no labels selected"));
     }
+    if !params.force
+        && !interactive::confirm(
+            &format!(
+                "Delete labels {}?",
+                labels
+                    .iter()
+                    .map(|l| l.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            &cfg.theme,
+        )
+        .wrap_err("use --force to delete without confirmation")?
+    {
+        return Ok(());
+    }
     for label in labels {
         gw.delete_label(&label.id).await?;
-        println!("deleted label: {}", &label);
+        info!("deleted label: {}", &label);
     }
     Ok(())
 }
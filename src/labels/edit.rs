@@ -0,0 +1,56 @@
+use crate::api::rest::{Gateway, UpdateLabel};
+use crate::output::info;
+use color_eyre::{Result, eyre::eyre};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Current name of the label to edit, ignoring case.
+    label: String,
+    /// New name of the label.
+    #[arg(short = 'n', long = "name")]
+    name: Option<String>,
+    /// New color of the label icon, like "berry_red" or "blue".
+    #[arg(long = "color", conflicts_with = "rename_shared")]
+    color: Option<String>,
+    /// Marks the label as favorite.
+    #[arg(long = "favorite", conflicts_with_all = ["unfavorite", "rename_shared"])]
+    favorite: bool,
+    /// Removes the label from the favorites.
+    #[arg(long = "unfavorite", conflicts_with = "rename_shared")]
+    unfavorite: bool,
+    /// Renames the label on all tasks that use it, including labels shared by collaborators,
+    /// instead of only renaming the personal label.
+    #[arg(long = "rename-shared", requires = "name")]
+    rename_shared: bool,
+}
+
+pub async fn edit(params: Params, gw: &Gateway) -> Result<()> {
+    if params.rename_shared {
+        let name = params.name.unwrap_or_default();
+        gw.rename_shared_label(&params.label, &name).await?;
+        info!("renamed shared label @{} to @{}", params.label, name);
+        return Ok(());
+    }
+    let update = UpdateLabel {
+        name: params.name,
+        color: params.color,
+        is_favorite: match (params.favorite, params.unfavorite) {
+            (true, _) => Some(true),
+            (_, true) => Some(false),
+            _ => None,
+        },
+    };
+    if update.is_empty() {
+        return Err(eyre!(
+            "nothing to update, specify at least one of --name, --color, --favorite or --unfavorite"
+        ));
+    }
+    let labels = gw.labels().await?;
+    let label = labels
+        .iter()
+        .find(|l| l.name.eq_ignore_ascii_case(&params.label))
+        .ok_or_else(|| eyre!("could not find label with name {}", params.label))?;
+    let label = gw.update_label(&label.id, &update).await?;
+    info!("updated label: {}", &label);
+    Ok(())
+}
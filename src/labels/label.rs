@@ -1,8 +1,10 @@
 use std::collections::HashMap;
 
 use crate::{
-    api::rest::{FullLabel, Label},
+    api::rest::{CreateLabel, FullLabel, Gateway, Label},
+    config::Theme,
     interactive,
+    output::info,
 };
 use color_eyre::{Result, eyre::eyre};
 
@@ -29,7 +31,36 @@ pub enum Selection {
 }
 
 impl LabelSelect {
-    pub fn labels(&self, labels: &[Label], selection: Selection) -> Result<Vec<Label>> {
+    /// Creates a selection of the labels with the given names.
+    pub fn from_names(names: &[String]) -> Self {
+        Self {
+            label_names: (!names.is_empty()).then(|| names.to_vec()),
+            label_ids: None,
+        }
+    }
+
+    /// Returns true if no labels were given.
+    pub fn is_empty(&self) -> bool {
+        self.label_names.is_none() && self.label_ids.is_none()
+    }
+
+    /// Returns the label names given on the command line that aren't exactly the name of one of
+    /// the labels, ignoring case.
+    pub fn missing_names(&self, labels: &[Label]) -> Vec<String> {
+        self.label_names
+            .iter()
+            .flatten()
+            .filter(|name| find_label(labels, label_name(name)).is_none())
+            .map(|name| label_name(name).to_string())
+            .collect()
+    }
+
+    pub fn labels(
+        &self,
+        labels: &[Label],
+        selection: Selection,
+        theme: &Theme,
+    ) -> Result<Vec<Label>> {
         let label_ids = self.label_ids.clone().unwrap_or_default();
         let mut all_labels = labels
             .iter()
@@ -40,17 +71,15 @@ impl LabelSelect {
             .map(ToOwned::to_owned)
             .collect::<Vec<_>>();
 
-        let mut found_labels = label_ids
-            .into_iter()
-            .map(|l| {
-                all_labels
-                    .get(&l)
-                    .ok_or_else(|| eyre!("could not find label with id {}", l))
-            })
-            .collect::<Result<Vec<_>>>()?
-            .iter()
-            .map(|&l| l.to_owned())
-            .collect::<Vec<_>>();
+        let mut found_labels: Vec<Label> = Vec::new();
+        for id in label_ids {
+            let label = all_labels
+                .get(&id)
+                .ok_or_else(|| eyre!("could not find label with id {}", id))?;
+            if !found_labels.iter().any(|l| l.id == label.id) {
+                found_labels.push(label.to_owned());
+            }
+        }
 
         if self.label_names.is_none() {
             if found_labels.is_empty() && selection == Selection::MustChoose {
@@ -60,6 +89,7 @@ impl LabelSelect {
                             &label_list[interactive::select(
                                 "Select label",
                                 &label_list.iter().map(FullLabel).collect::<Vec<_>>(),
+                                theme,
                             )?
                             .ok_or_else(|| eyre!("no labels selected"))?]
                             .id,
@@ -70,17 +100,81 @@ impl LabelSelect {
             return Ok(found_labels);
         }
 
-        found_labels.extend(
-            self.label_names
-                .as_ref()
-                .unwrap()
-                .iter()
-                .map(|label| {
-                    interactive::fuzz_select(&label_list, label)
-                        .map(|label| all_labels.remove(&label.id).unwrap())
-                })
-                .collect::<Result<Vec<_>>>()?,
-        );
+        for name in self.label_names.as_ref().unwrap() {
+            // An exact match wins, so a label created for a missing name is always the one used.
+            let label = match find_label(&label_list, label_name(name)) {
+                Some(label) => label,
+                None => interactive::fuzz_select(&label_list, label_name(name))
+                    .map_err(|_| eyre!("could not find label with name {}", name))?,
+            };
+            if !found_labels.iter().any(|l| l.id == label.id) {
+                found_labels.push(label.to_owned());
+            }
+        }
         Ok(found_labels)
     }
 }
+
+/// Finds the label with the given name, ignoring case.
+pub fn find_label<'a>(labels: &'a [Label], name: &str) -> Option<&'a Label> {
+    labels
+        .iter()
+        .find(|l| l.name.to_lowercase() == name.to_lowercase())
+}
+
+/// Strips the `@` that is used to write labels in the Todoist UI.
+fn label_name(name: &str) -> &str {
+    name.strip_prefix('@').unwrap_or(name)
+}
+
+/// Creates a label for each of the given names and adds them to the list of labels.
+pub async fn create_labels(names: &[String], labels: &mut Vec<Label>, gw: &Gateway) -> Result<()> {
+    for name in names {
+        let label = gw
+            .create_label(&CreateLabel {
+                name: name.clone(),
+                ..Default::default()
+            })
+            .await?;
+        info!("created label: {}", &label);
+        labels.push(label);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn label(name: &str) -> Label {
+        Label {
+            id: name.to_string(),
+            name: name.to_string(),
+            color: "red".to_string(),
+            order: 0,
+            is_favorite: false,
+        }
+    }
+
+    #[test]
+    fn missing_names_match_exactly() {
+        let labels = vec![label("Urgent-Work"), label("Home")];
+        let select = LabelSelect::from_names(&[
+            "urgent".to_string(),
+            "@home".to_string(),
+            "HOME".to_string(),
+        ]);
+        assert_eq!(select.missing_names(&labels), ["urgent"]);
+    }
+
+    #[test]
+    fn prefers_exact_name() {
+        let labels = vec![label("urgent-work"), label("urgent")];
+        let select = LabelSelect::from_names(&["Urgent".to_string()]);
+        let found = select
+            .labels(&labels, Selection::AllowEmpty, &Theme::default())
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name, "urgent");
+    }
+}
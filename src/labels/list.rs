@@ -1,13 +1,75 @@
-use crate::api::rest::{FullLabel, Gateway};
-use color_eyre::Result;
+use std::collections::HashMap;
+
+use crate::api::rest::{FullLabel, Gateway, Label};
+use crate::output::info;
+use color_eyre::{Result, eyre::eyre};
+use owo_colors::{OwoColorize, Stream};
 
 #[derive(clap::Parser, Debug)]
-pub struct Params {}
+pub struct Params {
+    /// Also shows on how many active tasks each label is used.
+    #[arg(long = "counts")]
+    counts: bool,
+    /// How to sort the labels. Sorting by count requires --counts.
+    #[arg(value_enum, long = "sort", default_value_t = SortBy::Name)]
+    sort: SortBy,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortBy {
+    /// Sort alphabetically by name.
+    Name,
+    /// Sort by the amount of tasks using the label, most used first.
+    Count,
+}
 
-pub async fn list(_params: Params, gw: &Gateway) -> Result<()> {
-    let labels = gw.labels().await?;
-    for label in labels {
-        println!("{}", &FullLabel(&label));
+/// Lists all labels sorted by name, with favorites marked.
+pub async fn list(params: Params, gw: &Gateway) -> Result<()> {
+    if params.sort == SortBy::Count && !params.counts {
+        return Err(eyre!("--sort count requires --counts"));
+    }
+    let mut labels = gw.labels().await?;
+    labels.sort_by(|a, b| a.name.cmp(&b.name));
+    if !params.counts {
+        for label in labels {
+            info!("{}", LabelLine(&label, None));
+        }
+        return Ok(());
+    }
+    let tasks = gw.tasks(None).await?;
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for label in tasks.iter().flat_map(|t| &t.labels) {
+        *counts.entry(label).or_default() += 1;
+    }
+    let count = |label: &Label| counts.get(label.name.as_str()).copied().unwrap_or_default();
+    if params.sort == SortBy::Count {
+        // Stable, so labels with the same count stay sorted by name.
+        labels.sort_by_key(|l| std::cmp::Reverse(count(l)));
+    }
+    for label in &labels {
+        info!("{}", LabelLine(label, Some(count(label))));
     }
     Ok(())
 }
+
+/// Shows a label with its ID, a star if it's a favorite and optionally its task count.
+struct LabelLine<'a>(&'a Label, Option<usize>);
+
+impl std::fmt::Display for LabelLine<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let LabelLine(label, count) = self;
+        write!(f, "{}", FullLabel(label))?;
+        if label.is_favorite {
+            write!(
+                f,
+                " {}",
+                "★".if_supports_color(Stream::Stdout, |text| text.yellow())
+            )?;
+        }
+        match count {
+            Some(1) => write!(f, " (1 task)"),
+            Some(count) => write!(f, " ({count} tasks)"),
+            None => Ok(()),
+        }
+    }
+}
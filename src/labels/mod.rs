@@ -1,6 +1,8 @@
 pub mod add;
 pub mod delete;
+pub mod edit;
 mod label;
 /// Controls things that work with [`crate::api::rest::Label`]s.
 pub mod list;
-pub use label::{LabelSelect, Selection};
+pub mod prune;
+pub use label::{LabelSelect, Selection, create_labels, find_label};
@@ -0,0 +1,92 @@
+use std::collections::HashSet;
+
+use color_eyre::{Result, eyre::eyre};
+use owo_colors::{OwoColorize, Stream};
+
+use crate::api::rest::{Gateway, Task, UpdateTask};
+use crate::output::info;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Removes the labels that no longer exist from the affected tasks.
+    #[arg(long = "fix")]
+    fix: bool,
+}
+
+/// Finds tasks that still carry labels that were deleted, as tasks only reference labels by name.
+/// Labels shared by collaborators count as known, even though they aren't personal labels.
+pub async fn prune(params: Params, gw: &Gateway) -> Result<()> {
+    let (tasks, labels, shared) =
+        tokio::try_join!(gw.tasks(None), gw.labels(), gw.shared_labels())?;
+    let known = labels
+        .iter()
+        .map(|l| l.name.as_str())
+        .chain(shared.iter().map(String::as_str))
+        .collect::<HashSet<_>>();
+    let affected = tasks
+        .iter()
+        .filter(|t| t.labels.iter().any(|l| !known.contains(l.as_str())))
+        .collect::<Vec<_>>();
+    if affected.is_empty() {
+        info!("No tasks with unknown labels");
+        return Ok(());
+    }
+    for task in &affected {
+        let dangling = task
+            .labels
+            .iter()
+            .filter(|l| !known.contains(l.as_str()))
+            .map(|l| format!("@{l}"))
+            .collect::<Vec<_>>();
+        info!(
+            "{} {}: {}",
+            task.id
+                .if_supports_color(Stream::Stdout, |text| text.bright_yellow()),
+            task.content,
+            dangling.join(", ")
+        );
+    }
+    if !params.fix {
+        info!(
+            "{} tasks have unknown labels, use --fix to remove them",
+            affected.len()
+        );
+        return Ok(());
+    }
+    let results = futures::future::join_all(affected.iter().map(|task| {
+        let update = UpdateTask {
+            labels: Some(kept_labels(task, &known)),
+            ..Default::default()
+        };
+        async move { gw.update(&task.id, &update).await }
+    }))
+    .await;
+    let mut failed = 0;
+    for (task, result) in affected.iter().zip(results) {
+        if let Err(e) = result {
+            failed += 1;
+            eprintln!("failed to update task {}: {e:#}", task.id);
+        }
+    }
+    info!(
+        "removed unknown labels from {} of {} tasks",
+        affected.len() - failed,
+        affected.len()
+    );
+    if failed > 0 {
+        return Err(eyre!(
+            "{failed} of {} tasks could not be updated",
+            affected.len()
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the labels of the task that still exist.
+fn kept_labels(task: &Task, known: &HashSet<&str>) -> Vec<String> {
+    task.labels
+        .iter()
+        .filter(|l| known.contains(l.as_str()))
+        .cloned()
+        .collect()
+}
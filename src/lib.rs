@@ -10,14 +10,18 @@
 //! ```
 #![warn(missing_docs)]
 pub mod api;
+mod cache;
 mod command;
 mod comments;
 pub mod config;
 mod interactive;
 mod labels;
+mod output;
 mod projects;
 mod sections;
 mod tasks;
+mod templates;
+mod user;
 
 #[doc(hidden)]
 pub use command::Arguments;
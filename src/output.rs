@@ -0,0 +1,56 @@
+//! Controls how much the CLI prints. Informational output goes through [`info`], so it can be
+//! silenced with `--quiet`, while `--verbose` additionally shows the requests sent to the API.
+use std::sync::OnceLock;
+
+/// How much output is shown, set once on startup.
+static LEVEL: OnceLock<Level> = OnceLock::new();
+
+/// The amount of output the user asked for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Level {
+    /// Only errors are shown.
+    Quiet,
+    /// Regular output.
+    #[default]
+    Normal,
+    /// Regular output and the requests sent to the API.
+    Verbose,
+}
+
+/// Sets the output level for the rest of the run. Only the first call has an effect.
+pub fn set_level(level: Level) {
+    let _ = LEVEL.set(level);
+}
+
+/// Returns the configured output level.
+pub fn level() -> Level {
+    LEVEL.get().copied().unwrap_or_default()
+}
+
+/// Returns where regular output is written to, which discards everything with `--quiet`.
+pub fn stdout() -> Box<dyn std::io::Write> {
+    match level() {
+        Level::Quiet => Box::new(std::io::sink()),
+        Level::Normal | Level::Verbose => Box::new(std::io::stdout()),
+    }
+}
+
+/// Prints a line to stdout, unless `--quiet` is given. Takes the same arguments as [`println`].
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if $crate::output::level() != $crate::output::Level::Quiet {
+            println!($($arg)*);
+        }
+    };
+}
+pub(crate) use info;
+
+/// Prints a line to stderr if `--verbose` is given. Takes the same arguments as [`eprintln`].
+macro_rules! verbose {
+    ($($arg:tt)*) => {
+        if $crate::output::level() == $crate::output::Level::Verbose {
+            eprintln!($($arg)*);
+        }
+    };
+}
+pub(crate) use verbose;
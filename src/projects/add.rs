@@ -1,19 +1,72 @@
-use crate::api::rest::{CreateProject, Gateway};
-use color_eyre::Result;
+use crate::{
+    api::{
+        rest::{CreateProject, CreateSection, CreateTask, Gateway, TableTask},
+        tree::Tree,
+    },
+    config::Config,
+    output::info,
+};
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, eyre},
+};
 
 #[derive(clap::Parser, Debug)]
 pub struct Params {
-    /// Name of the project to create.
-    name: String,
+    /// Name of the project to create. Can be left out if the template has a name.
+    #[arg(required_unless_present = "template")]
+    name: Option<String>,
+    /// Creates the project with the sections and tasks of the project template with the given
+    /// name from the config.
+    #[arg(long = "template")]
+    template: Option<String>,
 }
 
-pub async fn add(params: Params, gw: &Gateway) -> Result<()> {
+pub async fn add(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let template = params
+        .template
+        .as_deref()
+        .map(|name| cfg.project_template(name))
+        .transpose()?;
+    let name = params
+        .name
+        .or_else(|| template.and_then(|t| t.name.clone()))
+        .ok_or_else(|| eyre!("the template has no project name, it has to be given"))?;
     let project = gw
         .create_project(&CreateProject {
-            name: params.name,
+            name,
+            color: template.and_then(|t| t.color.clone()),
             ..Default::default()
         })
         .await?;
-    println!("created project: {}", &project);
+    info!("created project: {}", &project);
+    let Some(template) = template else {
+        return Ok(());
+    };
+    for (order, name) in template.sections.iter().enumerate() {
+        let section = gw
+            .create_section(&CreateSection {
+                name: name.clone(),
+                project_id: project.id.clone(),
+                order: Some(order as isize + 1),
+            })
+            .await
+            .wrap_err_with(|| format!("unable to create section '{name}' in the new project"))?;
+        info!("created section: {}", &section);
+    }
+    for content in &template.tasks {
+        let task = gw
+            .create(&CreateTask {
+                content: content.clone(),
+                project_id: Some(project.id.clone()),
+                ..Default::default()
+            })
+            .await
+            .wrap_err_with(|| format!("unable to create task '{content}' in the new project"))?;
+        info!(
+            "created task: {}",
+            TableTask::from_task(&Tree::new(task), cfg)
+        );
+    }
     Ok(())
 }
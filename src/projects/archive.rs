@@ -0,0 +1,22 @@
+use crate::{
+    api::rest::{Gateway, Project},
+    config::Config,
+    interactive,
+    output::info,
+};
+use color_eyre::Result;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    #[clap(flatten)]
+    project: interactive::Selection<Project>,
+}
+
+/// Archives a project, so it's hidden from the project list.
+pub async fn archive(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let projects = gw.projects().await?;
+    let project = params.project.mandatory(&projects, &cfg.theme)?;
+    gw.archive_project(&project.id).await?;
+    info!("archived project: {}", &project);
+    Ok(())
+}
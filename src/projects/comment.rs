@@ -2,29 +2,32 @@ use color_eyre::Result;
 
 use crate::{
     api::rest::{CreateComment, FullComment, Gateway, Project, ThreadID},
+    config::Config,
     interactive,
+    output::info,
 };
 
 #[derive(clap::Parser, Debug)]
 pub struct Params {
-    /// The text of the comment. Supports Markdown.
+    /// The text of the comment. Supports Markdown. Use `-` to read it from stdin.
     content: String,
     #[clap(flatten)]
     project: interactive::Selection<Project>,
 }
 
 /// Creates a new comment for a project.
-pub async fn comment(params: Params, gw: &Gateway) -> Result<()> {
+pub async fn comment(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let content = interactive::text_or_stdin(params.content)?;
     let projects = gw.projects().await?;
-    let project = params.project.mandatory(&projects)?;
+    let project = params.project.mandatory(&projects, &cfg.theme)?;
     let comment = gw
         .create_comment(&CreateComment {
             thread: ThreadID::Project {
                 project_id: project.id.clone(),
             },
-            content: params.content,
+            content,
         })
         .await?;
-    println!("created comment: {}", FullComment(&comment));
+    info!("created comment: {}", FullComment(&comment));
     Ok(())
 }
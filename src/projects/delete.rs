@@ -1,19 +1,30 @@
 use crate::{
     api::rest::{Gateway, Project},
+    config::Config,
     interactive,
+    output::info,
 };
-use color_eyre::Result;
+use color_eyre::{Result, eyre::WrapErr};
 
 #[derive(clap::Parser, Debug)]
 pub struct Params {
     #[clap(flatten)]
     project: interactive::Selection<Project>,
+    /// Deletes the project without asking for confirmation.
+    #[arg(long = "force")]
+    force: bool,
 }
 
-pub async fn delete(params: Params, gw: &Gateway) -> Result<()> {
+pub async fn delete(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
     let projects = gw.projects().await?;
-    let project = params.project.mandatory(&projects)?;
+    let project = params.project.mandatory(&projects, &cfg.theme)?;
+    if !params.force
+        && !interactive::confirm(&format!("Delete project {}?", project.name), &cfg.theme)
+            .wrap_err("use --force to delete without confirmation")?
+    {
+        return Ok(());
+    }
     gw.delete_project(&project.id).await?;
-    println!("deleted project: {}", &project);
+    info!("deleted project: {}", &project);
     Ok(())
 }
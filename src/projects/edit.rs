@@ -0,0 +1,69 @@
+use crate::{
+    api::rest::{Gateway, Project, UpdateProject, ViewStyle},
+    config::Config,
+    interactive,
+    output::info,
+};
+use color_eyre::{Result, eyre::eyre};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    #[clap(flatten)]
+    project: interactive::Selection<Project>,
+    /// New name of the project.
+    #[arg(short = 'n', long = "name")]
+    name: Option<String>,
+    /// New color of the project icon, like "berry_red" or "blue".
+    #[arg(long = "color")]
+    color: Option<String>,
+    /// Marks the project as favorite.
+    #[arg(long = "favorite", conflicts_with = "unfavorite")]
+    favorite: bool,
+    /// Removes the project from the favorites.
+    #[arg(long = "unfavorite")]
+    unfavorite: bool,
+    /// Sets how the project is displayed in the Todoist clients.
+    #[arg(value_enum, long = "view-style")]
+    view_style: Option<ViewStyleArg>,
+}
+
+/// Maps view styles from arguments to API view styles.
+#[derive(clap::ValueEnum, Debug, Copy, Clone)]
+enum ViewStyleArg {
+    List,
+    Board,
+    Calendar,
+}
+
+impl From<ViewStyleArg> for ViewStyle {
+    fn from(v: ViewStyleArg) -> Self {
+        match v {
+            ViewStyleArg::List => ViewStyle::List,
+            ViewStyleArg::Board => ViewStyle::Board,
+            ViewStyleArg::Calendar => ViewStyle::Calendar,
+        }
+    }
+}
+
+pub async fn edit(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let update = UpdateProject {
+        name: params.name,
+        color: params.color,
+        is_favorite: match (params.favorite, params.unfavorite) {
+            (true, _) => Some(true),
+            (_, true) => Some(false),
+            _ => None,
+        },
+        view_style: params.view_style.map(Into::into),
+    };
+    if update.is_empty() {
+        return Err(eyre!(
+            "nothing to update, specify at least one of --name, --color, --favorite, --unfavorite or --view-style"
+        ));
+    }
+    let projects = gw.projects().await?;
+    let project = params.project.mandatory(&projects, &cfg.theme)?;
+    let project = gw.update_project(&project.id, &update).await?;
+    info!("updated project: {}", &project);
+    Ok(())
+}
@@ -1,6 +1,14 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, io::Write};
 
-use crate::api::rest::{Gateway, Project, Task};
+use crate::{
+    api::{
+        rest::{Gateway, Project, Task, tree_prefix},
+        tree::Tree,
+    },
+    config::{Config, TreeStyle},
+    output::{self, info},
+    tasks::list::Format,
+};
 use color_eyre::{Result, eyre::eyre};
 
 #[derive(clap::Parser, Debug)]
@@ -8,28 +16,80 @@ pub struct Params {
     /// If specified, will only show projects whose tasks are passing this filter.
     #[arg(short = 'f', long = "filter")]
     pub filter: Option<String>,
+
+    /// Output format. JSON prints the projects as a flat array, subprojects reference their
+    /// parent with `parent_id`.
+    #[arg(long = "format", value_enum, default_value_t = Format::Table)]
+    pub format: Format,
+
+    /// Also lists archived projects, which are hidden by default.
+    #[arg(long = "include-archived")]
+    pub include_archived: bool,
 }
 
 /// Lists available projects.
-pub async fn list(params: Params, gw: &Gateway) -> Result<()> {
-    let projects = gw.projects().await?;
+pub async fn list(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let mut projects = gw.projects().await?;
+    if params.include_archived {
+        projects.extend(gw.archived_projects().await?);
+    } else {
+        projects.retain(|p| !p.is_archived);
+    }
     if let Some(filter) = params.filter {
         let tasks = gw.tasks(Some(&filter)).await?;
         if tasks.is_empty() {
             return Err(eyre!("no tasks match the given filter"))?;
         }
         let projects = filtered_projects(&projects, &tasks)?;
+        if params.format == Format::Json {
+            let projects = projects.iter().map(|(p, _)| p).collect::<Vec<_>>();
+            info!("{}", serde_json::to_string_pretty(&projects)?);
+            return Ok(());
+        }
         for (project, tasks) in projects.iter() {
-            println!("{} (Tasks: {})", &project, tasks);
+            info!("{} (Tasks: {})", &project, tasks);
         }
         return Ok(());
     }
-    for project in projects.iter() {
-        println!("{}", &project);
+    if params.format == Format::Json {
+        info!("{}", serde_json::to_string_pretty(&projects)?);
+        return Ok(());
     }
+    let projects = Tree::from_items(projects)?;
+    write!(
+        output::stdout(),
+        "{}",
+        project_tree(&projects, cfg.tree_style)
+    )?;
     Ok(())
 }
 
+/// Draws the projects with their subprojects indented below them.
+fn project_tree(projects: &[Tree<Project>], style: TreeStyle) -> String {
+    fn draw(
+        projects: &[Tree<Project>],
+        style: TreeStyle,
+        branches: &mut Vec<bool>,
+        out: &mut String,
+    ) {
+        for (i, project) in projects.iter().enumerate() {
+            branches.push(i == projects.len() - 1);
+            // The top level isn't drawn as a branch.
+            out.push_str(&tree_prefix(style, &branches[1..]));
+            out.push_str(&project.item.to_string());
+            if project.is_archived {
+                out.push_str(" (archived)");
+            }
+            out.push('\n');
+            draw(&project.subitems, style, branches, out);
+            branches.pop();
+        }
+    }
+    let mut out = String::new();
+    draw(projects, style, &mut vec![], &mut out);
+    out
+}
+
 fn filtered_projects<'a>(
     projects: &'a [Project],
     tasks: &'_ [Task],
@@ -50,6 +110,29 @@ mod test {
     use super::*;
     use crate::api::rest::{Project, Task};
 
+    #[test]
+    fn draws_project_tree() {
+        let mut child = Project::new("2", "child");
+        child.parent_id = Some("1".to_string());
+        let mut grandchild = Project::new("3", "grandchild");
+        grandchild.parent_id = Some("2".to_string());
+        let mut second = Project::new("4", "second");
+        second.parent_id = Some("1".to_string());
+        second.is_archived = true;
+        let projects = Tree::from_items(vec![
+            Project::new("1", "root"),
+            child,
+            grandchild,
+            second,
+            Project::new("5", "other"),
+        ])
+        .unwrap();
+        assert_eq!(
+            project_tree(&projects, TreeStyle::Unicode),
+            "1 root\n├─ 2 child\n│  └─ 3 grandchild\n└─ 4 second (archived)\n5 other\n"
+        );
+    }
+
     #[tokio::test]
     async fn filter_projects() {
         let ps = vec![
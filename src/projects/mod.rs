@@ -1,7 +1,11 @@
 //! Controls things that work with [`crate::api::rest::Project`]s.
 pub mod add;
+pub mod archive;
 pub mod comment;
 pub mod delete;
+pub mod edit;
 pub mod list;
 pub(crate) mod state;
+pub mod stats;
+pub mod unarchive;
 pub mod view;
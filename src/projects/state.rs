@@ -5,6 +5,7 @@ use crate::{
         rest::{Gateway, Project, ProjectID, Section, SectionID},
         tree::{Tree, TreeFlattenExt},
     },
+    config::Theme,
     interactive,
 };
 use color_eyre::{
@@ -25,7 +26,7 @@ impl State {
         Ok(State { projects, sections })
     }
 
-    pub fn _select_project(&self) -> Result<Option<&Tree<Project>>> {
+    pub fn _select_project(&self, theme: &Theme) -> Result<Option<&Tree<Project>>> {
         if self.projects.is_empty() {
             return Err(eyre!("no projects were found"));
         }
@@ -33,6 +34,7 @@ impl State {
         let result = interactive::select(
             "Select project",
             &items.iter().map(|i| &i.item).collect::<Vec<_>>(),
+            theme,
         )?;
         Ok(result.map(|index| items[index]))
     }
@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use color_eyre::{Result, eyre::WrapErr};
+
+use crate::{
+    api::rest::{
+        COMPLETED_TASKS_MAX_LIMIT, CompletedTasksByCompletionDateParams, Gateway, ProjectID, Task,
+    },
+    config::{CompletedRange, Config},
+    output::info,
+    tasks::completed::range_for,
+};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Which column the projects are sorted by, highest first.
+    #[arg(long = "sort", value_enum, default_value_t = SortBy::Active)]
+    sort: SortBy,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortBy {
+    /// Sort by the amount of active tasks.
+    Active,
+    /// Sort by the amount of overdue tasks.
+    Overdue,
+}
+
+/// Task counts of a single project.
+#[derive(Debug, Default, PartialEq)]
+struct ProjectStats {
+    active: usize,
+    overdue: usize,
+    completed: usize,
+    /// Sum of the ages of all active tasks in days, used for the average.
+    total_age: i64,
+}
+
+impl ProjectStats {
+    fn average_age(&self) -> Option<i64> {
+        (self.active > 0).then(|| self.total_age / self.active as i64)
+    }
+}
+
+/// Shows an overview of the active, overdue and recently completed tasks of each project.
+pub async fn stats(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let now = cfg.override_time.unwrap_or_else(Utc::now);
+    let (since, until) = range_for(CompletedRange::ThisWeek, cfg.local_date(&now), cfg)?;
+    let (projects, tasks) = tokio::join!(gw.projects(), gw.tasks(Some("all")));
+    let (projects, tasks) = (projects?, tasks?);
+
+    let mut completed = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let response = gw
+            .completed_tasks_by_completion_date(CompletedTasksByCompletionDateParams {
+                since: &since,
+                until: &until,
+                workspace_id: None,
+                project_id: None,
+                section_id: None,
+                parent_id: None,
+                filter_query: None,
+                cursor: cursor.as_deref(),
+                limit: Some(COMPLETED_TASKS_MAX_LIMIT),
+            })
+            .await
+            .wrap_err("failed to fetch completed tasks")?;
+        completed.extend(response.items);
+        cursor = response.next_cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+
+    let stats = project_stats(&tasks, &completed, &now, cfg.local_date(&now));
+    let mut rows = projects
+        .iter()
+        .map(|p| match stats.get(&p.id) {
+            Some(s) => (p, (s.active, s.overdue, s.completed, s.average_age())),
+            None => (p, (0, 0, 0, None)),
+        })
+        .collect::<Vec<_>>();
+    rows.sort_by(|(_, a), (_, b)| match params.sort {
+        SortBy::Active => b.0.cmp(&a.0).then(b.1.cmp(&a.1)),
+        SortBy::Overdue => b.1.cmp(&a.1).then(b.0.cmp(&a.0)),
+    });
+
+    let width = projects
+        .iter()
+        .map(|p| p.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("Project".len());
+    info!(
+        "{:width$}  {:>6}  {:>7}  {:>14}  {:>8}",
+        "Project", "Active", "Overdue", "Done this week", "Avg age"
+    );
+    for (project, (active, overdue, completed, age)) in rows {
+        let age = age
+            .map(|a| format!("{a}d"))
+            .unwrap_or_else(|| "-".to_string());
+        info!(
+            "{:width$}  {:>6}  {:>7}  {:>14}  {:>8}",
+            project.name, active, overdue, completed, age
+        );
+    }
+    Ok(())
+}
+
+/// Aggregates the active and completed tasks per project.
+fn project_stats(
+    tasks: &[Task],
+    completed: &[Task],
+    now: &DateTime<Utc>,
+    today: NaiveDate,
+) -> HashMap<ProjectID, ProjectStats> {
+    let mut stats: HashMap<ProjectID, ProjectStats> = HashMap::new();
+    for task in tasks {
+        let entry = stats.entry(task.project_id.clone()).or_default();
+        entry.active += 1;
+        entry.total_age += (*now - task.created_at).num_days();
+        if task.is_overdue(now, today) {
+            entry.overdue += 1;
+        }
+    }
+    for task in completed {
+        stats.entry(task.project_id.clone()).or_default().completed += 1;
+    }
+    stats
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::api::rest::DueDate;
+
+    fn task(id: &str, project_id: &str, due: Option<&str>, age_days: i64) -> Task {
+        let now = now();
+        let mut task = Task::new(id, id);
+        task.project_id = project_id.to_string();
+        task.created_at = now - chrono::Duration::days(age_days);
+        task.due = due.map(DueDate::on);
+        task
+    }
+
+    fn now() -> DateTime<Utc> {
+        "2025-10-08T12:00:00Z".parse().unwrap()
+    }
+
+    #[test]
+    fn aggregates_per_project() {
+        let today = NaiveDate::from_ymd_opt(2025, 10, 8).unwrap();
+        let tasks = vec![
+            task("1", "a", Some("2025-10-01"), 10),
+            task("2", "a", Some("2025-10-08"), 4),
+            task("3", "a", Some("2025-10-08T09:00:00Z"), 1),
+            task("4", "b", None, 3),
+        ];
+        let completed = vec![task("5", "b", None, 0), task("6", "c", None, 0)];
+        let stats = project_stats(&tasks, &completed, &now(), today);
+        assert_eq!(
+            stats["a"],
+            ProjectStats {
+                active: 3,
+                overdue: 2,
+                completed: 0,
+                total_age: 15,
+            }
+        );
+        assert_eq!(stats["a"].average_age(), Some(5));
+        assert_eq!(stats["b"].active, 1);
+        assert_eq!(stats["b"].completed, 1);
+        assert_eq!(stats["c"].average_age(), None);
+    }
+}
@@ -0,0 +1,23 @@
+use crate::{
+    api::rest::{Gateway, Project},
+    config::Config,
+    interactive,
+    output::info,
+};
+use color_eyre::Result;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Selects from the archived projects.
+    #[clap(flatten)]
+    project: interactive::Selection<Project>,
+}
+
+/// Restores an archived project.
+pub async fn unarchive(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let projects = gw.archived_projects().await?;
+    let project = params.project.mandatory(&projects, &cfg.theme)?;
+    gw.unarchive_project(&project.id).await?;
+    info!("unarchived project: {}", &project);
+    Ok(())
+}
@@ -1,6 +1,9 @@
 use crate::{
     api::rest::{Gateway, Project},
-    comments, interactive,
+    comments,
+    config::Config,
+    interactive,
+    output::info,
     projects::state::State,
 };
 use color_eyre::{Result, eyre::eyre};
@@ -11,31 +14,32 @@ pub struct Params {
     project: interactive::Selection<Project>,
 }
 
-pub async fn view(params: Params, gw: &Gateway) -> Result<()> {
+pub async fn view(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
     let projects = gw.projects().await?;
-    let project = params.project.mandatory(&projects)?;
+    let project = params.project.mandatory(&projects, &cfg.theme)?;
     // TODO: no refetch here
     let state = State::fetch_tree(gw).await?;
     let tree = state
         .project(&project.id)
         .ok_or_else(|| eyre!("full project list contained invalid data"))?;
-    println!("Project: {}", &tree.item);
+    info!("Project: {}", &tree.item);
     if !tree.subitems.is_empty() {
-        println!("Subprojects:");
+        info!("Subprojects:");
         for project in &tree.subitems {
-            println!("{}", project.item)
+            info!("{}", project.item)
         }
     }
     let sections = state.sections(&project.id);
     if !sections.is_empty() {
-        println!("Sections:");
+        info!("Sections:");
         for section in sections {
-            println!("{section}")
+            info!("{section}")
         }
     }
     if project.comment_count > 0 {
         let comments = gw.project_comments(&project.id).await?;
-        comments::list(&comments)
+        let collaborators = comments::collaborators(Some(project), gw).await;
+        comments::list(&comments, &collaborators)
     }
     Ok(())
 }
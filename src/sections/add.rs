@@ -1,6 +1,8 @@
 use crate::{
     api::rest::{CreateSection, Gateway, Project},
+    config::Config,
     interactive,
+    output::info,
 };
 use color_eyre::Result;
 
@@ -12,9 +14,9 @@ pub struct Params {
     name: String,
 }
 
-pub async fn add(params: Params, gw: &Gateway) -> Result<()> {
+pub async fn add(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
     let projects = gw.projects().await?;
-    let project = params.project.mandatory(&projects)?;
+    let project = params.project.mandatory(&projects, &cfg.theme)?;
     let section = gw
         .create_section(&CreateSection {
             name: params.name,
@@ -22,6 +24,6 @@ pub async fn add(params: Params, gw: &Gateway) -> Result<()> {
             ..Default::default()
         })
         .await?;
-    println!("created section: {}", &section);
+    info!("created section: {}", &section);
     Ok(())
 }
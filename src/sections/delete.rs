@@ -1,20 +1,31 @@
 use crate::{
     api::rest::{Gateway, Section},
+    config::Config,
     interactive,
+    output::info,
 };
-use color_eyre::Result;
+use color_eyre::{Result, eyre::WrapErr};
 
 #[derive(clap::Parser, Debug)]
 pub struct Params {
     // TODO: make soft dependency on project selection here
     #[clap(flatten)]
     section: interactive::Selection<Section>,
+    /// Deletes the section without asking for confirmation.
+    #[arg(long = "force")]
+    force: bool,
 }
 
-pub async fn delete(params: Params, gw: &Gateway) -> Result<()> {
+pub async fn delete(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
     let sections = gw.sections().await?;
-    let section = params.section.mandatory(&sections)?;
+    let section = params.section.mandatory(&sections, &cfg.theme)?;
+    if !params.force
+        && !interactive::confirm(&format!("Delete section {}?", section.name), &cfg.theme)
+            .wrap_err("use --force to delete without confirmation")?
+    {
+        return Ok(());
+    }
     gw.delete_section(&section.id).await?;
-    println!("deleted section: {}", &section);
+    info!("deleted section: {}", &section);
     Ok(())
 }
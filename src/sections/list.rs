@@ -1,6 +1,8 @@
 use crate::{
-    api::rest::{Gateway, Project},
+    api::rest::{FullSection, Gateway, Project},
+    config::Config,
     interactive,
+    output::info,
 };
 use color_eyre::Result;
 
@@ -8,21 +10,37 @@ use color_eyre::Result;
 pub struct Params {
     #[clap(flatten)]
     project: interactive::Selection<Project>,
+    /// Lists the sections of all projects instead of a single one.
+    #[arg(short = 'a', long = "all")]
+    all: bool,
 }
 
 /// Lists available sections in a project.
-pub async fn list(params: Params, gw: &Gateway) -> Result<()> {
-    let projects = gw.projects().await?;
-    let project = params.project.mandatory(&projects)?;
-    let sections = gw
-        .sections()
-        .await?
+pub async fn list(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let (projects, sections) = tokio::join!(gw.projects(), gw.sections());
+    let (projects, mut sections) = (projects?, sections?);
+    if params.all {
+        let project = |id: &str| projects.iter().find(|p| p.id == id);
+        sections.sort_by(|a, b| {
+            let name = |id: &str| project(id).map(|p| p.name.as_str());
+            name(&a.project_id)
+                .cmp(&name(&b.project_id))
+                .then_with(|| a.cmp(b))
+        });
+        for s in &sections {
+            info!("{}", FullSection(s, project(&s.project_id)));
+        }
+        return Ok(());
+    }
+    let project = params.project.mandatory(&projects, &cfg.theme)?;
+    let mut sections = sections
         .into_iter()
         .filter(|s| s.project_id == project.id)
         .collect::<Vec<_>>();
-    println!("{project} sections:");
-    for s in sections {
-        println!("{s}");
+    sections.sort();
+    info!("{project} sections:");
+    for s in &sections {
+        info!("{}", FullSection(s, Some(project)));
     }
     Ok(())
 }
@@ -0,0 +1,28 @@
+use crate::api::rest::{Gateway, SectionID, UpdateSection};
+use crate::output::info;
+use color_eyre::{Result, eyre::eyre};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// ID of the section to rename.
+    id: SectionID,
+    /// New name of the section.
+    name: String,
+}
+
+pub async fn rename(params: Params, gw: &Gateway) -> Result<()> {
+    let name = params.name.trim();
+    if name.is_empty() {
+        return Err(eyre!("the section name can't be empty"));
+    }
+    let section = gw
+        .update_section(
+            &params.id,
+            &UpdateSection {
+                name: Some(name.to_string()),
+            },
+        )
+        .await?;
+    info!("renamed section: {}", &section);
+    Ok(())
+}
@@ -1,37 +1,51 @@
-use std::collections::HashMap;
-
-use color_eyre::Result;
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, eyre},
+};
 
 use crate::{
     api::{
-        rest::{CreateTask, DurationUnit, Gateway, Label, Project, Section, TableTask, TaskDue},
+        rest::{
+            CreateComment, CreateTask, FullComment, Gateway, Label, Project, Section, TableTask,
+            Task, TaskDue, TaskID, ThreadID,
+        },
         tree::Tree,
     },
-    config::Config,
+    config::{Config, TaskTemplate},
     interactive,
     labels::{self, LabelSelect},
-    tasks::Priority,
+    output::info,
+    tasks::{Priority, assignee, dates, filter, state::available},
 };
 
 #[derive(clap::Parser, Debug)]
 pub struct Params {
-    /// Name (title) of the task to add to the todo list.
-    name: String,
+    /// Name (title) of the task to add to the todo list. With a template, it's appended to the
+    /// name of the template, and can be left out if the template has one.
+    #[arg(required_unless_present = "template")]
+    name: Option<String>,
     /// Set due with a human-readable text.
     ///
     /// Examples: "in two days" "tomorrow", "every 2 days from Monday"
     #[arg(short = 'd', long = "due")]
     due: Option<String>,
+    /// Set an exact due time as YYYY-MM-DDTHH:MM in the configured timezone, instead of letting
+    /// Todoist interpret the text.
+    #[arg(long = "due-datetime", conflicts_with = "due")]
+    due_datetime: Option<String>,
     /// Description that has more details about the task.
     #[arg(short = 'D', long = "desc")]
     desc: Option<String>,
-    /// Sets the priority on the task. The higher the priority the more urgent the task.
-    #[arg(value_enum, short = 'p', long = "priority")]
+    /// Sets the priority on the task like in the Todoist apps, from p1 (urgent) to p4 (normal).
+    /// The "p" can be left out. Note that the API counts the other way round, with 4 as urgent.
+    #[arg(value_enum, ignore_case = true, short = 'p', long = "priority")]
     priority: Option<Priority>,
-    /// Set deadline with a date in YYYY-MM-DD format.
+    /// Set deadline with a date in YYYY-MM-DD format or a phrase like "next friday" or
+    /// "in 2 weeks".
     #[arg(long = "deadline")]
     deadline: Option<String>,
-    /// Set task duration with format "<amount>:<unit>" (e.g., "30:minute" or "2:day"). Requires --due to be specified.
+    /// Set task duration like "90m", "1h30m" or "2d", or as "<amount>:<unit>" like "30:minute".
+    /// Requires --due to be specified.
     #[arg(long = "duration")]
     duration: Option<String>,
     #[clap(flatten)]
@@ -40,21 +54,129 @@ pub struct Params {
     section: interactive::Selection<Section>,
     #[clap(flatten)]
     labels: LabelSelect,
+    /// Leaves out the labels that are configured as `default_labels`.
+    #[arg(long = "no-default-labels")]
+    no_default_labels: bool,
+    /// Assigns the task to the collaborator of the shared project whose name matches exactly
+    /// (ignoring case) or by a unique prefix, or to yourself with "me".
+    #[arg(long = "assignee")]
+    assignee: Option<String>,
+    /// Adds a comment with the given text to the new task. Supports Markdown.
+    #[arg(long = "note")]
+    note: Option<String>,
+    /// Starts from the task template with the given name from the config, including its
+    /// subtasks. Other flags override the fields of the template.
+    #[arg(long = "template")]
+    template: Option<String>,
+    /// Creates the task as a subtask of the task with this ID. It's placed in the project and
+    /// section of the parent task.
+    #[arg(long = "parent")]
+    parent: Option<TaskID>,
+    /// Interactively selects the parent task among the tasks of the default filter.
+    #[arg(long = "select", conflicts_with = "parent")]
+    select: bool,
 }
 
 pub async fn add(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
-    let (projects, sections) = tokio::try_join!(gw.projects(), gw.sections())?;
-    let project = params.project.optional(&projects)?;
+    let (projects, sections, labels) = tokio::join!(gw.projects(), gw.sections(), gw.labels());
+    let projects = projects?;
+    let sections = available("sections", sections);
+    let labels = available("labels", labels);
+    let template = params
+        .template
+        .as_deref()
+        .map(|name| cfg.template(name))
+        .transpose()?;
+    let project = match (params.project.optional(&projects)?, template) {
+        (
+            None,
+            Some(TaskTemplate {
+                project: Some(name),
+                ..
+            }),
+        ) => Some(interactive::fuzz_select(&projects, name)?),
+        (project, _) => project,
+    };
     let section = params.section.optional(&sections)?;
-    let labels = params
-        .labels
-        .labels(&gw.labels().await?, labels::Selection::AllowEmpty)?;
+    let parent = match (&params.parent, params.select) {
+        (Some(id), _) => Some(id.clone()),
+        (None, true) => Some(
+            filter::TaskOrInteractive::select(filter::Filter::new(None))
+                .task_id(gw, cfg)
+                .await
+                .wrap_err("no parent task selected")?,
+        ),
+        (None, false) => None,
+    };
+    let parent = match parent {
+        Some(id) => Some(
+            gw.task(&id)
+                .await
+                .wrap_err_with(|| format!("unable to get parent task {id}"))?,
+        ),
+        None => None,
+    };
+    // Subtasks always end up next to their parent, so the parent's placement wins.
+    let (project, section) = match &parent {
+        Some(parent) => {
+            let parent_project = projects.iter().find(|p| p.id == parent.project_id);
+            let parent_section = parent
+                .section_id
+                .as_ref()
+                .and_then(|id| sections.iter().find(|s| s.id == *id));
+            if project.is_some_and(|p| p.id != parent.project_id)
+                || section.is_some_and(|s| Some(&s.id) != parent.section_id.as_ref())
+            {
+                eprintln!(
+                    "--project and --section are ignored for subtasks, using the ones of the parent task"
+                );
+            }
+            (parent_project, parent_section)
+        }
+        // A section already decides the project.
+        None if project.is_none() && section.is_none() => (default_project(&projects, cfg), None),
+        None => (project, section),
+    };
+    let assignee = match (&params.assignee, project) {
+        (Some(name), Some(project)) => Some(assignee::resolve(name, project, gw).await?),
+        (Some(_), None) => {
+            return Err(eyre!(
+                "--assignee needs a shared project to be given with --project"
+            ));
+        }
+        (None, _) => None,
+    };
+    let mut task_labels = match template {
+        Some(template) if params.labels.is_empty() => LabelSelect::from_names(&template.labels)
+            .labels(&labels, labels::Selection::AllowEmpty, &cfg.theme)?,
+        _ => params
+            .labels
+            .labels(&labels, labels::Selection::AllowEmpty, &cfg.theme)?,
+    };
+    if !params.no_default_labels {
+        for label in default_labels(&labels, cfg) {
+            if !task_labels.iter().any(|l| l.id == label.id) {
+                task_labels.push(label.clone());
+            }
+        }
+    }
+    let labels = task_labels;
+    let priority = match (params.priority, template.and_then(|t| t.priority)) {
+        (Some(priority), _) => Some(priority),
+        (None, Some(priority)) => Some(Priority::try_from(priority)?),
+        (None, None) => None,
+    };
+    let content = task_content(params.name, template)?;
     let mut create = CreateTask {
-        content: params.name,
-        description: params.desc,
-        priority: params.priority.map(|p| p.into()),
-        project_id: project.map(|p| p.id.clone()),
-        section_id: section.map(|s| s.id.clone()),
+        content,
+        description: params
+            .desc
+            .or_else(|| template.and_then(|t| t.description.clone())),
+        priority: priority.map(|p| p.into()),
+        project_id: project.filter(|_| parent.is_none()).map(|p| p.id.clone()),
+        section_id: section.filter(|_| parent.is_none()).map(|s| s.id.clone()),
+        parent_id: parent.as_ref().map(|p| p.id.clone()),
+        assignee_id: assignee,
         labels: if labels.is_empty() {
             None
         } else {
@@ -62,17 +184,20 @@ pub async fn add(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
         },
         ..Default::default()
     };
-    if let Some(due) = params.due {
+    if let Some(due) = params
+        .due
+        .or_else(|| template.and_then(|t| t.due.clone()))
+        .filter(|_| params.due_datetime.is_none())
+    {
         create.due = Some(TaskDue::String(due));
     }
-    if let Some(deadline_str) = params.deadline {
-        if chrono::NaiveDate::parse_from_str(&deadline_str, "%Y-%m-%d").is_ok() {
-            create.deadline_date = Some(deadline_str);
-        } else {
-            return Err(color_eyre::eyre::eyre!(
-                "Invalid deadline format. Use YYYY-MM-DD format."
-            ));
-        }
+    if let Some(due) = params.due_datetime {
+        create.due = Some(TaskDue::DateTime(dates::parse_datetime(&due, cfg)?));
+    }
+    if let Some(deadline) = params.deadline {
+        // The API doesn't interpret deadline phrases, so they are resolved here.
+        let deadline = dates::parse_date(&deadline, cfg).wrap_err("Invalid deadline")?;
+        create.deadline_date = Some(deadline.format("%Y-%m-%d").to_string());
     }
     if let Some(duration_str) = params.duration {
         if create.due.is_none() {
@@ -80,50 +205,121 @@ pub async fn add(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
                 "Duration requires a due date. Use --due option when specifying duration."
             ));
         }
-        if let Some((amount_str, unit_str)) = duration_str.split_once(':') {
-            if let Ok(amount) = amount_str.parse::<u32>() {
-                if amount == 0 {
-                    return Err(color_eyre::eyre::eyre!(
-                        "Duration amount must be greater than zero."
-                    ));
-                }
-                let unit = match unit_str {
-                    "minute" => DurationUnit::Minute,
-                    "day" => DurationUnit::Day,
-                    _ => {
-                        return Err(color_eyre::eyre::eyre!(
-                            "Invalid duration unit. Use 'minute' or 'day'."
-                        ));
-                    }
-                };
-                create.duration = Some(amount);
-                create.duration_unit = Some(unit);
+        let (amount, unit) = dates::parse_duration(&duration_str)?;
+        create.duration = Some(amount);
+        create.duration_unit = Some(unit);
+    }
+    let recurring_due = match &create.due {
+        Some(TaskDue::String(due)) if looks_recurring(due) => Some(due.clone()),
+        _ => None,
+    };
+    let subtask = |content: &str, parent_id: Option<TaskID>| CreateTask {
+        content: content.to_string(),
+        parent_id,
+        ..Default::default()
+    };
+    let subtasks = template.iter().flat_map(|t| &t.subtasks);
+    if gw.is_dry_run() {
+        // The parent doesn't exist yet, so the subtasks and the note are shown without it.
+        gw.dry_run("create task", &create)?;
+        let subtasks = subtasks.map(|s| subtask(s, None)).collect::<Vec<_>>();
+        if !subtasks.is_empty() {
+            gw.dry_run("create subtasks", &subtasks)?;
+        }
+        if let Some(note) = &params.note {
+            gw.dry_run("add note", &serde_json::json!({ "content": note }))?;
+        }
+        return Ok(());
+    }
+    let task = create_task(create, project, section, &labels, gw, cfg).await?;
+    for content in subtasks {
+        let create = subtask(content, Some(task.id.clone()));
+        create_task(create, project, section, &[], gw, cfg)
+            .await
+            .wrap_err_with(|| {
+                format!(
+                    "task {} was created, but adding the subtask '{content}' failed",
+                    task.id
+                )
+            })?;
+    }
+    if let Some(note) = params.note {
+        let comment = gw
+            .create_comment(&CreateComment {
+                thread: ThreadID::Task {
+                    task_id: task.id.clone(),
+                },
+                content: note,
+            })
+            .await
+            .wrap_err_with(|| {
+                format!("task {} was created, but adding the note failed", task.id)
+            })?;
+        info!("added note: {}", FullComment(&comment));
+    }
+    if let Some(due_string) = recurring_due {
+        report_recurrence(&due_string, &gw.task(&task.id).await?);
+    }
+    Ok(())
+}
+
+/// Combines the given task name with the name of the template, so a template can be the start of
+/// a more specific task.
+fn task_content(name: Option<String>, template: Option<&TaskTemplate>) -> Result<String> {
+    match (name, template.and_then(|t| t.content.as_deref())) {
+        (Some(name), Some(content)) => Ok(format!("{content} {name}")),
+        (Some(name), None) => Ok(name),
+        (None, Some(content)) => Ok(content.to_string()),
+        (None, None) => Err(eyre!("the template has no task name, it has to be given")),
+    }
+}
+
+/// Looks up the configured default project by ID or name. If it doesn't exist anymore, the task
+/// goes to the Inbox instead.
+fn default_project<'a>(projects: &'a [Project], cfg: &Config) -> Option<&'a Project> {
+    let name = cfg.default_project.as_deref()?;
+    let project = projects
+        .iter()
+        .find(|p| p.id == name || p.name.eq_ignore_ascii_case(name));
+    if project.is_none() {
+        eprintln!("default project '{name}' was not found, adding the task to the Inbox");
+    }
+    project
+}
+
+/// Looks up the configured default labels by name. Labels that don't exist anymore are left out
+/// with a warning.
+fn default_labels<'a>(labels: &'a [Label], cfg: &Config) -> Vec<&'a Label> {
+    cfg.default_labels
+        .iter()
+        .filter_map(|name| {
+            let name = name.strip_prefix('@').unwrap_or(name);
+            let label = labels.iter().find(|l| l.name.eq_ignore_ascii_case(name));
+            if label.is_none() {
+                eprintln!("default label '{name}' was not found, leaving it out");
+            }
+            label
+        })
+        .collect()
+}
+
+/// Checks if the human-readable due string is meant to set up a recurring task.
+fn looks_recurring(due: &str) -> bool {
+    due.to_lowercase().contains("every")
+}
+
+/// Shows how the API interpreted a recurring due string, so mistakes are noticed right away.
+fn report_recurrence(due_string: &str, task: &Task) {
+    match &task.due {
+        Some(due) if due.is_recurring => {
+            if let Some(exact) = due.exact_datetime() {
+                info!("recurring task, next due date: {}", exact);
             } else {
-                return Err(color_eyre::eyre::eyre!(
-                    "Invalid duration amount. Must be a positive integer."
-                ));
+                info!("recurring task, next due date: {}", due.date);
             }
-        } else {
-            return Err(color_eyre::eyre::eyre!(
-                "Invalid duration format. Use '<amount>:<unit>' format (e.g., '30:minute' or '2:day')."
-            ));
         }
+        _ => eprintln!("Due string '{due_string}' was not recognized as recurring."),
     }
-    let labels = if let Some(ref label_names) = create.labels {
-        let mut labels: HashMap<_, _> = gw
-            .labels()
-            .await?
-            .into_iter()
-            .map(|label| (label.name.clone(), label))
-            .collect();
-        label_names
-            .iter()
-            .filter_map(|l| labels.remove(l))
-            .collect()
-    } else {
-        Vec::new()
-    };
-    create_task(create, project, section, &labels, gw, cfg).await
 }
 
 pub(super) async fn create_task(
@@ -133,12 +329,77 @@ pub(super) async fn create_task(
     labels: &[Label],
     gw: &Gateway,
     cfg: &Config,
-) -> Result<()> {
+) -> Result<Task> {
     let task = Tree::new(gw.create(&create).await?);
-    let mut table = TableTask::from_task(&task, cfg);
-    table.1 = project;
-    table.2 = section;
-    table.3 = labels.iter().collect();
-    println!("created task: {table}");
-    Ok(())
+    let table = TableTask {
+        project,
+        section,
+        labels: labels.iter().collect(),
+        ..TableTask::from_task(&task, cfg)
+    };
+    info!("created task: {table}");
+    Ok(task.item)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn detects_recurring_due() {
+        assert!(looks_recurring("every weekday"));
+        assert!(looks_recurring("Every 2 days from Monday"));
+        assert!(!looks_recurring("tomorrow"));
+    }
+
+    #[test]
+    fn merges_template_content() {
+        let template = TaskTemplate {
+            content: Some("Weekly review".to_string()),
+            ..Default::default()
+        };
+        let content = |name: Option<&str>, template| task_content(name.map(Into::into), template);
+        assert_eq!(
+            content(Some("of Q3"), Some(&template)).unwrap(),
+            "Weekly review of Q3"
+        );
+        assert_eq!(content(None, Some(&template)).unwrap(), "Weekly review");
+        assert_eq!(content(Some("Task"), None).unwrap(), "Task");
+        let empty = TaskTemplate::default();
+        assert_eq!(content(Some("Task"), Some(&empty)).unwrap(), "Task");
+        assert!(content(None, Some(&empty)).is_err());
+    }
+
+    #[test]
+    fn resolves_default_project() {
+        let projects = [Project::new("1", "Inbox"), Project::new("2", "Work")];
+        let cfg = |project: Option<&str>| Config {
+            default_project: project.map(str::to_string),
+            ..Default::default()
+        };
+        let id = |cfg| default_project(&projects, &cfg).map(|p| p.id.as_str());
+        assert_eq!(id(cfg(None)), None);
+        assert_eq!(id(cfg(Some("work"))), Some("2"));
+        assert_eq!(id(cfg(Some("2"))), Some("2"));
+        // Projects that were deleted or renamed fall back to the Inbox.
+        assert_eq!(id(cfg(Some("Old work"))), None);
+    }
+
+    #[test]
+    fn resolves_default_labels() {
+        let labels = [Label::new("1", "home"), Label::new("2", "inbox-review")];
+        let cfg = Config {
+            default_labels: vec![
+                "@Inbox-Review".to_string(),
+                "gone".to_string(),
+                "home".to_string(),
+            ],
+            ..Default::default()
+        };
+        let ids = default_labels(&labels, &cfg)
+            .into_iter()
+            .map(|l| l.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["2", "1"]);
+    }
 }
@@ -0,0 +1,115 @@
+use color_eyre::{Result, eyre::eyre};
+
+use crate::api::rest::{Collaborator, Gateway, Project, UserID};
+
+/// Resolves the name of an assignee to the ID of a collaborator of the project. `me` resolves to
+/// the current user.
+pub async fn resolve(name: &str, project: &Project, gw: &Gateway) -> Result<UserID> {
+    if !project.is_shared {
+        return Err(eyre!(
+            "project {} is not shared, so its tasks can't be assigned",
+            project.name
+        ));
+    }
+    matching(name, gw, gw.collaborators(&project.id)).await
+}
+
+/// Resolves the name of an assignee like [`resolve`], but searches the collaborators of all given
+/// projects that are shared.
+pub async fn resolve_among(name: &str, projects: &[&Project], gw: &Gateway) -> Result<UserID> {
+    let collaborators = async {
+        let shared = projects.iter().filter(|p| p.is_shared).collect::<Vec<_>>();
+        if shared.is_empty() {
+            return Err(eyre!(
+                "none of the projects are shared, so no tasks can be assigned to {name}"
+            ));
+        }
+        let mut collaborators =
+            futures::future::try_join_all(shared.iter().map(|p| gw.collaborators(&p.id)))
+                .await?
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>();
+        collaborators.sort_by(|a, b| a.id.cmp(&b.id));
+        collaborators.dedup_by(|a, b| a.id == b.id);
+        Ok(collaborators)
+    };
+    matching(name, gw, collaborators).await
+}
+
+/// Resolves `me` to the current user, or finds the collaborator with the given name. The
+/// collaborators are only fetched if needed.
+///
+/// As the assignee gets notified, names are not fuzzy matched: they have to match exactly,
+/// ignoring case, or be the prefix of a single collaborator.
+async fn matching(
+    name: &str,
+    gw: &Gateway,
+    collaborators: impl Future<Output = Result<Vec<Collaborator>>>,
+) -> Result<UserID> {
+    if name.eq_ignore_ascii_case("me") {
+        return Ok(gw.user().await?.id);
+    }
+    let collaborators = collaborators.await?;
+    let lower = name.to_lowercase();
+    let exact = collaborators
+        .iter()
+        .filter(|c| c.name.to_lowercase() == lower)
+        .collect::<Vec<_>>();
+    let candidates = if exact.is_empty() {
+        collaborators
+            .iter()
+            .filter(|c| c.name.to_lowercase().starts_with(&lower))
+            .collect::<Vec<_>>()
+    } else {
+        exact
+    };
+    match candidates.as_slice() {
+        [collaborator] => Ok(collaborator.id.clone()),
+        [] => Err(eyre!(
+            "no collaborator matches {name}, choose one of: {}",
+            list(collaborators.iter())
+        )),
+        _ => Err(eyre!(
+            "{name} matches several collaborators, choose one of: {}",
+            list(candidates.into_iter())
+        )),
+    }
+}
+
+fn list<'a>(collaborators: impl Iterator<Item = &'a Collaborator>) -> String {
+    collaborators
+        .map(|c| c.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn collaborators() -> Vec<Collaborator> {
+        serde_json::from_value(serde_json::json!([
+            {"id": "1", "name": "Alice", "email": "alice@example.com"},
+            {"id": "2", "name": "Alice Doe", "email": "doe@example.com"},
+            {"id": "3", "name": "Bob Roe", "email": "bob@example.com"}
+        ]))
+        .unwrap()
+    }
+
+    async fn resolve(name: &str) -> Result<UserID> {
+        let gw = Gateway::new("", &"http://localhost".parse().unwrap());
+        matching(name, &gw, async { Ok(collaborators()) }).await
+    }
+
+    #[tokio::test]
+    async fn matches_exactly_or_by_unique_prefix() {
+        assert_eq!(resolve("alice").await.unwrap(), "1");
+        assert_eq!(resolve("ALICE DOE").await.unwrap(), "2");
+        assert_eq!(resolve("bo").await.unwrap(), "3");
+        let err = resolve("ali").await.unwrap_err().to_string();
+        assert!(err.contains("Alice, Alice Doe"), "{err}");
+        let err = resolve("bbo").await.unwrap_err().to_string();
+        assert!(err.contains("Alice, Alice Doe, Bob Roe"), "{err}");
+    }
+}
@@ -0,0 +1,128 @@
+use color_eyre::{Result, eyre::WrapErr};
+
+use crate::{
+    api::rest::{Gateway, Task, TaskID},
+    config::Config,
+    output::info,
+};
+
+use super::{filter, reorder};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    #[clap(flatten)]
+    pub task: filter::TaskOrInteractive,
+}
+
+/// The way a task is moved among its siblings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Towards the top of the list.
+    Up,
+    /// Towards the bottom of the list.
+    Down,
+}
+
+/// Swaps a task with the task right above or below it, among the tasks of the same project,
+/// section and parent task.
+pub async fn bump(params: Params, direction: Direction, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let id = params
+        .task
+        .task_id(gw, cfg)
+        .await
+        .wrap_err("no task selected for moving")?;
+    let task = gw.task(&id).await?;
+    let tasks = gw.project_tasks(&task.project_id).await?;
+    let Some((neighbor, items)) = swap(&task, &tasks, direction) else {
+        let edge = match direction {
+            Direction::Up => "top",
+            Direction::Down => "bottom",
+        };
+        info!("task {} is already at the {edge}", task.content);
+        return Ok(());
+    };
+    if gw.dry_run("reorder tasks", &items)? {
+        return Ok(());
+    }
+    gw.reorder_tasks(&items).await?;
+    let place = match direction {
+        Direction::Up => "above",
+        Direction::Down => "below",
+    };
+    info!("moved task {} {place} {}", task.content, neighbor.content);
+    Ok(())
+}
+
+/// Finds the sibling that the task swaps places with and the new orders of both of them. Returns
+/// [`None`] if the task is already at the top or bottom.
+fn swap<'a>(
+    task: &Task,
+    tasks: &'a [Task],
+    direction: Direction,
+) -> Option<(&'a Task, Vec<(TaskID, isize)>)> {
+    // The task itself is placed by its own order, in case it's missing from the list of tasks.
+    let mut siblings = tasks
+        .iter()
+        .filter(|t| t.id != task.id && reorder::are_siblings(t, task))
+        .collect::<Vec<_>>();
+    siblings.sort_by(|a, b| a.order.cmp(&b.order).then_with(|| a.id.cmp(&b.id)));
+    let position = siblings
+        .iter()
+        .position(|t| (t.order, &t.id) > (task.order, &task.id))
+        .unwrap_or(siblings.len());
+    let neighbor = match direction {
+        Direction::Up => siblings.get(position.checked_sub(1)?)?,
+        Direction::Down => siblings.get(position)?,
+    };
+    let tasks = match direction {
+        Direction::Up => [task.clone(), (*neighbor).clone()],
+        Direction::Down => [(*neighbor).clone(), task.clone()],
+    };
+    Some((neighbor, reorder::reordered(siblings, &tasks)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn task(id: &str, order: isize, section: Option<&str>) -> Task {
+        let mut task = Task::new(id, id);
+        task.order = order;
+        task.section_id = section.map(str::to_string);
+        task
+    }
+
+    #[test]
+    fn swaps_with_neighbor() {
+        let tasks = [
+            task("a", 1, None),
+            task("b", 2, None),
+            task("other", 3, Some("s")),
+            task("c", 4, None),
+            task("d", 5, None),
+        ];
+        let swapped = |id: &str, direction| {
+            let task = tasks.iter().find(|t| t.id == id).unwrap();
+            swap(task, &tasks, direction).map(|(neighbor, items)| (neighbor.id.clone(), items))
+        };
+        let item = |id: &str, order| (id.to_string(), order);
+
+        assert_eq!(
+            swapped("c", Direction::Up),
+            Some((
+                "b".to_string(),
+                vec![item("a", 1), item("c", 2), item("b", 3), item("d", 4)]
+            ))
+        );
+        assert_eq!(
+            swapped("b", Direction::Down),
+            Some((
+                "c".to_string(),
+                vec![item("a", 1), item("c", 2), item("b", 3), item("d", 4)]
+            ))
+        );
+        assert_eq!(swapped("a", Direction::Up), None);
+        assert_eq!(swapped("d", Direction::Down), None);
+        assert_eq!(swapped("other", Direction::Up), None);
+    }
+}
@@ -1,53 +1,344 @@
-use color_eyre::{Result, eyre::WrapErr};
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, eyre},
+};
 use owo_colors::{OwoColorize, Stream};
 
 use crate::{
-    api::{self, rest::Gateway},
+    api::{
+        self,
+        rest::{Gateway, Project, Task},
+        tree::Tree,
+    },
     config::Config,
+    interactive,
+    output::info,
 };
 
-use super::filter;
+use super::{filter, state::State};
+
+/// Filter used to find the next task with --next.
+const NEXT_FILTER: &str = "(today | overdue)";
 
 #[derive(clap::Parser, Debug)]
+#[command(group(
+    clap::ArgGroup::new("next_project")
+        .args(["project", "project_id"])
+        .multiple(true)
+        .requires("next")
+))]
 pub struct Params {
     #[clap(flatten)]
-    pub task: filter::TaskOrInteractive,
+    pub tasks: filter::TasksOrInteractive,
     /// Complete will completely close a task, even if it's recurring.
     /// Since the REST API does not support completely closing tasks, this will change the due date
     /// of the task to "today" and then close it.
     #[arg(short = 'c', long = "complete")]
     pub complete: bool,
+    /// With --complete, recurring tasks are only closed, which moves them on to their next due
+    /// date. Other tasks are completed as usual.
+    #[arg(long = "keep-recurring", requires = "complete")]
+    pub keep_recurring: bool,
+    /// Closes tasks that still have open subtasks without asking for confirmation. The subtasks
+    /// stay open, but are hidden together with their parent.
+    #[arg(long = "force")]
+    pub force: bool,
+    /// Closes all open subtasks first, starting with the deepest ones.
+    #[arg(short = 'r', long = "recursive")]
+    pub recursive: bool,
+    /// After closing, shows the next task that is due today or overdue, so you can keep going.
+    #[arg(long = "next")]
+    pub next: bool,
+    /// Limits the task shown with --next to the given project.
+    #[clap(flatten)]
+    pub project: interactive::Selection<Project>,
+}
+
+impl Params {
+    pub fn new(id: api::rest::TaskID, complete: bool) -> Self {
+        Self {
+            tasks: id.into(),
+            complete,
+            keep_recurring: false,
+            force: false,
+            recursive: false,
+            next: false,
+            project: Default::default(),
+        }
+    }
 }
 
 pub async fn close(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
-    let id = params
-        .task
-        .task_id(gw, cfg)
+    let ids = params
+        .tasks
+        .task_ids(gw, cfg)
         .await
         .wrap_err("no task selected for closing")?;
-    if params.complete {
-        return complete(&id, gw).await;
+    let mode = Mode {
+        complete: params.complete,
+        keep_recurring: params.keep_recurring,
+    };
+    let check_subtasks = !params.force || params.recursive;
+    // The tasks are needed to find their subtasks, or to see if they are recurring.
+    let tasks = if check_subtasks || (mode.complete && mode.keep_recurring) {
+        futures::future::try_join_all(ids.iter().map(|id| gw.task(id))).await?
+    } else {
+        Vec::new()
+    };
+    let subtasks = if check_subtasks {
+        fetch_open_subtasks(&tasks, mode, gw).await?
+    } else {
+        Vec::new()
+    };
+    let completes = ids
+        .iter()
+        .map(|id| {
+            tasks
+                .iter()
+                .find(|t| t.id == *id)
+                .map_or(mode.complete, |t| mode.completes_task(t))
+        })
+        .collect::<Vec<_>>();
+    let action = if params.complete {
+        "complete tasks"
+    } else {
+        "close tasks"
+    };
+    if gw.is_dry_run() {
+        if params.recursive {
+            let ids = subtasks.iter().map(|t| &t.id).collect::<Vec<_>>();
+            gw.dry_run("close subtasks", &ids)?;
+        } else if !subtasks.is_empty() {
+            eprintln!("{} open subtasks would be left behind", subtasks.len());
+        }
+        gw.dry_run(action, &ids)?;
+        return Ok(());
+    }
+    if !subtasks.is_empty() {
+        if params.recursive {
+            close_subtasks(&subtasks, mode, gw).await?;
+        } else {
+            let subject = if ids.len() == 1 {
+                "This task has"
+            } else {
+                "These tasks have"
+            };
+            let verb = if mode.complete { "Complete" } else { "Close" };
+            let prompt = format!("{subject} {} open subtasks. {verb} anyway?", subtasks.len());
+            if !interactive::confirm(&prompt, &cfg.theme).wrap_err_with(|| {
+                format!(
+                    "{} open subtasks would be left behind, use --force to close anyway or --recursive to close them as well",
+                    subtasks.len()
+                )
+            })? {
+                return Ok(());
+            }
+        }
+    }
+    if let ([id], [complete]) = (&ids[..], &completes[..]) {
+        close_single(id, *complete, gw).await?;
+    } else {
+        close_multiple(&ids, &completes, mode, gw).await?;
+    }
+    if params.next {
+        show_next(&params.project, gw, cfg).await?;
+    }
+    Ok(())
+}
+
+/// How tasks are closed.
+#[derive(Debug, Clone, Copy)]
+struct Mode {
+    /// Stops recurring tasks from recurring, see [`Gateway::complete`].
+    complete: bool,
+    /// Lets recurring tasks keep recurring even if `complete` is set.
+    keep_recurring: bool,
+}
+
+impl Mode {
+    /// Checks if the task has to be completed instead of closed.
+    fn completes_task(self, task: &Task) -> bool {
+        self.complete && !(self.keep_recurring && task.due.as_ref().is_some_and(|d| d.is_recurring))
+    }
+}
+
+/// Fetches the open subtasks of the given tasks, see [`open_subtasks`]. Only the projects of the
+/// tasks are fetched. Recurring tasks that are only moved on to their next due date stay open
+/// together with their subtasks, so they are left out.
+async fn fetch_open_subtasks(tasks: &[Task], mode: Mode, gw: &Gateway) -> Result<Vec<Task>> {
+    let ids = tasks.iter().map(|t| t.id.clone()).collect::<Vec<_>>();
+    let tasks = tasks
+        .iter()
+        .filter(|t| mode.completes_task(t) || !t.due.as_ref().is_some_and(|d| d.is_recurring))
+        .collect::<Vec<_>>();
+    let mut projects = tasks.iter().map(|t| &t.project_id).collect::<Vec<_>>();
+    projects.sort();
+    projects.dedup();
+    let project_tasks =
+        futures::future::try_join_all(projects.into_iter().map(|id| gw.project_tasks(id))).await?;
+    let trees = Tree::from_items(project_tasks.into_iter().flatten().collect())?;
+    let checked = tasks.iter().map(|t| t.id.clone()).collect::<Vec<_>>();
+    Ok(open_subtasks(&trees, &checked)
+        .into_iter()
+        .filter(|t| !ids.contains(&t.id))
+        .cloned()
+        .collect())
+}
+
+/// Returns the open subtasks of the given tasks, with every subtask coming before its parent so
+/// they can be closed bottom-up. Subtasks that are given themselves are left out.
+fn open_subtasks<'a>(trees: &'a [Tree<Task>], ids: &[api::rest::TaskID]) -> Vec<&'a Task> {
+    let mut subtasks: Vec<&Task> = Vec::new();
+    for tree in ids
+        .iter()
+        .filter_map(|id| trees.iter().find_map(|tree| tree.find(id)))
+    {
+        // Reversing the tree puts the deepest subtasks first.
+        for subtask in tree.flatten().into_iter().skip(1).rev() {
+            if !ids.contains(&subtask.id) && !subtasks.iter().any(|t| t.id == subtask.id) {
+                subtasks.push(&subtask.item);
+            }
+        }
     }
-    gw.close(&id).await?;
-    println!("closed task {}", id.clone().bright_red());
-    let task = gw.task(&id).await?;
+    subtasks
+}
+
+/// Closes the subtasks one after the other, so no parent is closed before its subtasks.
+async fn close_subtasks(subtasks: &[Task], mode: Mode, gw: &Gateway) -> Result<()> {
+    for task in subtasks {
+        if mode.completes_task(task) {
+            gw.complete(&task.id).await
+        } else {
+            gw.close(&task.id).await
+        }
+        .wrap_err_with(|| format!("unable to close subtask {}", task.content))?;
+        info!(
+            "closed subtask {}",
+            task.id
+                .if_supports_color(Stream::Stdout, |text| text.bright_red())
+        );
+    }
+    Ok(())
+}
+
+/// Closes all given tasks concurrently, reporting the outcome of each of them. Each task is
+/// completed instead if the matching entry of `completes` is set.
+async fn close_multiple(
+    ids: &[api::rest::TaskID],
+    completes: &[bool],
+    mode: Mode,
+    gw: &Gateway,
+) -> Result<()> {
+    let results =
+        futures::future::join_all(ids.iter().zip(completes).map(|(id, &complete)| async move {
+            if complete {
+                gw.complete(id).await
+            } else {
+                gw.close(id).await
+            }
+        }))
+        .await;
+    let action = if mode.complete { "completed" } else { "closed" };
+    let mut failed = 0;
+    for (id, result) in ids.iter().zip(results) {
+        match result {
+            Ok(()) => info!(
+                "{action} task {}",
+                id.if_supports_color(Stream::Stdout, |text| text.bright_red())
+            ),
+            Err(e) => {
+                failed += 1;
+                eprintln!("failed to close task {id}: {e:#}");
+            }
+        }
+    }
+    info!("{action} {} of {} tasks", ids.len() - failed, ids.len());
+    if failed > 0 {
+        return Err(eyre!("{failed} of {} tasks could not be closed", ids.len()));
+    }
+    Ok(())
+}
+
+/// Closes or completes a single task, showing the next due date if it's recurring.
+async fn close_single(id: &api::rest::TaskID, complete: bool, gw: &Gateway) -> Result<()> {
+    if complete {
+        return self::complete(id, gw).await;
+    }
+    gw.close(id).await?;
+    info!("closed task {}", id.clone().bright_red());
+    let task = gw.task(id).await?;
     if !task.is_completed
         && let Some(due) = task.due
     {
         if let Some(exact) = due.exact_datetime() {
-            println!("next due date: {}", exact);
+            info!("next due date: {}", exact);
         } else {
-            println!("next due date: {}", due.date);
+            info!("next due date: {}", due.date);
+        }
+    }
+    Ok(())
+}
+
+/// Prints the top task that is due today or overdue, optionally only from the selected project.
+async fn show_next(
+    project: &interactive::Selection<Project>,
+    gw: &Gateway,
+    cfg: &Config,
+) -> Result<()> {
+    let state = State::fetch_tree(Some(NEXT_FILTER), gw, cfg).await?;
+    let projects = state.projects.values().cloned().collect::<Vec<_>>();
+    let state = match project.optional(&projects)? {
+        Some(project) => {
+            let id = project.id.clone();
+            state.filter(|t| t.project_id == id)
         }
+        None => state,
+    };
+    match state.tasks.iter().min() {
+        Some(task) => info!("next task: {}", state.table_task(task, true)),
+        None => info!("no more tasks due today"),
     }
     Ok(())
 }
 
 pub async fn complete(id: &api::rest::TaskID, gw: &Gateway) -> Result<()> {
     gw.complete(id).await?;
-    println!(
+    info!(
         "completed task {}",
         id.if_supports_color(Stream::Stdout, |text| text.bright_red())
     );
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn task(id: &str, parent: Option<&str>) -> Task {
+        let mut task = Task::new(id, id);
+        task.parent_id = parent.map(str::to_string);
+        task
+    }
+
+    #[test]
+    fn finds_subtasks_bottom_up() {
+        let trees = Tree::from_items(vec![
+            task("parent", None),
+            task("child", Some("parent")),
+            task("grandchild", Some("child")),
+            task("other", None),
+        ])
+        .unwrap();
+        let ids = |ids: &[&str]| {
+            let ids = ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+            open_subtasks(&trees, &ids)
+                .into_iter()
+                .map(|t| t.id.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(&["parent"]), vec!["grandchild", "child"]);
+        assert_eq!(ids(&["parent", "child"]), vec!["grandchild"]);
+        assert_eq!(ids(&["child", "parent"]), vec!["grandchild"]);
+        assert!(ids(&["other"]).is_empty());
+    }
+}
@@ -1,29 +1,56 @@
-use color_eyre::Result;
+use color_eyre::{Result, eyre::eyre};
 
 use crate::{
-    api::rest::{CreateComment, FullComment, Gateway, ThreadID},
+    api::rest::{CreateComment, FullComment, Gateway, Project, TaskID, ThreadID},
     config::Config,
+    interactive,
+    output::info,
 };
 
 use super::filter::TaskOrInteractive;
 
 #[derive(clap::Parser, Debug)]
+#[command(group(
+    clap::ArgGroup::new("project_thread")
+        .args(["project", "project_id"])
+        .multiple(true)
+        .conflicts_with_all(["id", "task_id"])
+))]
 pub struct Params {
-    /// The text of the comment. Supports Markdown.
+    /// The text of the comment. Supports Markdown. Use `-` to read it from stdin.
     content: String,
     #[clap(flatten)]
     task: TaskOrInteractive,
+    /// ID of the task to comment on, same as giving the ID after the text.
+    #[arg(long = "task", conflicts_with = "id")]
+    task_id: Option<TaskID>,
+    /// The project to comment on instead of a task.
+    #[clap(flatten)]
+    project: interactive::Selection<Project>,
 }
 
-/// Creates a new comment for a task.
+/// Creates a new comment for a task or project.
 pub async fn comment(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
-    let (id, _) = params.task.task(gw, cfg).await?;
+    let content = interactive::text_or_stdin(params.content)?;
+    let thread = if !params.project.is_empty() {
+        let projects = gw.projects().await?;
+        let project = params
+            .project
+            .optional(&projects)?
+            .ok_or_else(|| eyre!("could not find the given project"))?;
+        ThreadID::Project {
+            project_id: project.id.clone(),
+        }
+    } else if let Some(task_id) = params.task_id {
+        ThreadID::Task { task_id }
+    } else {
+        ThreadID::Task {
+            task_id: params.task.task_id(gw, cfg).await?,
+        }
+    };
     let comment = gw
-        .create_comment(&CreateComment {
-            thread: ThreadID::Task { task_id: id },
-            content: params.content,
-        })
+        .create_comment(&CreateComment { thread, content })
         .await?;
-    println!("created comment: {}", FullComment(&comment));
+    info!("created comment: {}", FullComment(&comment));
     Ok(())
 }
@@ -0,0 +1,55 @@
+use color_eyre::{Result, eyre::eyre};
+
+use crate::{
+    api::rest::{Gateway, Project, TaskID},
+    config::Config,
+    interactive,
+    output::info,
+};
+
+use super::filter::{Filter, TaskOrInteractive};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// ID of the task to show the comments of.
+    #[arg(long = "task", conflicts_with_all = ["project", "project_id"])]
+    task: Option<TaskID>,
+    /// The project to show the comments of.
+    #[clap(flatten)]
+    project: interactive::Selection<Project>,
+    /// Selects the task interactively if neither --task nor --project is given.
+    #[clap(flatten)]
+    filter: Filter,
+}
+
+/// Lists the comments of a task or project, oldest first.
+pub async fn comments(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let projects = gw.projects().await?;
+    let (comments, project) = if !params.project.is_empty() {
+        let project = params
+            .project
+            .optional(&projects)?
+            .ok_or_else(|| eyre!("could not find the given project"))?;
+        (gw.project_comments(&project.id).await?, Some(project))
+    } else {
+        let id = match params.task {
+            Some(task) => task,
+            None => {
+                TaskOrInteractive::select(params.filter)
+                    .task_id(gw, cfg)
+                    .await?
+            }
+        };
+        let (comments, task) = tokio::try_join!(gw.task_comments(&id), gw.task(&id))?;
+        let project = projects.iter().find(|p| p.id == task.project_id);
+        (comments, project)
+    };
+    if comments.is_empty() {
+        info!("No comments");
+        return Ok(());
+    }
+    // Posters are shown by name if the project is shared.
+    let collaborators = crate::comments::collaborators(project, gw).await;
+    crate::comments::list(&comments, &collaborators);
+    Ok(())
+}
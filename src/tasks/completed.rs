@@ -1,16 +1,33 @@
+use std::{collections::BTreeMap, fmt::Display, io::Write};
+
 use color_eyre::{Result, eyre::WrapErr};
-use owo_colors::OwoColorize;
+use owo_colors::{OwoColorize, Stream};
 
 use crate::{
     api::rest::{
-        CompletedTasksByCompletionDateParams, CompletedTasksByDueDateParams, Gateway, Project,
-        Section,
+        COMPLETED_TASKS_MAX_LIMIT, CompletedTasksByCompletionDateParams,
+        CompletedTasksByDueDateParams, Gateway, JsonTask, Project, Section, Task,
     },
-    config::Config,
+    config::{CompletedRange, Config},
     interactive,
+    output::{self, info},
+    tasks::state::{State, available},
 };
 
-use super::list::GroupBy;
+use super::list::{Format, GroupBy};
+
+/// How completed tasks can be grouped, which adds the day of completion to the groups of `list`.
+#[derive(clap::ValueEnum, Clone, Debug)]
+pub enum CompletedGroupBy {
+    /// Group tasks by project
+    Project,
+    /// Group tasks by section, tasks without a section are grouped together
+    Section,
+    /// Group tasks by label, tasks with multiple labels show up in each of their groups
+    Label,
+    /// Group tasks by the day they were completed on, in the configured timezone
+    Day,
+}
 
 #[derive(clap::Parser, Debug)]
 pub struct Params {
@@ -34,11 +51,11 @@ pub struct Params {
     #[arg(long = "yesterday", conflicts_with_all = ["since", "until", "date", "today", "this_week", "last_week", "this_month"])]
     yesterday: bool,
 
-    /// Show tasks completed this week (Monday to today)
+    /// Show tasks completed this week (start of the week to today, see `week_start` in the config)
     #[arg(long = "this-week", conflicts_with_all = ["since", "until", "date", "today", "yesterday", "last_week", "this_month"])]
     this_week: bool,
 
-    /// Show tasks completed last week (Monday to Sunday)
+    /// Show tasks completed last week (a full week, see `week_start` in the config)
     #[arg(long = "last-week", conflicts_with_all = ["since", "until", "date", "today", "yesterday", "this_week", "this_month"])]
     last_week: bool,
 
@@ -46,6 +63,11 @@ pub struct Params {
     #[arg(long = "this-month", conflicts_with_all = ["since", "until", "date", "today", "yesterday", "this_week", "last_week"])]
     this_month: bool,
 
+    /// Timezone (IANA name, e.g. "Asia/Seoul") that the date flags are calculated in. Defaults
+    /// to `timezone` in the config, then the timezone of the Todoist account.
+    #[arg(long = "tz")]
+    tz: Option<chrono_tz::Tz>,
+
     /// Filter by project
     #[clap(flatten)]
     project: interactive::Selection<Project>,
@@ -58,7 +80,7 @@ pub struct Params {
     #[arg(long = "filter")]
     filter: Option<String>,
 
-    /// Limit results per page (max: 200)
+    /// Limit results per page (max: 200, larger values are clamped)
     #[arg(long = "limit", default_value = "50")]
     limit: u32,
 
@@ -68,7 +90,7 @@ pub struct Params {
 
     /// Group tasks by specific criteria
     #[arg(long = "group-by", value_enum)]
-    group_by: Option<GroupBy>,
+    group_by: Option<CompletedGroupBy>,
 
     /// Use due date instead of completion date for filtering (supports up to 6 weeks instead of 3 months)
     #[arg(long = "by-due-date")]
@@ -77,6 +99,44 @@ pub struct Params {
     /// Show task IDs in the output.
     #[arg(long = "show-id")]
     show_id: bool,
+
+    /// Also count the tasks of the preceding range of equal length and compare both counts.
+    /// Fetches all pages.
+    #[arg(long = "compare-previous")]
+    compare_previous: bool,
+
+    /// Print the amount of tasks per project, day and priority below the tasks. Not available
+    /// with --format, as the JSON output only contains the tasks.
+    #[arg(long = "stats")]
+    stats: bool,
+
+    /// Output format. JSON prints a flat array of the completed tasks with their project and
+    /// section names, and nothing else on stdout.
+    #[arg(
+        long = "format",
+        value_enum,
+        default_value_t = Format::Table,
+        conflicts_with_all = ["group_by", "compare_previous", "stats"]
+    )]
+    format: Format,
+}
+
+/// Query parameters that are shared by all fetches of completed tasks in one command.
+struct CompletedQuery<'a> {
+    by_due_date: bool,
+    project_id: Option<&'a str>,
+    section_id: Option<&'a str>,
+    filter: Option<&'a str>,
+    limit: u32,
+}
+
+/// Result of [`fetch_completed`].
+struct CompletedPages {
+    tasks: Vec<Task>,
+    /// The amount of fetched pages.
+    pages: usize,
+    /// Set if there are more pages that were not fetched.
+    more: bool,
 }
 
 /// Lists completed tasks by completion date (default, up to 3 months) or due date (--by-due-date, up to 6 weeks).
@@ -84,7 +144,8 @@ pub struct Params {
 /// # Examples
 ///
 /// ```bash
-/// # Get tasks completed today (default, by completion date)
+/// # Get tasks completed today (default, by completion date, unless `completed_default_range`
+/// # is set in the config)
 /// doist completed
 /// doist completed --today
 ///
@@ -104,95 +165,239 @@ pub struct Params {
 /// doist completed --since 2025-10-01 --until 2025-10-31 --all --group-by project
 /// ```
 pub async fn completed(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    // Day boundaries are calculated in the timezone of the account, not the machine
+    let zoned;
+    let cfg = match range_timezone(&params, gw, cfg).await {
+        Some(tz) => {
+            zoned = Config {
+                timezone: Some(tz),
+                ..cfg.clone()
+            };
+            &zoned
+        }
+        None => cfg,
+    };
+
     // Calculate date range based on convenience flags or use provided dates
-    let (since, until) = calculate_date_range(&params)?;
+    let now = cfg.override_time.unwrap_or_else(chrono::Utc::now);
+    let (since, until) = calculate_date_range(&params, now, cfg)?;
 
     // Validate date range
     let max_weeks = if params.by_due_date { 6 } else { 12 }; // 6 weeks vs 3 months
-    validate_date_range(&since, &until, max_weeks)?;
+    validate_date_range(&since, &until, max_weeks, cfg)?;
 
-    // Fetch projects and sections for filtering
-    let projects = gw.projects().await?;
-    let sections = gw.sections().await?;
+    // Fetch projects and sections for filtering, only projects are essential
+    let (projects, sections) = tokio::join!(gw.projects(), gw.sections());
+    let projects = projects?;
+    let sections = available("sections", sections);
 
     let project_id = params.project.optional(&projects)?.map(|p| p.id.clone());
     let section_id = params.section.optional(&sections)?.map(|s| s.id.clone());
+    let limit = clamp_limit(params.limit);
+
+    let query = CompletedQuery {
+        by_due_date: params.by_due_date,
+        project_id: project_id.as_deref(),
+        section_id: section_id.as_deref(),
+        filter: params.filter.as_deref(),
+        limit,
+    };
+    let fetch_all = params.fetch_all || params.compare_previous;
+    let CompletedPages {
+        tasks: all_tasks,
+        pages,
+        more,
+    } = fetch_completed(gw, &query, &since, &until, fetch_all).await?;
+    if more && params.format != Format::Json && output::level() != output::Level::Quiet {
+        // Hints go to stderr, so they don't end up in piped output.
+        eprintln!(
+            "{} Showing page {pages} ({} tasks). Use --all to fetch all pages.",
+            "ℹ".if_supports_color(Stream::Stderr, |text| text.blue()),
+            all_tasks.len()
+        );
+    }
+    let previous = if params.compare_previous {
+        let (prev_since, prev_until) = previous_range(&since, &until, cfg)?;
+        let previous = fetch_completed(gw, &query, &prev_since, &prev_until, true).await?;
+        Some((prev_since, prev_until, previous.tasks.len()))
+    } else {
+        None
+    };
+
+    if params.format == Format::Json {
+        print_json(&all_tasks, &projects, &sections)?;
+    } else if all_tasks.is_empty() {
+        info!("No completed tasks found in the specified date range.");
+    } else {
+        let stats = params
+            .stats
+            .then(|| completed_stats(&all_tasks, &projects, cfg));
+        display_completed_tasks(
+            &all_tasks,
+            projects,
+            sections,
+            &params.group_by,
+            params.show_id,
+            gw,
+            cfg,
+        )
+        .await?;
+
+        info!(
+            "\n{} Total: {} completed tasks",
+            "✓".green(),
+            all_tasks.len()
+        );
+        if let Some(stats) = stats {
+            info!("\n{stats}");
+        }
+    }
+    if let Some((prev_since, prev_until, prev_count)) = previous {
+        info!(
+            "This period ({}): {}, Previous period ({}): {}, {}",
+            range_label(&since, &until, cfg)?,
+            all_tasks.len(),
+            range_label(&prev_since, &prev_until, cfg)?,
+            prev_count,
+            change(all_tasks.len(), prev_count)
+        );
+    }
+
+    Ok(())
+}
+
+/// Picks the timezone the date range is calculated in: `--tz`, the configured timezone or the
+/// timezone of the Todoist account, in that order. Returns [`None`] to keep the configuration
+/// as is, which falls back to the local timezone if the account's can't be fetched.
+async fn range_timezone(params: &Params, gw: &Gateway, cfg: &Config) -> Option<chrono_tz::Tz> {
+    if params.tz.is_some() {
+        return params.tz;
+    }
+    if cfg.timezone.is_some() || (params.since.is_some() && params.until.is_some()) {
+        return None;
+    }
+    let user = gw.user().await.ok()?;
+    user.timezone()?.parse().ok()
+}
 
-    let mut all_tasks = Vec::new();
+/// Prints the completed tasks as a JSON array, with their project and section names resolved.
+fn print_json(tasks: &[Task], projects: &[Project], sections: &[Section]) -> Result<()> {
+    let tasks = tasks
+        .iter()
+        .map(|task| JsonTask {
+            task,
+            project_name: projects
+                .iter()
+                .find(|p| p.id == task.project_id)
+                .map(|p| p.name.as_str()),
+            section_name: task
+                .section_id
+                .as_ref()
+                .and_then(|id| sections.iter().find(|s| s.id == *id))
+                .map(|s| s.name.as_str()),
+            subtasks: Vec::new(),
+        })
+        .collect::<Vec<_>>();
+    info!("{}", serde_json::to_string_pretty(&tasks)?);
+    Ok(())
+}
+
+/// Fetches completed tasks in the given range, following the pagination cursor if `fetch_all`
+/// is set.
+async fn fetch_completed(
+    gw: &Gateway,
+    query: &CompletedQuery<'_>,
+    since: &str,
+    until: &str,
+    fetch_all: bool,
+) -> Result<CompletedPages> {
+    let mut tasks = Vec::new();
     let mut cursor: Option<String> = None;
-    let mut page_count = 0;
+    let mut pages = 0;
 
     loop {
-        let response = if params.by_due_date {
+        let response = if query.by_due_date {
             gw.completed_tasks_by_due_date(CompletedTasksByDueDateParams {
-                since: &since,
-                until: &until,
-                project_id: project_id.as_deref(),
-                section_id: section_id.as_deref(),
-                filter_query: params.filter.as_deref(),
+                since,
+                until,
+                project_id: query.project_id,
+                section_id: query.section_id,
+                filter_query: query.filter,
                 cursor: cursor.as_deref(),
-                limit: Some(params.limit),
+                limit: Some(query.limit),
             })
             .await
             .wrap_err("failed to fetch completed tasks by due date")?
         } else {
             gw.completed_tasks_by_completion_date(CompletedTasksByCompletionDateParams {
-                since: &since,
-                until: &until,
+                since,
+                until,
                 workspace_id: None,
-                project_id: project_id.as_deref(),
-                section_id: section_id.as_deref(),
+                project_id: query.project_id,
+                section_id: query.section_id,
                 parent_id: None,
-                filter_query: params.filter.as_deref(),
+                filter_query: query.filter,
                 cursor: cursor.as_deref(),
-                limit: Some(params.limit),
+                limit: Some(query.limit),
             })
             .await
             .wrap_err("failed to fetch completed tasks by completion date")?
         };
 
-        let tasks_count = response.items.len();
-        all_tasks.extend(response.items);
-        page_count += 1;
-
+        tasks.extend(response.items);
+        pages += 1;
         cursor = response.next_cursor;
 
-        // If not fetching all or no more pages, break
-        if !params.fetch_all || cursor.is_none() {
-            if cursor.is_some() && !params.fetch_all {
-                println!(
-                    "\n{} Showing page {page_count} ({tasks_count} tasks). Use --all to fetch all pages.",
-                    "ℹ".blue()
-                );
-            }
+        if !fetch_all || cursor.is_none() {
             break;
         }
     }
 
-    if all_tasks.is_empty() {
-        println!("No completed tasks found in the specified date range.");
-        return Ok(());
-    }
+    Ok(CompletedPages {
+        tasks,
+        pages,
+        more: cursor.is_some(),
+    })
+}
 
-    // Display tasks
-    display_completed_tasks(&all_tasks, &params.group_by, params.show_id, gw, cfg).await?;
+/// Calculates the range of equal length that immediately precedes the given range.
+fn previous_range(since: &str, until: &str, cfg: &Config) -> Result<(String, String)> {
+    let since = parse_range_date(since, cfg)?;
+    let until = parse_range_date(until, cfg)?;
+    let length = until.signed_duration_since(since);
+    let previous_until = since - chrono::Duration::days(1);
+    day_range(previous_until - length, previous_until, cfg)
+}
 
-    println!(
-        "\n{} Total: {} completed tasks",
-        "✓".green(),
-        all_tasks.len()
-    );
+/// Formats a range as "MM/DD - MM/DD" for display.
+fn range_label(since: &str, until: &str, cfg: &Config) -> Result<String> {
+    Ok(format!(
+        "{} - {}",
+        parse_range_date(since, cfg)?.format("%m/%d"),
+        parse_range_date(until, cfg)?.format("%m/%d")
+    ))
+}
 
-    Ok(())
+/// Describes the relative change from the previous to the current count.
+fn change(current: usize, previous: usize) -> String {
+    if previous == 0 {
+        return "n/a".to_string();
+    }
+    let percent = (current as f64 - previous as f64) / previous as f64 * 100.0;
+    format!("{:+.0}%", percent)
 }
 
 /// Calculates the date range based on convenience flags or uses provided dates.
-/// If no flags or dates are provided, defaults to today.
-fn calculate_date_range(params: &Params) -> Result<(String, String)> {
-    use chrono::{Datelike, Duration, Local, NaiveDate};
+/// If no flags or dates are provided, defaults to the configured default range or today.
+/// Relative flags are calculated from `now`.
+fn calculate_date_range(
+    params: &Params,
+    now: chrono::DateTime<chrono::Utc>,
+    cfg: &Config,
+) -> Result<(String, String)> {
+    use chrono::NaiveDate;
 
-    let now = Local::now();
-    let today = now.date_naive();
+    let today = cfg.local_date(&now);
 
     if let Some(date_str) = &params.date {
         // Specific date: 00:00:00 to 23:59:59 in ISO 8601
@@ -200,81 +405,113 @@ fn calculate_date_range(params: &Params) -> Result<(String, String)> {
             "Invalid date format: '{}'. Use YYYY-MM-DD",
             date_str
         ))?;
-        Ok((
-            format!("{}T00:00:00Z", date.format("%Y-%m-%d")),
-            format!("{}T23:59:59Z", date.format("%Y-%m-%d")),
-        ))
+        day_range(date, date, cfg)
     } else if params.today {
-        // Today: 00:00:00 to 23:59:59 in ISO 8601
-        Ok((
-            format!("{}T00:00:00Z", today.format("%Y-%m-%d")),
-            format!("{}T23:59:59Z", today.format("%Y-%m-%d")),
-        ))
+        range_for(CompletedRange::Today, today, cfg)
     } else if params.yesterday {
-        // Yesterday: 00:00:00 to 23:59:59 in ISO 8601
-        let yesterday = today - Duration::days(1);
-        Ok((
-            format!("{}T00:00:00Z", yesterday.format("%Y-%m-%d")),
-            format!("{}T23:59:59Z", yesterday.format("%Y-%m-%d")),
-        ))
+        range_for(CompletedRange::Yesterday, today, cfg)
     } else if params.this_week {
-        // This week: Monday 00:00:00 to today 23:59:59
-        let days_from_monday = today.weekday().num_days_from_monday() as i64;
-        let monday = today - Duration::days(days_from_monday);
-        Ok((
-            format!("{}T00:00:00Z", monday.format("%Y-%m-%d")),
-            format!("{}T23:59:59Z", today.format("%Y-%m-%d")),
-        ))
+        range_for(CompletedRange::ThisWeek, today, cfg)
     } else if params.last_week {
-        // Last week: Monday to Sunday
-        let days_from_monday = today.weekday().num_days_from_monday() as i64;
-        let last_sunday = today - Duration::days(days_from_monday + 1);
-        let last_monday = last_sunday - Duration::days(6);
-        Ok((
-            format!("{}T00:00:00Z", last_monday.format("%Y-%m-%d")),
-            format!("{}T23:59:59Z", last_sunday.format("%Y-%m-%d")),
-        ))
+        range_for(CompletedRange::LastWeek, today, cfg)
     } else if params.this_month {
-        // This month: 1st to today
-        let first_of_month = NaiveDate::from_ymd_opt(today.year(), today.month(), 1)
-            .ok_or_else(|| color_eyre::eyre::eyre!("Failed to calculate first day of month"))?;
-        Ok((
-            format!("{}T00:00:00Z", first_of_month.format("%Y-%m-%d")),
-            format!("{}T23:59:59Z", today.format("%Y-%m-%d")),
-        ))
+        range_for(CompletedRange::ThisMonth, today, cfg)
     } else if let (Some(since), Some(until)) = (&params.since, &params.until) {
         // Use provided dates
         Ok((since.clone(), until.clone()))
     } else {
-        // Default: today
-        Ok((
-            format!("{}T00:00:00Z", today.format("%Y-%m-%d")),
-            format!("{}T23:59:59Z", today.format("%Y-%m-%d")),
-        ))
+        range_for(cfg.completed_default_range.unwrap_or_default(), today, cfg)
     }
 }
 
-/// Validates that the date range is within the specified maximum weeks.
-fn validate_date_range(since: &str, until: &str, max_weeks: i64) -> Result<()> {
-    use chrono::NaiveDate;
+/// Clamps the page size to the maximum the API accepts, warning if it had to be lowered.
+fn clamp_limit(limit: u32) -> u32 {
+    if limit > COMPLETED_TASKS_MAX_LIMIT {
+        eprintln!(
+            "--limit {limit} exceeds the API maximum, using {COMPLETED_TASKS_MAX_LIMIT} instead."
+        );
+        COMPLETED_TASKS_MAX_LIMIT
+    } else {
+        limit
+    }
+}
+
+/// Calculates the date range of a range keyword relative to the given day, in the timezone and
+/// with the week start of the configuration.
+pub(crate) fn range_for(
+    range: CompletedRange,
+    today: chrono::NaiveDate,
+    cfg: &Config,
+) -> Result<(String, String)> {
+    use chrono::{Datelike, Duration, NaiveDate};
+
+    let week_start = cfg.week_start;
 
-    let parse_date = |s: &str| -> Result<NaiveDate> {
-        // Try YYYY-MM-DD format first
-        if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
-            return Ok(date);
+    match range {
+        // Today: 00:00:00 to 23:59:59 in ISO 8601
+        CompletedRange::Today => day_range(today, today, cfg),
+        CompletedRange::Yesterday => {
+            let yesterday = today - Duration::days(1);
+            day_range(yesterday, yesterday, cfg)
         }
-        // Try ISO 8601 with time
-        if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(s) {
-            return Ok(dt.date_naive());
+        CompletedRange::ThisWeek => {
+            // This week: start of the week 00:00:00 to today 23:59:59
+            day_range(week_start.first_day(today), today, cfg)
         }
-        Err(color_eyre::eyre::eyre!(
-            "Invalid date format: '{}'. Use YYYY-MM-DD or ISO 8601",
-            s
-        ))
-    };
+        CompletedRange::LastWeek => {
+            // Last week: the seven days before the start of this week
+            let last_end = week_start.first_day(today) - Duration::days(1);
+            day_range(last_end - Duration::days(6), last_end, cfg)
+        }
+        CompletedRange::ThisMonth => {
+            // This month: 1st to today
+            let first_of_month = NaiveDate::from_ymd_opt(today.year(), today.month(), 1)
+                .ok_or_else(|| color_eyre::eyre::eyre!("Failed to calculate first day of month"))?;
+            day_range(first_of_month, today, cfg)
+        }
+    }
+}
+
+/// Formats the range from the start of `since` to the end of `until` in the configured timezone
+/// as UTC times in ISO 8601.
+fn day_range(
+    since: chrono::NaiveDate,
+    until: chrono::NaiveDate,
+    cfg: &Config,
+) -> Result<(String, String)> {
+    let start = since.and_time(chrono::NaiveTime::MIN);
+    let end = until.and_hms_opt(23, 59, 59).unwrap_or(start);
+    Ok((
+        cfg.utc_datetime(&start)?
+            .format("%Y-%m-%dT%H:%M:%SZ")
+            .to_string(),
+        cfg.utc_datetime(&end)?
+            .format("%Y-%m-%dT%H:%M:%SZ")
+            .to_string(),
+    ))
+}
 
-    let since_date = parse_date(since)?;
-    let until_date = parse_date(until)?;
+/// Parses the date of a range boundary, given as YYYY-MM-DD or ISO 8601. Times are converted to
+/// the configured timezone first.
+fn parse_range_date(s: &str, cfg: &Config) -> Result<chrono::NaiveDate> {
+    // Try YYYY-MM-DD format first
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(date);
+    }
+    // Try ISO 8601 with time
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(s) {
+        return Ok(cfg.local_date(&dt));
+    }
+    Err(color_eyre::eyre::eyre!(
+        "Invalid date format: '{}'. Use YYYY-MM-DD or ISO 8601",
+        s
+    ))
+}
+
+/// Validates that the date range is within the specified maximum weeks.
+fn validate_date_range(since: &str, until: &str, max_weeks: i64, cfg: &Config) -> Result<()> {
+    let since_date = parse_range_date(since, cfg)?;
+    let until_date = parse_range_date(until, cfg)?;
 
     if until_date < since_date {
         return Err(color_eyre::eyre::eyre!(
@@ -300,21 +537,22 @@ fn validate_date_range(since: &str, until: &str, max_weeks: i64) -> Result<()> {
 
 /// Displays completed tasks with optional grouping.
 async fn display_completed_tasks(
-    tasks: &[crate::api::rest::Task],
-    group_by: &Option<GroupBy>,
+    tasks: &[Task],
+    projects: Vec<Project>,
+    sections: Vec<Section>,
+    group_by: &Option<CompletedGroupBy>,
     show_id: bool,
     gw: &Gateway,
     cfg: &Config,
 ) -> Result<()> {
     use crate::api::tree::Tree;
-    use crate::tasks::state::State;
 
     // Convert tasks to Tree structure for display
-    let tasks_tree: Vec<Tree<crate::api::rest::Task>> =
+    let tasks_tree: Vec<Tree<Task>> =
         Tree::from_items(tasks.to_vec()).wrap_err("failed to build task tree")?;
 
-    // Fetch related data for display
-    let (projects, sections, labels) = tokio::try_join!(gw.projects(), gw.sections(), gw.labels())?;
+    // Labels are only used for display, so we can do without them
+    let labels = available("labels", gw.labels().await);
 
     let state = State {
         tasks: tasks_tree,
@@ -322,14 +560,390 @@ async fn display_completed_tasks(
         sections: sections.into_iter().map(|s| (s.id.clone(), s)).collect(),
         labels: labels.into_iter().map(|l| (l.name.clone(), l)).collect(),
         config: cfg,
+        highlights: Vec::new(),
+        fields: None,
     };
 
-    // Display with grouping if specified
-    if let Some(GroupBy::Project) = group_by {
-        super::list::list_tasks_grouped_by_project(&state.tasks, &state, None, show_id);
-    } else {
-        super::list::list_tasks_with_sort(&state.tasks, &state, None, show_id);
+    write_completed_tasks(&state, group_by, show_id, &mut output::stdout())
+}
+
+/// Writes the completed tasks of the state, grouped if specified.
+fn write_completed_tasks(
+    state: &State,
+    group_by: &Option<CompletedGroupBy>,
+    show_id: bool,
+    out: &mut dyn Write,
+) -> Result<()> {
+    let group_by = match group_by {
+        None => return super::list::list_tasks_with_sort(&state.tasks, state, None, show_id, out),
+        Some(CompletedGroupBy::Day) => {
+            return super::list::list_tasks_grouped(
+                &state.tasks,
+                state,
+                |task| {
+                    completion_day(task, state.config)
+                        .map(|day| (day.clone(), day))
+                        .into_iter()
+                        .collect()
+                },
+                "(unknown)",
+                None,
+                show_id,
+                out,
+            );
+        }
+        Some(CompletedGroupBy::Project) => GroupBy::Project,
+        Some(CompletedGroupBy::Section) => GroupBy::Section,
+        Some(CompletedGroupBy::Label) => GroupBy::Label,
+    };
+    super::list::list_tasks_grouped_by(&state.tasks, state, &group_by, None, show_id, out)
+}
+
+/// Amounts of completed tasks, as shown with `--stats`.
+#[derive(Debug, Default, PartialEq)]
+struct CompletedStats {
+    total: usize,
+    /// Tasks per project name, tasks of unknown projects are counted by project ID.
+    projects: Vec<(String, usize)>,
+    /// Tasks per day of completion, see [`completion_day`]. Tasks without a completion time come
+    /// last, like with `--group-by day`.
+    days: Vec<(String, usize)>,
+    /// Tasks per priority, named like in the apps.
+    priorities: Vec<(String, usize)>,
+}
+
+impl Display for CompletedStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let groups = [
+            ("Project", &self.projects),
+            ("Day", &self.days),
+            ("Priority", &self.priorities),
+        ];
+        // Names are indented below their header, so they are two characters wider.
+        let width = groups
+            .iter()
+            .flat_map(|(_, counts)| counts.iter().map(|(name, _)| name.chars().count() + 2))
+            .chain(groups.iter().map(|(header, _)| header.len()))
+            .max()
+            .unwrap_or(0);
+        for (header, counts) in groups {
+            writeln!(f, "{header:width$}  {:>5}", "Tasks")?;
+            for (name, count) in counts {
+                writeln!(f, "{:width$}  {count:>5}", format!("  {name}"))?;
+            }
+        }
+        write!(f, "{:width$}  {:>5}", "Total", self.total)
     }
+}
 
-    Ok(())
+/// Counts the completed tasks per project, day of completion and priority.
+fn completed_stats(tasks: &[Task], projects: &[Project], cfg: &Config) -> CompletedStats {
+    let mut by_project: BTreeMap<String, usize> = BTreeMap::new();
+    let mut by_day: BTreeMap<Option<String>, usize> = BTreeMap::new();
+    let mut by_priority: BTreeMap<String, usize> = BTreeMap::new();
+    for task in tasks {
+        let project = projects
+            .iter()
+            .find(|p| p.id == task.project_id)
+            .map_or(&task.project_id, |p| &p.name);
+        *by_project.entry(project.clone()).or_default() += 1;
+        *by_day.entry(completion_day(task, cfg)).or_default() += 1;
+        // The API counts priorities the other way around than the apps.
+        let priority = format!("p{}", 5 - task.priority as u8);
+        *by_priority.entry(priority).or_default() += 1;
+    }
+    let unknown_day = by_day.remove(&None);
+    CompletedStats {
+        total: tasks.len(),
+        projects: by_project.into_iter().collect(),
+        days: by_day
+            .into_iter()
+            .filter_map(|(day, count)| Some((day?, count)))
+            .chain(unknown_day.map(|count| ("(unknown)".to_string(), count)))
+            .collect(),
+        priorities: by_priority.into_iter().collect(),
+    }
+}
+
+/// Returns the local day a task was completed on, which sorts chronologically as a group name.
+fn completion_day(task: &Task, cfg: &Config) -> Option<String> {
+    let completed_at: chrono::DateTime<chrono::Utc> = task.completed_at.as_deref()?.parse().ok()?;
+    Some(
+        cfg.local_date(&completed_at)
+            .format("%Y-%m-%d %a")
+            .to_string(),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::api::rest::Priority as RESTPriority;
+    use crate::{api::tree::Tree, config::WeekStart};
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use clap::Parser;
+
+    fn zoned(timezone: chrono_tz::Tz) -> Config {
+        Config {
+            timezone: Some(timezone),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn clamps_limit() {
+        assert_eq!(clamp_limit(50), 50);
+        assert_eq!(clamp_limit(200), 200);
+        assert_eq!(clamp_limit(500), COMPLETED_TASKS_MAX_LIMIT);
+    }
+
+    #[test]
+    fn previous_ranges() {
+        let cfg = zoned(chrono_tz::UTC);
+        assert_eq!(
+            previous_range("2025-10-06T00:00:00Z", "2025-10-08T23:59:59Z", &cfg).unwrap(),
+            day_range(
+                NaiveDate::from_ymd_opt(2025, 10, 3).unwrap(),
+                NaiveDate::from_ymd_opt(2025, 10, 5).unwrap(),
+                &cfg
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            previous_range("2025-10-08", "2025-10-08", &cfg).unwrap(),
+            (
+                "2025-10-07T00:00:00Z".to_string(),
+                "2025-10-07T23:59:59Z".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn week_ranges() {
+        let range = |range, day, week_start| {
+            let today = NaiveDate::from_ymd_opt(2025, 10, day).unwrap();
+            let cfg = Config {
+                week_start,
+                ..zoned(chrono_tz::UTC)
+            };
+            let (since, until) = range_for(range, today, &cfg).unwrap();
+            (since[..10].to_string(), until[..10].to_string())
+        };
+        let dates = |since: &str, until: &str| (since.to_string(), until.to_string());
+
+        // 2025-10-05 is a Sunday, 2025-10-06 a Monday.
+        assert_eq!(
+            range(CompletedRange::ThisWeek, 5, WeekStart::Monday),
+            dates("2025-09-29", "2025-10-05")
+        );
+        assert_eq!(
+            range(CompletedRange::ThisWeek, 5, WeekStart::Sunday),
+            dates("2025-10-05", "2025-10-05")
+        );
+        assert_eq!(
+            range(CompletedRange::ThisWeek, 6, WeekStart::Monday),
+            dates("2025-10-06", "2025-10-06")
+        );
+        assert_eq!(
+            range(CompletedRange::ThisWeek, 6, WeekStart::Sunday),
+            dates("2025-10-05", "2025-10-06")
+        );
+        assert_eq!(
+            range(CompletedRange::LastWeek, 5, WeekStart::Monday),
+            dates("2025-09-22", "2025-09-28")
+        );
+        assert_eq!(
+            range(CompletedRange::LastWeek, 5, WeekStart::Sunday),
+            dates("2025-09-28", "2025-10-04")
+        );
+        assert_eq!(
+            range(CompletedRange::LastWeek, 6, WeekStart::Sunday),
+            dates("2025-09-28", "2025-10-04")
+        );
+    }
+
+    #[test]
+    fn ranges_in_timezone() {
+        // 00:30 on 2025-10-06 in Seoul (UTC+9), still the 5th in UTC.
+        let cfg = Config {
+            override_time: Some(Utc.with_ymd_and_hms(2025, 10, 5, 15, 30, 0).unwrap()),
+            ..zoned(chrono_tz::Asia::Seoul)
+        };
+        let now = cfg.override_time.unwrap();
+        let range = |flag: &str| {
+            let params = Params::try_parse_from(["completed", flag]).unwrap();
+            calculate_date_range(&params, now, &cfg).unwrap()
+        };
+        let dates = |since: &str, until: &str| (since.to_string(), until.to_string());
+
+        assert_eq!(
+            range("--today"),
+            dates("2025-10-05T15:00:00Z", "2025-10-06T14:59:59Z")
+        );
+        assert_eq!(
+            range("--yesterday"),
+            dates("2025-10-04T15:00:00Z", "2025-10-05T14:59:59Z")
+        );
+        // 2025-10-06 is a Monday in Seoul, so the week only just started.
+        assert_eq!(
+            range("--this-week"),
+            dates("2025-10-05T15:00:00Z", "2025-10-06T14:59:59Z")
+        );
+        assert_eq!(
+            range_label("2025-10-05T15:00:00Z", "2025-10-06T14:59:59Z", &cfg).unwrap(),
+            "10/06 - 10/06"
+        );
+        assert_eq!(
+            previous_range("2025-10-05T15:00:00Z", "2025-10-06T14:59:59Z", &cfg).unwrap(),
+            dates("2025-10-04T15:00:00Z", "2025-10-05T14:59:59Z")
+        );
+
+        // The same instant in UTC is still on the Sunday.
+        let cfg = Config {
+            timezone: Some(chrono_tz::UTC),
+            ..cfg
+        };
+        let params = Params::try_parse_from(["completed", "--today"]).unwrap();
+        assert_eq!(
+            calculate_date_range(&params, now, &cfg).unwrap(),
+            dates("2025-10-05T00:00:00Z", "2025-10-05T23:59:59Z")
+        );
+    }
+
+    #[test]
+    fn ranges_across_boundaries() {
+        let cfg = zoned(chrono_tz::UTC);
+        let range = |args: &[&str], now: &str| {
+            let params = Params::try_parse_from([&["completed"], args].concat()).unwrap();
+            let now = chrono::DateTime::parse_from_rfc3339(now).unwrap().to_utc();
+            calculate_date_range(&params, now, &cfg).unwrap()
+        };
+        let dates = |since: &str, until: &str| (since.to_string(), until.to_string());
+
+        // First day of the month only covers that day.
+        assert_eq!(
+            range(&["--this-month"], "2025-11-01T08:00:00Z"),
+            dates("2025-11-01T00:00:00Z", "2025-11-01T23:59:59Z")
+        );
+        assert_eq!(
+            range(&["--this-month"], "2024-02-29T23:59:59Z"),
+            dates("2024-02-01T00:00:00Z", "2024-02-29T23:59:59Z")
+        );
+        // Yesterday and weeks reach back into the previous year.
+        assert_eq!(
+            range(&["--yesterday"], "2026-01-01T00:00:00Z"),
+            dates("2025-12-31T00:00:00Z", "2025-12-31T23:59:59Z")
+        );
+        // 2026-01-01 is a Thursday.
+        assert_eq!(
+            range(&["--this-week"], "2026-01-01T12:00:00Z"),
+            dates("2025-12-29T00:00:00Z", "2026-01-01T23:59:59Z")
+        );
+        assert_eq!(
+            range(&["--last-week"], "2026-01-01T12:00:00Z"),
+            dates("2025-12-22T00:00:00Z", "2025-12-28T23:59:59Z")
+        );
+        // Explicit dates are passed on as they are.
+        assert_eq!(
+            range(
+                &["--since", "2025-10-01", "--until", "2025-10-06"],
+                "2026-01-01T12:00:00Z"
+            ),
+            dates("2025-10-01", "2025-10-06")
+        );
+        assert_eq!(
+            range(&["--date", "2025-10-10"], "2026-01-01T12:00:00Z"),
+            dates("2025-10-10T00:00:00Z", "2025-10-10T23:59:59Z")
+        );
+    }
+
+    #[test]
+    fn changes() {
+        assert_eq!(change(34, 28), "+21%");
+        assert_eq!(change(14, 28), "-50%");
+        assert_eq!(change(3, 0), "n/a");
+    }
+
+    #[test]
+    fn groups_by_day() {
+        let cfg = zoned(chrono_tz::Asia::Seoul);
+        let completed = |id: &str, at: Option<&str>| {
+            let mut task = Task::new(id, id);
+            task.completed_at = at.map(str::to_string);
+            Tree::new(task)
+        };
+        let state = State::for_tests(
+            vec![
+                completed("Late", Some("2025-10-06T14:30:00.000000Z")),
+                completed("Past midnight", Some("2025-10-06T15:30:00.000000Z")),
+                completed("Morning", Some("2025-10-07T01:00:00.000000Z")),
+                completed("Whenever", None),
+            ],
+            &cfg,
+        );
+        let mut out = Vec::new();
+        write_completed_tasks(&state, &Some(CompletedGroupBy::Day), false, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\n[2025-10-06 Mon] (1/1 tasks)\n  Late\n\
+             \n[2025-10-07 Tue] (2/2 tasks)\n  Morning\n  Past midnight\n\
+             \n[(unknown)] (1/1 tasks)\n  Whenever\n"
+        );
+    }
+
+    #[test]
+    fn aggregates_stats() {
+        let cfg = zoned(chrono_tz::Asia::Seoul);
+        let completed = |project: &str, at: Option<&str>, priority| {
+            let mut task = Task::new("1", "task");
+            task.project_id = project.to_string();
+            task.completed_at = at.map(str::to_string);
+            task.priority = priority;
+            task
+        };
+        let tasks = [
+            completed("1", Some("2025-10-06T14:30:00Z"), RESTPriority::Urgent),
+            completed("1", Some("2025-10-06T15:30:00Z"), RESTPriority::Normal),
+            completed("2", Some("2025-10-07T01:00:00Z"), RESTPriority::Urgent),
+            completed("unknown", None, RESTPriority::High),
+        ];
+        let projects = [Project::new("1", "Inbox"), Project::new("2", "Work")];
+        let counts = |counts: &[(&str, usize)]| {
+            counts
+                .iter()
+                .map(|(name, count)| (name.to_string(), *count))
+                .collect::<Vec<_>>()
+        };
+
+        let stats = completed_stats(&tasks, &projects, &cfg);
+        assert_eq!(
+            stats,
+            CompletedStats {
+                total: 4,
+                projects: counts(&[("Inbox", 2), ("Work", 1), ("unknown", 1)]),
+                days: counts(&[
+                    ("2025-10-06 Mon", 1),
+                    ("2025-10-07 Tue", 2),
+                    ("(unknown)", 1)
+                ]),
+                priorities: counts(&[("p1", 2), ("p3", 1), ("p4", 1)]),
+            }
+        );
+        assert_eq!(
+            stats.to_string(),
+            "Project           Tasks\n  \
+             Inbox               2\n  \
+             Work                1\n  \
+             unknown             1\n\
+             Day               Tasks\n  \
+             2025-10-06 Mon      1\n  \
+             2025-10-07 Tue      2\n  \
+             (unknown)           1\n\
+             Priority          Tasks\n  \
+             p1                  2\n  \
+             p3                  1\n  \
+             p4                  1\n\
+             Total                 4"
+        );
+    }
 }
@@ -6,6 +6,7 @@ use crate::{
     api::rest::{CreateTask, Gateway, TaskDue},
     config::Config,
     interactive,
+    output::info,
 };
 
 use super::add::create_task;
@@ -54,7 +55,7 @@ impl From<usize> for Selection {
 
 pub async fn create(_params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
     let mut create = CreateTask {
-        content: interactive::input_content("")?,
+        content: interactive::input_content("", &cfg.theme)?,
         ..Default::default()
     };
 
@@ -96,31 +97,37 @@ pub async fn create(_params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
             .iter()
             .map(|(name, content)| format!("{}: {}", name.bold(), content)),
         );
-        let selection = match interactive::select("Edit task fields or submit", &items)? {
+        let selection = match interactive::select("Edit task fields or submit", &items, &cfg.theme)?
+        {
             Some(0) => break,
             Some(s) => Selection::from(s - 1),
             None => {
-                println!("No selection was made");
+                info!("No selection was made");
                 return Ok(());
             }
         };
         match selection {
-            Selection::TaskName => create.content = interactive::input_content(&create.content)?,
-            Selection::Due => due = interactive::input_optional("Due", due)?,
+            Selection::TaskName => {
+                create.content = interactive::input_content(&create.content, &cfg.theme)?
+            }
+            Selection::Due => due = interactive::input_optional("Due", due, &cfg.theme)?,
             Selection::Description => {
-                create.description = interactive::input_optional("Description", create.description)?
+                create.description =
+                    interactive::input_optional("Description", create.description, &cfg.theme)?
             }
             Selection::Project => {
-                if let Some((p, s)) = interactive::input_project(&projects, &sections)? {
+                if let Some((p, s)) = interactive::input_project(&projects, &sections, &cfg.theme)?
+                {
                     create.project_id = Some(p);
                     create.section_id = s;
                 };
             }
-            Selection::Priority => create.priority = interactive::input_priority()?,
+            Selection::Priority => create.priority = interactive::input_priority(&cfg.theme)?,
         }
     }
     if let Some(due) = due {
         create.due = Some(TaskDue::String(due));
     }
-    create_task(create, None, None, &[], gw, cfg).await
+    create_task(create, None, None, &[], gw, cfg).await?;
+    Ok(())
 }
@@ -0,0 +1,334 @@
+//! Helpers to parse dates and durations given on the command line.
+use chrono::{
+    DateTime, Datelike, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc,
+    Weekday,
+};
+use color_eyre::{Result, eyre::eyre};
+
+use crate::{api::rest::DurationUnit, config::Config};
+
+/// Parses a point in time for filtering.
+///
+/// Accepts `YYYY-MM-DD` (start of that day), RFC 3339 datetimes, `today`, `yesterday` or an age
+/// like `3d` or `2w` that is subtracted from the current time. Dates are interpreted in the
+/// configured timezone.
+pub fn parse_since(input: &str, cfg: &Config) -> Result<DateTime<Utc>> {
+    let now = cfg.override_time.unwrap_or_else(Utc::now);
+    let input = input.trim();
+    match input.to_lowercase().as_str() {
+        "today" => return start_of_day(cfg.local_date(&now), cfg),
+        "yesterday" => return start_of_day(cfg.local_date(&now) - Duration::days(1), cfg),
+        _ => {}
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return start_of_day(date, cfg);
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(input) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    if let Ok(age) = parse_age(input) {
+        return Ok(now - age);
+    }
+    Err(eyre!(
+        "Invalid date: '{input}'. Use YYYY-MM-DD, an RFC 3339 datetime, today, yesterday or an age like 3d"
+    ))
+}
+
+/// Parses a calendar day given as `YYYY-MM-DD` or a simple phrase, relative to the configured
+/// timezone.
+///
+/// Understood phrases are `today`, `tomorrow`, `yesterday`, a weekday like `friday` or
+/// `next friday` (the first one after today), `next week` (the start of next week), `next month`
+/// (its 1st) and offsets like `in 3 days`, `in a week` or `in 2 months`.
+pub fn parse_date(input: &str, cfg: &Config) -> Result<NaiveDate> {
+    let today = cfg.local_date(&cfg.override_time.unwrap_or_else(Utc::now));
+    let input = input.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return Ok(date);
+    }
+    relative_date(&input.to_lowercase(), today, cfg).ok_or_else(|| {
+        eyre!(
+            "Invalid date: '{input}'. Use YYYY-MM-DD, today, tomorrow, a weekday like 'next friday' or an offset like 'in 3 days'"
+        )
+    })
+}
+
+/// Resolves a lowercase date phrase against today, see [`parse_date`].
+fn relative_date(phrase: &str, today: NaiveDate, cfg: &Config) -> Option<NaiveDate> {
+    match phrase {
+        "today" => return Some(today),
+        "tomorrow" => return Some(today + Duration::days(1)),
+        "yesterday" => return Some(today - Duration::days(1)),
+        "next week" => return Some(cfg.week_start.first_day(today) + Duration::weeks(1)),
+        "next month" => return today.with_day(1)?.checked_add_months(Months::new(1)),
+        _ => {}
+    }
+    let weekday = phrase.strip_prefix("next ").unwrap_or(phrase);
+    if let Ok(weekday) = weekday.parse::<Weekday>() {
+        let days =
+            (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+        let days = if days == 0 { 7 } else { days };
+        return Some(today + Duration::days(days.into()));
+    }
+    let (amount, unit) = phrase.strip_prefix("in ")?.split_once(' ')?;
+    let amount: u32 = match amount {
+        "a" | "an" | "one" => 1,
+        amount => amount.parse().ok()?,
+    };
+    match unit.trim_end_matches('s') {
+        "day" => today.checked_add_days(chrono::Days::new(amount.into())),
+        "week" => today.checked_add_days(chrono::Days::new(u64::from(amount) * 7)),
+        "month" => today.checked_add_months(Months::new(amount)),
+        _ => None,
+    }
+}
+
+/// Parses an exact point in time given as `YYYY-MM-DDTHH:MM`, optionally with seconds or a space
+/// instead of the `T`, in the configured timezone. RFC 3339 datetimes with an offset are taken as
+/// they are.
+pub fn parse_datetime(input: &str, cfg: &Config) -> Result<DateTime<Utc>> {
+    let input = input.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(input) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    [
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M:%S",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(input, format).ok())
+    .ok_or_else(|| {
+        eyre!("Invalid date and time: '{input}'. Use YYYY-MM-DDTHH:MM, like 2025-12-25T14:00")
+    })
+    .and_then(|dt| cfg.utc_datetime(&dt))
+}
+
+/// Parses a task duration like `90m`, `1h30m` or `2d` into the amount and unit the API expects.
+///
+/// Durations in whole days are kept in days, everything else is converted into minutes. The
+/// `<amount>:<unit>` format, like `30:minute` or `2:day`, is accepted as well.
+pub fn parse_duration(input: &str) -> Result<(u32, DurationUnit)> {
+    let input = input.trim();
+    let invalid = || {
+        eyre!(
+            "Invalid duration: '{input}'. Use minutes, hours or days like 90m, 1h30m or 2d, or '<amount>:<unit>' like 30:minute"
+        )
+    };
+    let (amount, unit) = if let Some((amount, unit)) = input.split_once(':') {
+        let amount: u32 = amount.parse().map_err(|_| invalid())?;
+        let unit = match unit {
+            "minute" => DurationUnit::Minute,
+            "day" => DurationUnit::Day,
+            _ => return Err(invalid()),
+        };
+        (amount, unit)
+    } else {
+        let (mut days, mut minutes) = (0u32, 0u32);
+        let mut rest = input;
+        while !rest.is_empty() {
+            let split = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .ok_or_else(invalid)?;
+            let amount: u32 = rest[..split].parse().map_err(|_| invalid())?;
+            let unit = rest[split..].chars().next().ok_or_else(invalid)?;
+            let (total, factor) = match unit {
+                'd' => (&mut days, 1),
+                'h' => (&mut minutes, 60),
+                'm' => (&mut minutes, 1),
+                _ => return Err(invalid()),
+            };
+            *total = amount
+                .checked_mul(factor)
+                .and_then(|a| total.checked_add(a))
+                .ok_or_else(invalid)?;
+            rest = &rest[split + 1..];
+        }
+        if minutes == 0 {
+            (days, DurationUnit::Day)
+        } else {
+            let minutes = days
+                .checked_mul(24 * 60)
+                .and_then(|d| d.checked_add(minutes))
+                .ok_or_else(invalid)?;
+            (minutes, DurationUnit::Minute)
+        }
+    };
+    if amount == 0 {
+        return Err(eyre!("Duration must be greater than zero."));
+    }
+    Ok((amount, unit))
+}
+
+/// Parses an age given in days or weeks, like `30d` or `2w`.
+pub fn parse_age(input: &str) -> Result<Duration> {
+    let input = input.trim();
+    let invalid = || eyre!("Invalid age: '{input}'. Use a number of days or weeks, like 30d or 2w");
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(invalid)?;
+    let (amount, unit) = input.split_at(split);
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+    match unit {
+        "d" | "day" | "days" => Ok(Duration::days(amount)),
+        "w" | "week" | "weeks" => Ok(Duration::weeks(amount)),
+        _ => Err(invalid()),
+    }
+}
+
+/// Returns the beginning of the given day in the configured timezone.
+pub fn start_of_day(date: NaiveDate, cfg: &Config) -> Result<DateTime<Utc>> {
+    let midnight = date.and_time(NaiveTime::MIN);
+    let start = match cfg.timezone {
+        Some(tz) => tz
+            .from_local_datetime(&midnight)
+            .earliest()
+            .map(|d| d.with_timezone(&Utc)),
+        None => chrono::Local
+            .from_local_datetime(&midnight)
+            .earliest()
+            .map(|d| d.with_timezone(&Utc)),
+    };
+    start.ok_or_else(|| eyre!("{date} has no valid start of day"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn config() -> Config {
+        Config {
+            override_time: Some("2025-10-10T12:00:00Z".parse().unwrap()),
+            timezone: Some(chrono_tz::Asia::Seoul),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parses_absolute() {
+        let cfg = config();
+        assert_eq!(
+            parse_since("2025-10-01", &cfg).unwrap(),
+            "2025-09-30T15:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(
+            parse_since("2025-10-01T10:00:00Z", &cfg).unwrap(),
+            "2025-10-01T10:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+    }
+
+    #[test]
+    fn parses_relative() {
+        let cfg = config();
+        assert_eq!(
+            parse_since("today", &cfg).unwrap(),
+            "2025-10-09T15:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(
+            parse_since("yesterday", &cfg).unwrap(),
+            "2025-10-08T15:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(
+            parse_since("3d", &cfg).unwrap(),
+            "2025-10-07T12:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert!(parse_since("whenever", &cfg).is_err());
+    }
+
+    #[test]
+    fn parses_date() {
+        let cfg = config();
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(parse_date("2025-10-01", &cfg).unwrap(), date(2025, 10, 1));
+        assert_eq!(parse_date("today", &cfg).unwrap(), date(2025, 10, 10));
+        assert_eq!(parse_date("Tomorrow", &cfg).unwrap(), date(2025, 10, 11));
+        assert_eq!(parse_date("yesterday", &cfg).unwrap(), date(2025, 10, 9));
+        assert!(parse_date("3d", &cfg).is_err());
+    }
+
+    #[test]
+    fn parses_relative_date() {
+        // 2025-10-10 is a Friday.
+        let cfg = config();
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(parse_date("next friday", &cfg).unwrap(), date(2025, 10, 17));
+        assert_eq!(parse_date("Monday", &cfg).unwrap(), date(2025, 10, 13));
+        assert_eq!(parse_date("sat", &cfg).unwrap(), date(2025, 10, 11));
+        assert_eq!(parse_date("next week", &cfg).unwrap(), date(2025, 10, 13));
+        assert_eq!(parse_date("next month", &cfg).unwrap(), date(2025, 11, 1));
+        assert_eq!(parse_date("in 3 days", &cfg).unwrap(), date(2025, 10, 13));
+        assert_eq!(parse_date("in a week", &cfg).unwrap(), date(2025, 10, 17));
+        assert_eq!(parse_date("in 2 months", &cfg).unwrap(), date(2025, 12, 10));
+        assert!(parse_date("in a while", &cfg).is_err());
+        assert!(parse_date("next fortnight", &cfg).is_err());
+    }
+
+    #[test]
+    fn parses_datetime() {
+        let cfg = config();
+        let utc = |s: &str| s.parse::<DateTime<Utc>>().unwrap();
+        assert_eq!(
+            parse_datetime("2025-12-25T14:00", &cfg).unwrap(),
+            utc("2025-12-25T05:00:00Z")
+        );
+        assert_eq!(
+            parse_datetime("2025-12-25 14:00:30", &cfg).unwrap(),
+            utc("2025-12-25T05:00:30Z")
+        );
+        assert_eq!(
+            parse_datetime("2025-12-25T14:00:00+01:00", &cfg).unwrap(),
+            utc("2025-12-25T13:00:00Z")
+        );
+        assert!(parse_datetime("2025-12-25", &cfg).is_err());
+        assert!(parse_datetime("christmas at 2", &cfg).is_err());
+
+        // Clocks in Berlin skip from 02:00 to 03:00.
+        let cfg = Config {
+            timezone: Some(chrono_tz::Europe::Berlin),
+            ..config()
+        };
+        assert!(parse_datetime("2025-03-30T02:30", &cfg).is_err());
+        assert_eq!(
+            parse_datetime("2025-03-30T03:30", &cfg).unwrap(),
+            utc("2025-03-30T01:30:00Z")
+        );
+    }
+
+    #[test]
+    fn parses_duration() {
+        let minutes = |m| Some((m, DurationUnit::Minute));
+        let ok = |input| parse_duration(input).ok();
+        assert_eq!(ok("1h30m"), minutes(90));
+        assert_eq!(ok("90m"), minutes(90));
+        assert_eq!(ok("2h"), minutes(120));
+        assert_eq!(ok("1d2h"), minutes(1560));
+        assert_eq!(ok("2d"), Some((2, DurationUnit::Day)));
+        assert_eq!(ok("30:minute"), minutes(30));
+        assert_eq!(ok("2:day"), Some((2, DurationUnit::Day)));
+        for invalid in [
+            "",
+            "90",
+            "1x",
+            "h",
+            "0m",
+            "1h-30m",
+            "30:hour",
+            "5000000000m",
+        ] {
+            assert!(
+                parse_duration(invalid).is_err(),
+                "{invalid} should be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn parses_age() {
+        assert_eq!(parse_age("30d").unwrap(), Duration::days(30));
+        assert_eq!(parse_age("2w").unwrap(), Duration::weeks(2));
+        assert!(parse_age("2m").is_err());
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("12").is_err());
+    }
+}
@@ -0,0 +1,77 @@
+use color_eyre::{Result, eyre::WrapErr};
+
+use crate::{
+    api::rest::{CreateTask, Gateway, Task, TaskDue},
+    config::Config,
+    tasks::add::create_task,
+};
+
+use super::filter;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    #[clap(flatten)]
+    pub task: filter::TaskOrInteractive,
+    /// How many copies of the task to create.
+    #[arg(
+        short = 'n',
+        long = "count",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    count: u32,
+    /// Name (title) of the copies, instead of the name of the original task.
+    #[arg(long = "content")]
+    content: Option<String>,
+}
+
+/// Creates copies of a task in the same place, with the same description, labels, priority, due
+/// date, deadline and duration. Subtasks and comments are not copied.
+pub async fn duplicate(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let (id, state) = params
+        .task
+        .task(gw, cfg)
+        .await
+        .wrap_err("no task selected for duplicating")?;
+    let task = gw.task(&id).await?;
+    let project = state.projects.get(&task.project_id);
+    let section = task
+        .section_id
+        .as_ref()
+        .and_then(|id| state.sections.get(id));
+    let labels = task
+        .labels
+        .iter()
+        .filter_map(|l| state.labels.get(l).cloned())
+        .collect::<Vec<_>>();
+
+    for _ in 0..params.count {
+        let create = copy_task(&task, params.content.clone());
+        create_task(create, project, section, &labels, gw, cfg).await?;
+    }
+    Ok(())
+}
+
+/// Builds the command to create a copy of the task, optionally with a different name.
+fn copy_task(task: &Task, content: Option<String>) -> CreateTask {
+    CreateTask {
+        content: content.unwrap_or_else(|| task.content.clone()),
+        description: (!task.description.is_empty()).then(|| task.description.clone()),
+        project_id: Some(task.project_id.clone()),
+        section_id: task.section_id.clone(),
+        parent_id: task.parent_id.clone(),
+        labels: (!task.labels.is_empty()).then(|| task.labels.clone()),
+        priority: Some(task.priority),
+        // The due string keeps recurring tasks recurring.
+        due: task.due.as_ref().map(|d| TaskDue::String(d.string.clone())),
+        due_lang: task.due.as_ref().map(|d| d.lang.clone()),
+        deadline_date: task
+            .deadline
+            .as_ref()
+            .and_then(|d| d.date())
+            .map(|d| d.format("%Y-%m-%d").to_string()),
+        duration: task.duration.as_ref().and_then(|d| d.amount()),
+        duration_unit: task.duration.as_ref().and_then(|d| d.unit()),
+        ..Default::default()
+    }
+}
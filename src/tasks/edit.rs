@@ -1,64 +1,162 @@
-use color_eyre::Result;
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, eyre},
+};
 
 use crate::{
     api::{
         self,
-        rest::{DurationUnit, Gateway, TaskDue, UpdateTask},
+        rest::{FieldUpdate, Gateway, Label, Task, TaskDue, TaskID, UpdateTask},
     },
     config::Config,
-    labels::{self, LabelSelect},
-    tasks::{Priority, filter::TaskOrInteractive},
+    interactive,
+    labels::{self, LabelSelect, find_label},
+    output::info,
+    tasks::{Priority, assignee, dates, filter::TasksOrInteractive},
 };
 
 #[derive(clap::Parser, Debug)]
 pub struct Params {
     #[clap(flatten)]
-    pub task: TaskOrInteractive,
+    pub tasks: TasksOrInteractive,
     /// Name of a task
     #[arg(short = 'n', long = "name")]
     pub name: Option<String>,
     #[arg(short = 'd', long = "due")]
     pub due: Option<String>,
+    /// Set an exact due time as YYYY-MM-DDTHH:MM in the configured timezone, instead of letting
+    /// Todoist interpret the text.
+    #[arg(long = "due-datetime", conflicts_with = "due")]
+    pub due_datetime: Option<String>,
+    /// Removes the due date of the task.
+    #[arg(long = "no-due", conflicts_with_all = ["due", "due_datetime"])]
+    pub no_due: bool,
+    /// Exact due date, used when the date is picked interactively.
+    #[arg(skip)]
+    pub due_date: Option<chrono::NaiveDate>,
     /// Description of a task.
     #[arg(short = 'D', long = "desc")]
     pub desc: Option<String>,
-    /// Sets the priority on the task. The lower the priority the more urgent the task.
-    #[arg(value_enum, short = 'p', long = "priority")]
+    /// Sets the priority on the task like in the Todoist apps, from p1 (urgent) to p4 (normal).
+    /// The "p" can be left out. Note that the API counts the other way round, with 4 as urgent.
+    #[arg(value_enum, ignore_case = true, short = 'p', long = "priority")]
     pub priority: Option<Priority>,
-    /// Set deadline with a date in YYYY-MM-DD format.
+    /// Makes the task one priority level more urgent, staying at the most urgent level.
+    #[arg(long = "priority-bump", conflicts_with_all = ["priority", "priority_drop"])]
+    pub priority_bump: bool,
+    /// Makes the task one priority level less urgent, staying at the least urgent level.
+    #[arg(long = "priority-drop", conflicts_with = "priority")]
+    pub priority_drop: bool,
+    /// Set deadline with a date in YYYY-MM-DD format or a phrase like "next friday" or
+    /// "in 2 weeks".
     #[arg(long = "deadline")]
     pub deadline: Option<String>,
-    /// Set task duration with format "<amount>:<unit>" (e.g., "30:minute" or "2:day"). Requires --due to be specified.
+    /// Removes the deadline of the task.
+    #[arg(long = "no-deadline", conflicts_with = "deadline")]
+    pub no_deadline: bool,
+    /// Set task duration like "90m", "1h30m" or "2d", or as "<amount>:<unit>" like "30:minute".
+    /// Requires --due to be specified.
     #[arg(long = "duration")]
     pub duration: Option<String>,
+    /// Removes the duration of the task.
+    #[arg(long = "no-duration", conflicts_with = "duration")]
+    pub no_duration: bool,
     #[clap(flatten)]
     pub labels: LabelSelect,
+    /// Adds the label with the given name if the task doesn't have it yet, removes it otherwise.
+    /// Can be used multiple times to toggle more labels.
+    #[arg(long = "toggle-label")]
+    pub toggle_labels: Vec<String>,
+    /// Creates labels given with --label or --toggle-label if they don't exist yet.
+    #[arg(long = "create-label")]
+    pub create_label: bool,
+    /// Assigns the task to the collaborator of its shared project whose name matches exactly
+    /// (ignoring case) or by a unique prefix, or to yourself with "me".
+    #[arg(long = "assignee", conflicts_with = "unassign")]
+    pub assignee: Option<String>,
+    /// Removes the assignee from the task.
+    #[arg(long = "unassign")]
+    pub unassign: bool,
+    /// Exact set of label names, used when the labels are picked interactively. Unlike --label,
+    /// an empty set clears all labels of the task.
+    #[arg(skip)]
+    pub label_names: Option<Vec<String>>,
+    /// Edits all tasks matching --filter instead of selecting one of them.
+    #[arg(long = "all-matching", requires = "filter", conflicts_with = "ids")]
+    pub all_matching: bool,
+    /// Edits the tasks matching --all-matching without asking for confirmation first.
+    #[arg(short = 'y', long = "yes", requires = "all_matching")]
+    pub yes: bool,
 }
 
 impl Params {
     pub fn new(id: api::rest::TaskID) -> Self {
         Self {
-            task: TaskOrInteractive::with_id(id),
+            tasks: id.into(),
             name: None,
             due: None,
+            due_datetime: None,
+            no_due: false,
+            due_date: None,
             desc: None,
             priority: None,
+            priority_bump: false,
+            priority_drop: false,
             deadline: None,
+            no_deadline: false,
             duration: None,
+            no_duration: false,
             labels: LabelSelect::default(),
+            toggle_labels: Vec::new(),
+            create_label: false,
+            assignee: None,
+            unassign: false,
+            label_names: None,
+            all_matching: false,
+            yes: false,
         }
     }
 }
 
 pub async fn edit(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
-    let labels = {
-        let labels = params
-            .labels
-            .labels(&gw.labels().await?, labels::Selection::AllowEmpty)?;
-        if labels.is_empty() {
-            None
-        } else {
-            Some(labels.into_iter().map(|l| l.name).collect())
+    // All matching tasks are fetched once the changes are known to be valid.
+    let filter = params
+        .tasks
+        .filter_only()
+        .filter(|_| params.all_matching)
+        .map(str::to_owned);
+    let ids = match filter {
+        Some(_) => Vec::new(),
+        None => params.tasks.task_ids(gw, cfg).await?,
+    };
+    let mut all_labels = gw.labels().await?;
+    if params.create_label {
+        let mut missing = params.labels.missing_names(&all_labels);
+        missing.extend(
+            params
+                .toggle_labels
+                .iter()
+                .filter(|&t| {
+                    find_label(&all_labels, t).is_none()
+                        && !missing.iter().any(|m| m.eq_ignore_ascii_case(t))
+                })
+                .cloned()
+                .collect::<Vec<_>>(),
+        );
+        labels::create_labels(&missing, &mut all_labels, gw).await?;
+    }
+    let labels = match params.label_names {
+        Some(names) => Some(names),
+        None => {
+            let labels =
+                params
+                    .labels
+                    .labels(&all_labels, labels::Selection::AllowEmpty, &cfg.theme)?;
+            if labels.is_empty() {
+                None
+            } else {
+                Some(labels.into_iter().map(|l| l.name).collect::<Vec<_>>())
+            }
         }
     };
     let mut update = UpdateTask {
@@ -66,57 +164,202 @@ pub async fn edit(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
         description: params.desc,
         priority: params.priority.map(|p| p.into()),
         labels,
+        assignee: if params.unassign {
+            FieldUpdate::Clear
+        } else {
+            FieldUpdate::Keep
+        },
         ..Default::default()
     };
-    let due_provided = params.due.is_some();
     if let Some(due) = params.due {
         update.due = Some(TaskDue::String(due))
     }
-    if let Some(deadline_str) = params.deadline {
-        if chrono::NaiveDate::parse_from_str(&deadline_str, "%Y-%m-%d").is_ok() {
-            update.deadline_date = Some(deadline_str);
-            update.deadline_lang = Some("en".to_string());
-        } else {
-            return Err(color_eyre::eyre::eyre!(
-                "Invalid deadline format. Use YYYY-MM-DD format."
-            ));
-        }
+    if let Some(due) = params.due_datetime {
+        update.due = Some(TaskDue::DateTime(dates::parse_datetime(&due, cfg)?))
+    }
+    if let Some(date) = params.due_date {
+        update.due = Some(TaskDue::Date(date.format("%Y-%m-%d").to_string()))
+    }
+    if params.no_due {
+        update.due = Some(TaskDue::String(UpdateTask::NO_DUE.to_string()))
+    }
+    if params.no_deadline {
+        update.deadline_date = FieldUpdate::Clear;
+    }
+    if params.no_duration {
+        update.duration = FieldUpdate::Clear;
+        update.duration_unit = FieldUpdate::Clear;
+    }
+    if let Some(deadline) = params.deadline {
+        // The API doesn't interpret deadline phrases, so they are resolved here.
+        let deadline = dates::parse_date(&deadline, cfg).wrap_err("Invalid deadline")?;
+        update.deadline_date = FieldUpdate::Set(deadline.format("%Y-%m-%d").to_string());
+        update.deadline_lang = Some("en".to_string());
     }
     if let Some(duration_str) = params.duration {
-        if update.due.is_none() && !due_provided {
-            return Err(color_eyre::eyre::eyre!(
-                "Duration requires a due date. Use --due option when specifying duration."
-            ));
+        let (amount, unit) = dates::parse_duration(&duration_str)?;
+        update.duration = FieldUpdate::Set(amount);
+        update.duration_unit = FieldUpdate::Set(unit);
+    }
+
+    let targets: Vec<(TaskID, Option<Task>)> = match filter {
+        Some(filter) => {
+            let changes_nothing = serde_json::to_value(&update)?
+                .as_object()
+                .is_some_and(|fields| fields.is_empty())
+                && params.toggle_labels.is_empty()
+                && params.assignee.is_none()
+                && !params.priority_bump
+                && !params.priority_drop;
+            if changes_nothing {
+                return Err(eyre!("no changes given for the tasks matching {filter}"));
+            }
+            let tasks = gw.tasks(Some(&filter)).await?;
+            if tasks.is_empty() {
+                info!("No tasks match the filter {filter}");
+                return Ok(());
+            }
+            if !params.yes
+                && !interactive::confirm(
+                    &format!("Edit {} tasks matching {filter}?", tasks.len()),
+                    &cfg.theme,
+                )
+                .wrap_err("use --yes to edit the tasks without confirmation")?
+            {
+                return Ok(());
+            }
+            tasks.into_iter().map(|t| (t.id.clone(), Some(t))).collect()
         }
-        if let Some((amount_str, unit_str)) = duration_str.split_once(':') {
-            if let Ok(amount) = amount_str.parse::<u32>() {
-                if amount == 0 {
-                    return Err(color_eyre::eyre::eyre!(
-                        "Duration amount must be greater than zero."
-                    ));
-                }
-                let unit = match unit_str {
-                    "minute" => DurationUnit::Minute,
-                    "day" => DurationUnit::Day,
-                    _ => {
-                        return Err(color_eyre::eyre::eyre!(
-                            "Invalid duration unit. Use 'minute' or 'day'."
-                        ));
-                    }
-                };
-                update.duration = Some(amount);
-                update.duration_unit = Some(unit);
-            } else {
-                return Err(color_eyre::eyre::eyre!(
-                    "Invalid duration amount. Must be a positive integer."
+        None => ids.into_iter().map(|id| (id, None)).collect(),
+    };
+    // A duration without a new due date needs the task to have a due date already.
+    let needs_due = matches!(update.duration, FieldUpdate::Set(_)) && update.due.is_none();
+    // Relative changes depend on the current state of each task.
+    let needs_current = params.priority_bump
+        || params.priority_drop
+        || params.assignee.is_some()
+        || needs_due
+        || (!params.toggle_labels.is_empty() && update.labels.is_none());
+    let prepared = futures::future::try_join_all(targets.into_iter().map(|(id, current)| {
+        let mut update = update.clone();
+        let (toggles, assignee, all_labels) =
+            (&params.toggle_labels, &params.assignee, &all_labels);
+        let (bump, drop) = (params.priority_bump, params.priority_drop);
+        async move {
+            let current = match current {
+                Some(task) => Some(task),
+                None if needs_current => Some(gw.task(&id).await?),
+                None => None,
+            };
+            if needs_due && current.as_ref().is_some_and(|task| task.due.is_none()) {
+                return Err(eyre!(
+                    "Duration requires a due date. Use --due option when specifying duration."
                 ));
             }
+            if !toggles.is_empty() {
+                let labels = match (update.labels.take(), &current) {
+                    (Some(labels), _) => labels,
+                    (None, Some(task)) => task.labels.clone(),
+                    (None, None) => Vec::new(),
+                };
+                update.labels = Some(toggle_labels(labels, toggles, all_labels)?);
+            }
+            let mut note = None;
+            if let Some(task) = &current
+                && (bump || drop)
+            {
+                let priority = if bump {
+                    task.priority.raise()
+                } else {
+                    task.priority.lower()
+                };
+                update.priority = Some(priority);
+                note = Some(format!("task {id}: {} -> {priority}", task.priority));
+            }
+            if let (Some(name), Some(task)) = (assignee, &current) {
+                let project = gw.project(&task.project_id).await?;
+                update.assignee = FieldUpdate::Set(assignee::resolve(name, &project, gw).await?);
+            }
+            Ok::<_, color_eyre::Report>((id, update, note))
+        }
+    }))
+    .await?;
+
+    let mut pending = Vec::new();
+    for (id, update, note) in prepared {
+        if let Some(note) = note {
+            info!("{note}");
+        }
+        if !gw.dry_run(&format!("update task {id}"), &update)? {
+            pending.push((id, update));
+        }
+    }
+    let results = futures::future::join_all(
+        pending
+            .iter()
+            .map(|(id, update)| async move { gw.update(id, update).await }),
+    )
+    .await;
+    let mut errors = pending
+        .iter()
+        .zip(results)
+        .filter_map(|((id, _), result)| result.err().map(|e| (id, e)))
+        .collect::<Vec<_>>();
+    match errors.len() {
+        0 => Ok(()),
+        1 => Err(errors.remove(0).1),
+        failed => {
+            for (id, e) in &errors {
+                eprintln!("failed to update task {id}: {e:#}");
+            }
+            Err(eyre!(
+                "{failed} of {} tasks could not be updated",
+                pending.len()
+            ))
+        }
+    }
+}
+
+/// Flips each of the toggled labels in the given set of label names. Toggled names have to match
+/// an existing label, ignoring case.
+fn toggle_labels(
+    mut current: Vec<String>,
+    toggles: &[String],
+    all_labels: &[Label],
+) -> Result<Vec<String>> {
+    for toggle in toggles {
+        let label = find_label(all_labels, toggle)
+            .ok_or_else(|| eyre!("could not find label with name {}", toggle))?;
+        if let Some(pos) = current.iter().position(|l| *l == label.name) {
+            current.remove(pos);
         } else {
-            return Err(color_eyre::eyre::eyre!(
-                "Invalid duration format. Use '<amount>:<unit>' format (e.g., '30:minute' or '2:day')."
-            ));
+            current.push(label.name.clone());
         }
     }
-    gw.update(&params.task.task_id(gw, cfg).await?, &update)
-        .await
+    Ok(current)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn label(name: &str) -> Label {
+        Label {
+            id: name.to_string(),
+            name: name.to_string(),
+            color: "red".to_string(),
+            order: 0,
+            is_favorite: false,
+        }
+    }
+
+    #[test]
+    fn toggles_labels() {
+        let all = vec![label("home"), label("work"), label("urgent")];
+        let current = vec!["home".to_string(), "work".to_string()];
+        let toggled =
+            toggle_labels(current, &["Work".to_string(), "urgent".to_string()], &all).unwrap();
+        assert_eq!(toggled, vec!["home".to_string(), "urgent".to_string()]);
+        assert!(toggle_labels(vec![], &["missing".to_string()], &all).is_err());
+    }
 }
@@ -7,7 +7,7 @@ use crate::{
 
 use super::state::State;
 
-#[derive(clap::Parser, Debug)]
+#[derive(clap::Parser, Debug, Clone)]
 pub struct Filter {
     /// When selecting tasks, this will specify a filter query to run against the Todoist API to narrow down possibilities.
     #[arg(short = 'f', long = "filter")]
@@ -21,6 +21,10 @@ impl Filter {
     pub fn set_filter(&mut self, filter: Option<&str>) {
         self.filter = filter.map(str::to_string);
     }
+    /// Returns the filter query, if one was given.
+    pub fn query(&self) -> Option<&str> {
+        self.filter.as_deref()
+    }
     pub fn select(&self, cfg: &Config) -> String {
         self.filter
             .clone()
@@ -46,7 +50,16 @@ impl TaskOrInteractive {
             filter: Filter::new(None),
         }
     }
+    /// Selects the task interactively among the tasks matching the filter.
+    pub fn select(filter: Filter) -> Self {
+        Self { id: None, filter }
+    }
+    /// Returns the given Task ID, or selects a task interactively if none was given. Tasks are
+    /// only fetched for the selection.
     pub async fn task_id(&self, gw: &Gateway, cfg: &Config) -> Result<TaskID> {
+        if let Some(id) = &self.id {
+            return Ok(id.clone());
+        }
         let (id, _) = self.task(gw, cfg).await?;
         Ok(id)
     }
@@ -74,6 +87,45 @@ impl From<TaskID> for TaskOrInteractive {
     }
 }
 
+/// TasksOrInteractive works like [`TaskOrInteractive`], but accepts multiple Task IDs at once.
+#[derive(clap::Parser, Debug)]
+pub struct TasksOrInteractive {
+    /// The Task IDs as provided from the Todoist API. Use `list` to find out what IDs your tasks
+    /// have. If omitted, will interactively select a single task.
+    ids: Vec<TaskID>,
+    #[clap(flatten)]
+    filter: Filter,
+}
+
+impl TasksOrInteractive {
+    pub async fn task_ids(&self, gw: &Gateway, cfg: &Config) -> Result<Vec<TaskID>> {
+        if !self.ids.is_empty() {
+            return Ok(self.ids.clone());
+        }
+        let task = TaskOrInteractive::select(self.filter.clone());
+        Ok(vec![task.task_id(gw, cfg).await?])
+    }
+
+    /// Returns the filter if it was given without any Task IDs, for commands that can work on
+    /// all matching tasks instead of selecting one.
+    pub fn filter_only(&self) -> Option<&str> {
+        if self.ids.is_empty() {
+            self.filter.query()
+        } else {
+            None
+        }
+    }
+}
+
+impl From<TaskID> for TasksOrInteractive {
+    fn from(id: TaskID) -> Self {
+        Self {
+            ids: vec![id],
+            filter: Filter::new(None),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::config::Config;
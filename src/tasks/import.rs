@@ -0,0 +1,360 @@
+use std::{fmt, path::PathBuf};
+
+use clap::ValueEnum;
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, eyre},
+};
+use futures::StreamExt;
+use serde::{Deserialize, Deserializer, de};
+
+use crate::{
+    api::{
+        rest::{CreateTask, Gateway, Label, Project, TableTask, TaskDue},
+        tree::Tree,
+    },
+    config::Config,
+    labels::find_label,
+    output::info,
+    tasks::Priority,
+};
+
+/// The amount of tasks that are created at the same time, to stay clear of the rate limit.
+const MAX_CONCURRENT_CREATES: usize = 4;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// The JSON or CSV file with the tasks to create.
+    file: PathBuf,
+    /// Format of the file. Taken from the file extension if not given.
+    #[arg(long = "format", value_enum)]
+    format: Option<ImportFormat>,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// An array of objects with the fields of a task.
+    Json,
+    /// A header row with the field names, then one task per row. Labels are separated by commas.
+    Csv,
+}
+
+/// A single task of an import file. Only the content is required.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+struct Record {
+    content: String,
+    #[serde(default)]
+    description: Option<String>,
+    /// Due date in any format that Todoist understands, like "tomorrow".
+    #[serde(default)]
+    due: Option<String>,
+    /// Priority like in the Todoist apps, from p1 (urgent) to p4 (normal).
+    #[serde(default, deserialize_with = "deserialize_priority")]
+    priority: Option<Priority>,
+    /// Name or ID of the project. Names have to match exactly, ignoring case.
+    #[serde(default)]
+    project: Option<String>,
+    /// Names of the labels. They have to match exactly, ignoring case.
+    #[serde(default, deserialize_with = "deserialize_labels")]
+    labels: Vec<String>,
+}
+
+/// Creates all tasks of a JSON or CSV file.
+///
+/// All projects and labels are resolved before the first task is created, so a typo doesn't
+/// leave a half imported file behind.
+pub async fn import(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let format = match params.format {
+        Some(format) => format,
+        None => params
+            .file
+            .extension()
+            .and_then(|ext| ImportFormat::from_str(&ext.to_string_lossy(), true).ok())
+            .ok_or_else(|| {
+                eyre!(
+                    "unable to tell the format of {}, use --format",
+                    params.file.display()
+                )
+            })?,
+    };
+    let data = std::fs::read_to_string(&params.file)
+        .wrap_err_with(|| format!("unable to read {}", params.file.display()))?;
+    let records = parse_records(&data, format)?;
+    if records.is_empty() {
+        return Err(eyre!("no tasks found in {}", params.file.display()));
+    }
+
+    let (projects, labels) = tokio::try_join!(gw.projects(), gw.labels())?;
+    let tasks = records
+        .into_iter()
+        .enumerate()
+        .map(|(i, record)| {
+            let content = record.content.clone();
+            resolve(record, &projects, &labels)
+                .wrap_err_with(|| format!("task {} ({content}) is invalid", i + 1))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let creates = tasks.iter().map(|(create, ..)| create).collect::<Vec<_>>();
+    if gw.dry_run("create tasks", &creates)? {
+        return Ok(());
+    }
+
+    let results = futures::stream::iter(creates.iter().map(|create| gw.create(create)))
+        .buffered(MAX_CONCURRENT_CREATES)
+        .collect::<Vec<_>>()
+        .await;
+    let mut failed = 0;
+    for ((create, project, labels), result) in tasks.iter().zip(results) {
+        match result {
+            Ok(task) => {
+                let task = Tree::new(task);
+                let table = TableTask {
+                    project: *project,
+                    labels: labels.iter().collect(),
+                    ..TableTask::from_task(&task, cfg)
+                };
+                info!("created task: {table}");
+            }
+            Err(e) => {
+                failed += 1;
+                eprintln!("failed to create task {}: {e:#}", create.content);
+            }
+        }
+    }
+    info!("imported {} of {} tasks", tasks.len() - failed, tasks.len());
+    if failed > 0 {
+        return Err(eyre!(
+            "{failed} of {} tasks could not be imported",
+            tasks.len()
+        ));
+    }
+    Ok(())
+}
+
+/// Parses the records of an import file in the given format.
+fn parse_records(data: &str, format: ImportFormat) -> Result<Vec<Record>> {
+    match format {
+        ImportFormat::Json => serde_json::from_str(data).wrap_err("unable to parse JSON"),
+        ImportFormat::Csv => csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(data.as_bytes())
+            .deserialize()
+            .collect::<Result<_, _>>()
+            .wrap_err("unable to parse CSV"),
+    }
+}
+
+/// Turns a record into the task to create, looking up its project and labels.
+fn resolve<'a>(
+    record: Record,
+    projects: &'a [Project],
+    labels: &[Label],
+) -> Result<(CreateTask, Option<&'a Project>, Vec<Label>)> {
+    // Unlike `--project` and `--label`, names aren't fuzzy matched, as nobody checks the pick of
+    // each record.
+    let project = record
+        .project
+        .as_deref()
+        .map(|name| {
+            projects
+                .iter()
+                .find(|p| p.id == name || p.name.to_lowercase() == name.to_lowercase())
+                .ok_or_else(|| eyre!("could not find project with name or ID {name}"))
+        })
+        .transpose()?;
+    let mut found: Vec<Label> = Vec::new();
+    for name in &record.labels {
+        let name = name.strip_prefix('@').unwrap_or(name);
+        let label = find_label(labels, name)
+            .ok_or_else(|| eyre!("could not find label with name {name}"))?;
+        if !found.iter().any(|l| l.id == label.id) {
+            found.push(label.clone());
+        }
+    }
+    let labels = found;
+    let create = CreateTask {
+        content: record.content,
+        description: record.description,
+        project_id: project.map(|p| p.id.clone()),
+        labels: (!labels.is_empty()).then(|| labels.iter().map(|l| l.name.clone()).collect()),
+        priority: record.priority.map(Into::into),
+        due: record.due.map(TaskDue::String),
+        ..Default::default()
+    };
+    Ok((create, project, labels))
+}
+
+/// Reads a priority like "p1" or "1", which CSV files always have as text.
+fn deserialize_priority<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Priority>, D::Error> {
+    struct Visitor;
+    impl de::Visitor<'_> for Visitor {
+        type Value = Option<Priority>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a priority from p1 to p4")
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+            self.visit_str(&v.to_string())
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            if v.is_empty() {
+                return Ok(None);
+            }
+            Priority::from_str(v, true).map(Some).map_err(E::custom)
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+    }
+    d.deserialize_any(Visitor)
+}
+
+/// Reads labels either as a list or as text separated by commas, which is how CSV files have
+/// them.
+fn deserialize_labels<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<String>, D::Error> {
+    struct Visitor;
+    impl<'de> de::Visitor<'de> for Visitor {
+        type Value = Vec<String>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a list of labels or labels separated by commas")
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            Ok(v.split(',')
+                .map(str::trim)
+                .filter(|label| !label.is_empty())
+                .map(str::to_string)
+                .collect())
+        }
+
+        fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut labels = Vec::new();
+            while let Some(label) = seq.next_element()? {
+                labels.push(label);
+            }
+            Ok(labels)
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(Vec::new())
+        }
+    }
+    d.deserialize_any(Visitor)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn fixtures() -> (Vec<Project>, Vec<Label>) {
+        (
+            vec![Project::new("1", "Inbox"), Project::new("2", "Chores")],
+            vec![Label::new("1", "home"), Label::new("2", "errand")],
+        )
+    }
+
+    /// Returns the tasks as they would be sent to the API.
+    fn creates(data: &str, format: ImportFormat) -> serde_json::Value {
+        let (projects, labels) = fixtures();
+        let creates = parse_records(data, format)
+            .unwrap()
+            .into_iter()
+            .map(|record| resolve(record, &projects, &labels).unwrap().0)
+            .collect::<Vec<_>>();
+        serde_json::to_value(creates).unwrap()
+    }
+
+    fn expected() -> serde_json::Value {
+        serde_json::json!([
+            {
+                "content": "Buy milk",
+                "project_id": "2",
+                "labels": ["errand", "home"],
+                "priority": 4,
+                "due_string": "tomorrow"
+            },
+            {"content": "Call mom", "description": "About the weekend"}
+        ])
+    }
+
+    #[test]
+    fn parses_json() {
+        let data = r#"[
+            {"content": "Buy milk", "due": "tomorrow", "priority": 1, "project": "Chores",
+             "labels": ["errand", "home"]},
+            {"content": "Call mom", "description": "About the weekend", "priority": null}
+        ]"#;
+        assert_eq!(creates(data, ImportFormat::Json), expected());
+        assert!(
+            parse_records(
+                r#"[{"content": "Nope", "priority": 5}]"#,
+                ImportFormat::Json
+            )
+            .is_err()
+        );
+        assert!(parse_records(r#"[{"due": "today"}]"#, ImportFormat::Json).is_err());
+    }
+
+    #[test]
+    fn parses_csv() {
+        let data = "content,description,due,priority,project,labels\n\
+                    Buy milk,,tomorrow,p1,Chores,\"errand, home\"\n\
+                    Call mom,About the weekend,,,,\n";
+        assert_eq!(creates(data, ImportFormat::Csv), expected());
+        // Columns can be left out entirely.
+        assert_eq!(
+            creates("content\nCall mom\n", ImportFormat::Csv),
+            serde_json::json!([{"content": "Call mom"}])
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_projects() {
+        let (projects, labels) = fixtures();
+        let record = Record {
+            content: "Task".to_string(),
+            project: Some("xyz".to_string()),
+            ..Default::default()
+        };
+        assert!(resolve(record, &projects, &labels).is_err());
+        // Close names that would be fuzzy matched are rejected as well.
+        let record = Record {
+            content: "Task".to_string(),
+            project: Some("Chore".to_string()),
+            ..Default::default()
+        };
+        assert!(resolve(record, &projects, &labels).is_err());
+        // Labels aren't fuzzy matched either.
+        let record = Record {
+            content: "Task".to_string(),
+            labels: vec!["erand".to_string()],
+            ..Default::default()
+        };
+        assert!(resolve(record, &projects, &labels).is_err());
+        let record = Record {
+            content: "Task".to_string(),
+            labels: vec!["@Errand".to_string()],
+            ..Default::default()
+        };
+        let (create, _, _) = resolve(record, &projects, &labels).unwrap();
+        assert_eq!(create.labels, Some(vec!["errand".to_string()]));
+    }
+
+    #[test]
+    fn matches_projects_exactly() {
+        let (projects, labels) = fixtures();
+        for name in ["chores", "CHORES", "2"] {
+            let record = Record {
+                content: "Task".to_string(),
+                project: Some(name.to_string()),
+                ..Default::default()
+            };
+            let (create, _, _) = resolve(record, &projects, &labels).unwrap();
+            assert_eq!(create.project_id.as_deref(), Some("2"));
+        }
+    }
+}
@@ -1,19 +1,33 @@
-use std::{collections::HashMap, ops::Not};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufWriter, Write},
+    ops::Not,
+    path::PathBuf,
+};
 
 use crate::{
     api::{
-        rest::{DurationUnit, Gateway, Project, ProjectID, Section, Task},
+        rest::{
+            DurationUnit, Gateway, Label, Project, Section, TableTask, Task, TaskField, TaskID,
+            marker,
+        },
         tree::Tree,
     },
-    config::Config,
+    config::{Config, Theme, TreeStyle},
     interactive, labels,
+    output::{self, info},
     tasks::{
-        close, edit, filter,
+        assignee, close, dates, edit, filter,
         state::{State, TaskMenu},
     },
 };
-use color_eyre::{Result, eyre::WrapErr};
-use owo_colors::OwoColorize;
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, eyre},
+};
+use owo_colors::{OwoColorize, Stream};
 use strum::{Display, FromRepr, VariantNames};
 
 use super::create;
@@ -48,6 +62,92 @@ pub struct Params {
     /// Show task IDs in the output.
     #[arg(long = "show-id")]
     show_id: bool,
+    /// Only show tasks that were modified after the given date. Accepts YYYY-MM-DD, RFC 3339,
+    /// "today", "yesterday" or an age like "3d" or "2w".
+    #[arg(long = "modified-since")]
+    modified_since: Option<String>,
+    /// Only show tasks created longer ago than the given age, like "30d" or "2w".
+    #[arg(long = "older-than")]
+    older_than: Option<String>,
+    /// Only show tasks created more recently than the given age, like "7d" or "1w".
+    #[arg(long = "newer-than")]
+    newer_than: Option<String>,
+    /// Only show tasks due before the given day, not including it. Accepts YYYY-MM-DD, "today",
+    /// "tomorrow" or "yesterday". Hides tasks without a due date.
+    #[arg(long = "due-before")]
+    due_before: Option<String>,
+    /// Only show tasks due after the given day, not including it. Accepts YYYY-MM-DD, "today",
+    /// "tomorrow" or "yesterday". Hides tasks without a due date.
+    #[arg(long = "due-after")]
+    due_after: Option<String>,
+    /// Only show overdue tasks, including recurring ones.
+    #[arg(long = "overdue")]
+    overdue: bool,
+    /// Only show tasks assigned to the collaborator whose name matches exactly (ignoring case) or
+    /// by a unique prefix, to yourself with "me" or to nobody with "unassigned". Names are looked
+    /// up in the shared projects of the listed tasks, so this works best together with --project.
+    #[arg(long = "assignee")]
+    assignee: Option<String>,
+    /// Only show tasks without a due date.
+    #[arg(
+        long = "no-due",
+        conflicts_with_all = ["overdue", "due_before", "due_after", "timed_only"]
+    )]
+    no_due: bool,
+    /// Comma-separated list of fields to show for each task, in order. Valid fields are id,
+    /// priority, content, age, due, labels, deadline, duration, project and completed.
+    #[arg(long = "fields", value_delimiter = ',')]
+    fields: Option<Vec<TaskField>>,
+    /// Characters used to draw the subtask tree. Overrides `tree_style` from the config.
+    #[arg(long = "tree-style", value_enum)]
+    tree_style: Option<TreeStyle>,
+    /// Highlights the given text in task names, without hiding other tasks. Can be used multiple
+    /// times, each pattern gets its own color.
+    #[arg(long = "highlight")]
+    highlights: Vec<String>,
+    /// Only shows tasks that are due at an exact time, ordered by that time like an agenda.
+    #[arg(long = "timed-only")]
+    timed_only: bool,
+    /// Output format of the listed tasks. JSON includes project and section names and nests
+    /// subtasks.
+    #[arg(
+        long = "format",
+        value_enum,
+        default_value_t = Format::Table,
+        conflicts_with_all = ["interactive", "continuous", "group_by", "timed_only"]
+    )]
+    format: Format,
+    /// Writes the output to the given file instead of stdout, without colors.
+    #[arg(long = "output-file")]
+    output_file: Option<PathBuf>,
+    /// Only prints a single summary line with the amount of overdue, today's and upcoming tasks
+    /// in the filter, e.g. for a shell prompt.
+    #[arg(
+        long = "overdue-rollup",
+        conflicts_with_all = ["interactive", "continuous", "format", "output_file"]
+    )]
+    overdue_rollup: bool,
+    /// Only shows the task with this ID and its subtasks, no matter if they match the filter.
+    #[arg(long = "parent", conflicts_with_all = ["limit", "cursor"])]
+    parent: Option<TaskID>,
+    /// Only fetches a single page with up to this many tasks. The cursor of the next page is
+    /// printed to stderr, to be passed to `--cursor`.
+    #[arg(long = "limit", conflicts_with_all = ["expand", "continuous"])]
+    limit: Option<u32>,
+    /// Fetches the page of tasks that starts at this cursor, as printed by a previous `--limit`.
+    #[arg(long = "cursor", conflicts_with_all = ["expand", "continuous"])]
+    cursor: Option<String>,
+    /// Prints the JSON Schema of the task output and exits.
+    #[arg(long = "json-schema")]
+    json_schema: bool,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// Colored table, one task per line.
+    Table,
+    /// JSON array of tasks, for scripting.
+    Json,
 }
 
 #[derive(clap::ValueEnum, Clone, Debug)]
@@ -56,53 +156,163 @@ pub enum SortBy {
     Created,
     /// Sort by duration (shortest first) - useful for quick wins
     Duration,
+    /// Sort by priority (urgent first)
+    Priority,
+    /// Sort by due date (earliest first), tasks without a due date come last
+    Due,
 }
 
 #[derive(clap::ValueEnum, Clone, Debug)]
 pub enum GroupBy {
     /// Group tasks by project - useful for focusing on specific projects
     Project,
+    /// Group tasks by section, tasks without a section are grouped together
+    Section,
+    /// Group tasks by label, tasks with multiple labels show up in each of their groups
+    Label,
 }
 
 /// List lists the tasks of the current user accessing the gateway with the given filter.
 pub async fn list(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    if params.json_schema {
+        info!("{}", serde_json::to_string_pretty(&Task::json_schema())?);
+        return Ok(());
+    }
+    if params.overdue_rollup {
+        // Only fetches the tasks, so it stays cheap enough for status bars.
+        let tasks = gw.tasks(Some(&params.filter.select(cfg))).await?;
+        let now = cfg.override_time.unwrap_or_else(Utc::now);
+        info!("{}", Rollup::count(&tasks, &now, cfg).line(cfg));
+        return Ok(());
+    }
+    let styled_cfg;
+    let cfg = match params.tree_style {
+        Some(tree_style) => {
+            styled_cfg = Config {
+                tree_style,
+                ..cfg.clone()
+            };
+            &styled_cfg
+        }
+        None => cfg,
+    };
     if params.continuous && params.interactive {
         return list_interactive(params, gw, cfg).await;
     }
-    match list_action(&params, gw, cfg).await {
-        Ok(_) => Ok(()),
-        Err(e) => Err(e),
+    if let Some(path) = &params.output_file {
+        if params.interactive {
+            return Err(eyre!("--output-file can't be used with --select"));
+        }
+        owo_colors::set_override(false);
+        let cfg = Config {
+            hyperlinks: false,
+            ..cfg.clone()
+        };
+        let file = File::create(path)
+            .wrap_err_with(|| format!("unable to create output file {}", path.display()))?;
+        let mut out = BufWriter::new(file);
+        list_action(&params, gw, &cfg, &mut out).await?;
+        return out.flush().wrap_err("unable to write output file");
     }
+    list_action(&params, gw, cfg, &mut output::stdout()).await
 }
 
-async fn list_action(params: &Params, gw: &Gateway, cfg: &Config) -> Result<()> {
-    let state = if params.expand {
-        State::fetch_full_tree(Some(&params.filter.select(cfg)), gw, cfg).await
+async fn list_action(
+    params: &Params,
+    gw: &Gateway,
+    cfg: &Config,
+    out: &mut dyn Write,
+) -> Result<()> {
+    let filter = params.filter.select(cfg);
+    let (state, next_cursor) = if params.limit.is_some() || params.cursor.is_some() {
+        State::fetch_tree_page(
+            Some(&filter),
+            params.cursor.as_deref(),
+            params.limit,
+            gw,
+            cfg,
+        )
+        .await?
     } else {
-        State::fetch_tree(Some(&params.filter.select(cfg)), gw, cfg).await
-    }?;
-    let state = filter_list(state, params).await?;
+        (fetch_state(params, &filter, gw, cfg).await?, None)
+    };
+    let state = filter_list(state, params, gw).await?;
+    list_state(&state, params, gw, out).await?;
+    if let Some(cursor) = next_cursor
+        && output::level() != output::Level::Quiet
+    {
+        // Hints go to stderr, so they don't end up in piped output.
+        eprintln!("More tasks available, continue with: --cursor {cursor}");
+    }
+    Ok(())
+}
+
+/// Fetches the tasks matching the filter, or the subtree of `--parent` if given.
+async fn fetch_state<'a>(
+    params: &Params,
+    filter: &str,
+    gw: &Gateway,
+    cfg: &'a Config,
+) -> Result<State<'a>> {
+    let Some(parent) = &params.parent else {
+        return if params.expand {
+            State::fetch_full_tree(Some(filter), gw, cfg).await
+        } else {
+            State::fetch_tree(Some(filter), gw, cfg).await
+        };
+    };
+    let mut state = State::fetch_tree(Some("all"), gw, cfg).await?;
+    let mut subtree = state
+        .task(parent)
+        .cloned()
+        .ok_or_else(|| eyre!("no open task with ID {parent} was found"))?;
+    subtree.set_depth(0);
+    state.tasks = vec![subtree];
+    Ok(state)
+}
+
+/// Shows the fetched and filtered tasks as requested by the parameters.
+async fn list_state(
+    state: &State<'_>,
+    params: &Params,
+    gw: &Gateway,
+    out: &mut dyn Write,
+) -> Result<()> {
     if params.interactive {
         match state.select_task()? {
-            Some(task) => select_task_option(task, &state, gw).await?,
+            Some(task) => select_task_option(task, state, gw).await?,
             None => {
-                println!("No selection was made");
+                info!("No selection was made");
             }
         }
-    } else if let Some(GroupBy::Project) = params.group_by {
-        list_tasks_grouped_by_project(
+    } else if params.format == Format::Json {
+        let mut tasks = state.tasks.iter().collect::<Vec<_>>();
+        apply_sort(&mut tasks, params.sort_by.as_ref());
+        let tasks = tasks
+            .into_iter()
+            .map(|task| state.json_task(task))
+            .collect::<Vec<_>>();
+        serde_json::to_writer_pretty(&mut *out, &tasks)?;
+        writeln!(out)?;
+    } else if params.timed_only {
+        list_timed_tasks(&state.tasks, state, params.show_id, out)?;
+    } else if let Some(group_by) = &params.group_by {
+        list_tasks_grouped_by(
             &state.tasks,
-            &state,
+            state,
+            group_by,
             params.sort_by.as_ref(),
             params.show_id,
-        );
+            out,
+        )?;
     } else {
         list_tasks_with_sort(
             &state.tasks,
-            &state,
+            state,
             params.sort_by.as_ref(),
             params.show_id,
-        );
+            out,
+        )?;
     }
     Ok(())
 }
@@ -130,13 +340,9 @@ async fn list_interactive_action(
     cfg: &Config,
 ) -> Result<ListAction> {
     let filter = params.filter.select(cfg);
-    let state = if params.expand {
-        State::fetch_full_tree(Some(&filter), gw, cfg).await
-    } else {
-        State::fetch_tree(Some(&filter), gw, cfg).await
-    }?;
+    let state = fetch_state(params, &filter, gw, cfg).await?;
 
-    let state = filter_list(state, params).await?;
+    let state = filter_list(state, params, gw).await?;
     match state.select_or_menu()? {
         TaskMenu::Menu => {
             match interactive::select(
@@ -156,13 +362,15 @@ async fn list_interactive_action(
                     "| Upcoming",
                     "| Default Filter",
                 ],
+                &cfg.theme,
             )? {
                 // TODO change this once we have async closures and can iterate over a Vec<(str, async Fn)>
                 Some(0) => create::create(create::Params {}, gw, cfg).await?,
                 Some(1) => {
                     let filter = filter.is_empty().not().then_some(filter);
                     params.filter.set_filter(Some(
-                        &interactive::input_optional("Filter", filter)?.unwrap_or_default(),
+                        &interactive::input_optional("Filter", filter, &cfg.theme)?
+                            .unwrap_or_default(),
                     ));
                 }
                 Some(2) => params.filter.set_filter(Some("all")),
@@ -179,14 +387,18 @@ async fn list_interactive_action(
             Ok(ListAction::Action)
         }
         TaskMenu::None => {
-            println!("No selection was made");
+            info!("No selection was made");
             Ok(ListAction::Cancel)
         }
     }
 }
 
 /// Show a list that's filtered down based on the params.
-async fn filter_list<'a>(state: State<'a>, params: &'_ Params) -> Result<State<'a>> {
+async fn filter_list<'a>(
+    state: State<'a>,
+    params: &'_ Params,
+    gw: &'_ Gateway,
+) -> Result<State<'a>> {
     let projects = state
         .projects
         .values()
@@ -204,10 +416,13 @@ async fn filter_list<'a>(state: State<'a>, params: &'_ Params) -> Result<State<'
         .collect::<Vec<_>>();
     let project = params.project.optional(&projects)?;
     let section = params.section.optional(&sections)?;
-    let labels = params
-        .label
-        .labels(&labels, labels::Selection::AllowEmpty)?;
+    let labels =
+        params
+            .label
+            .labels(&labels, labels::Selection::AllowEmpty, &state.config.theme)?;
     let mut state = state;
+    state.highlights = params.highlights.clone();
+    state.fields = params.fields.clone();
     if let Some(p) = project {
         state = state.filter(|tree| tree.project_id == *p.id);
     }
@@ -222,63 +437,289 @@ async fn filter_list<'a>(state: State<'a>, params: &'_ Params) -> Result<State<'
                 .any(|l| tree.labels.contains(&l))
         });
     }
+    if let Some(since) = &params.modified_since {
+        let since = dates::parse_since(since, state.config)?;
+        state = state.filter(|tree| tree.last_updated().is_some_and(|u| u > since));
+    }
+    let now = state.config.override_time.unwrap_or_else(Utc::now);
+    if let Some(age) = &params.older_than {
+        let before = now - dates::parse_age(age)?;
+        state = state.filter(|tree| tree.created_at < before);
+    }
+    if let Some(age) = &params.newer_than {
+        let after = now - dates::parse_age(age)?;
+        state = state.filter(|tree| tree.created_at > after);
+    }
+    if params.due_before.is_some() || params.due_after.is_some() {
+        let parse = |date: &Option<String>| {
+            date.as_deref()
+                .map(|d| dates::parse_date(d, state.config))
+                .transpose()
+        };
+        let (after, before) = (parse(&params.due_after)?, parse(&params.due_before)?);
+        state = state.filter(|tree| due_between(tree, after, before));
+    }
+    if params.overdue {
+        let today = state.config.local_date(&now);
+        state = state.filter(|tree| tree.is_overdue(&now, today));
+    }
+    if params.no_due {
+        state = state.filter(|tree| tree.due.is_none());
+    }
+    if let Some(name) = &params.assignee {
+        let assignee = if name.eq_ignore_ascii_case("unassigned") {
+            None
+        } else {
+            let projects = match project {
+                Some(p) => vec![p],
+                None => {
+                    let mut projects = state
+                        .tasks
+                        .iter()
+                        .filter_map(|t| state.projects.get(&t.project_id))
+                        .collect::<Vec<_>>();
+                    projects.sort_by(|a, b| a.id.cmp(&b.id));
+                    projects.dedup_by(|a, b| a.id == b.id);
+                    projects
+                }
+            };
+            Some(assignee::resolve_among(name, &projects, gw).await?)
+        };
+        state = state.filter(|tree| tree.assignee_id == assignee);
+    }
     Ok(state)
 }
 
-pub fn list_tasks_grouped_by_project<'a>(
+/// Checks if the task is due strictly between the given days. A missing bound is open, but tasks
+/// without a due date never match.
+fn due_between(task: &Task, after: Option<NaiveDate>, before: Option<NaiveDate>) -> bool {
+    let Some(due) = task.due.as_ref().and_then(|d| d.date_naive()) else {
+        return false;
+    };
+    after.is_none_or(|after| due > after) && before.is_none_or(|before| due < before)
+}
+
+/// Lists tasks grouped by the given criteria.
+pub fn list_tasks_grouped_by<'a>(
+    tasks: &'a [Tree<Task>],
+    state: &'a State,
+    group_by: &GroupBy,
+    sort_by: Option<&SortBy>,
+    show_id: bool,
+    out: &mut dyn Write,
+) -> Result<()> {
+    match group_by {
+        GroupBy::Project => list_tasks_grouped_by_project(tasks, state, sort_by, show_id, out),
+        GroupBy::Section => list_tasks_grouped(
+            tasks,
+            state,
+            |task| {
+                // Sections are keyed by ID, as different projects can have sections of the same
+                // name.
+                task.section_id
+                    .as_ref()
+                    .map(|id| {
+                        let project = state
+                            .projects
+                            .get(&task.project_id)
+                            .map(|p| &p.name)
+                            .unwrap_or(&task.project_id);
+                        let name = state.sections.get(id).map(|s| &s.name).unwrap_or(id);
+                        vec![(id.clone(), format!("{project} / {name}"))]
+                    })
+                    .unwrap_or_default()
+            },
+            "(no section)",
+            sort_by,
+            show_id,
+            out,
+        ),
+        GroupBy::Label => list_tasks_grouped(
+            tasks,
+            state,
+            |task| task.labels.iter().map(|l| (l.clone(), l.clone())).collect(),
+            "(no label)",
+            sort_by,
+            show_id,
+            out,
+        ),
+    }
+}
+
+/// Lists tasks grouped by project, with subprojects indented below their parents. Parents
+/// without tasks of their own are still shown if a subproject has tasks.
+fn list_tasks_grouped_by_project<'a>(
     tasks: &'a [Tree<Task>],
     state: &'a State,
     sort_by: Option<&SortBy>,
     show_id: bool,
-) {
-    // Group tasks by project
-    let mut project_groups: HashMap<ProjectID, Vec<&Tree<Task>>> = HashMap::new();
+    out: &mut dyn Write,
+) -> Result<()> {
+    let mut task_groups: HashMap<&str, Vec<&Tree<Task>>> = HashMap::new();
+    fn collect_tasks<'a>(
+        tasks: &'a [Tree<Task>],
+        task_groups: &mut HashMap<&'a str, Vec<&'a Tree<Task>>>,
+    ) {
+        for task in tasks {
+            task_groups.entry(&task.project_id).or_default().push(task);
+            collect_tasks(&task.subitems, task_groups);
+        }
+    }
+    collect_tasks(tasks, &mut task_groups);
+
+    fn has_tasks(project: &Tree<Project>, task_groups: &HashMap<&str, Vec<&Tree<Task>>>) -> bool {
+        task_groups.contains_key(project.id.as_str())
+            || project.subitems.iter().any(|p| has_tasks(p, task_groups))
+    }
+
+    fn list_projects(
+        projects: &mut [Tree<Project>],
+        task_groups: &mut HashMap<&str, Vec<&Tree<Task>>>,
+        state: &State,
+        sort_by: Option<&SortBy>,
+        show_id: bool,
+        out: &mut dyn Write,
+    ) -> Result<()> {
+        projects.sort_by(|a, b| a.name.cmp(&b.name));
+        for project in projects {
+            if !has_tasks(project, task_groups) {
+                continue;
+            }
+            let indent = "  ".repeat(project.depth);
+            match task_groups.remove(project.id.as_str()) {
+                Some(group_tasks) => write_group(
+                    out,
+                    &indent,
+                    &project.name,
+                    group_tasks,
+                    state,
+                    sort_by,
+                    show_id,
+                )?,
+                None => writeln!(out, "\n{indent}[{}]", project.name)?,
+            }
+            list_projects(
+                &mut project.subitems,
+                task_groups,
+                state,
+                sort_by,
+                show_id,
+                out,
+            )?;
+        }
+        Ok(())
+    }
+
+    let mut projects = Tree::from_items(state.projects.values().cloned().collect())?;
+    list_projects(
+        &mut projects,
+        &mut task_groups,
+        state,
+        sort_by,
+        show_id,
+        out,
+    )?;
+
+    // Tasks of projects that aren't known are grouped by project ID at the end
+    let mut unknown: Vec<_> = task_groups.into_iter().collect();
+    unknown.sort_by(|a, b| a.0.cmp(b.0));
+    for (project_id, group_tasks) in unknown {
+        write_group(out, "", project_id, group_tasks, state, sort_by, show_id)?;
+    }
+    Ok(())
+}
+
+/// Lists tasks grouped under a header for each group, sorted by header. The `groups` closure
+/// returns the key and header of each group a task belongs to, tasks without any group end up in
+/// the `ungrouped` group at the end.
+pub fn list_tasks_grouped<'a>(
+    tasks: &'a [Tree<Task>],
+    state: &'a State,
+    groups: impl Fn(&Task) -> Vec<(String, String)>,
+    ungrouped: &str,
+    sort_by: Option<&SortBy>,
+    show_id: bool,
+    out: &mut dyn Write,
+) -> Result<()> {
+    // Group tasks by key with their header, None is the group of tasks without any group
+    type Groups<'a> = HashMap<Option<String>, (String, Vec<&'a Tree<Task>>)>;
+    let mut task_groups: Groups = HashMap::new();
 
     fn collect_tasks<'a>(
         tasks: &'a [Tree<Task>],
-        groups: &mut HashMap<ProjectID, Vec<&'a Tree<Task>>>,
+        groups: &impl Fn(&Task) -> Vec<(String, String)>,
+        ungrouped: &str,
+        task_groups: &mut Groups<'a>,
     ) {
         for task in tasks {
-            groups
-                .entry(task.project_id.clone())
-                .or_default()
-                .push(task);
-            collect_tasks(&task.subitems, groups);
+            let keys = groups(task);
+            if keys.is_empty() {
+                let group = task_groups.entry(None);
+                group
+                    .or_insert_with(|| (ungrouped.to_string(), Vec::new()))
+                    .1
+                    .push(task);
+            }
+            for (key, header) in keys {
+                let group = task_groups.entry(Some(key));
+                group.or_insert_with(|| (header, Vec::new())).1.push(task);
+            }
+            collect_tasks(&task.subitems, groups, ungrouped, task_groups);
         }
     }
 
-    collect_tasks(tasks, &mut project_groups);
+    collect_tasks(tasks, &groups, ungrouped, &mut task_groups);
 
-    // Sort projects by name and display
-    let mut sorted_projects: Vec<_> = project_groups.into_iter().collect();
-    sorted_projects.sort_by(|a, b| {
-        let name_a = state.projects.get(&a.0).map(|p| &p.name).unwrap_or(&a.0);
-        let name_b = state.projects.get(&b.0).map(|p| &p.name).unwrap_or(&b.0);
-        name_a.cmp(name_b)
-    });
+    // Sort groups by header and display, tasks without a group come last
+    let mut sorted_groups: Vec<_> = task_groups.into_iter().collect();
+    sorted_groups.sort_by(
+        |(key_a, (header_a, _)), (key_b, (header_b, _))| match (key_a, key_b) {
+            (Some(key_a), Some(key_b)) => header_a.cmp(header_b).then_with(|| key_a.cmp(key_b)),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        },
+    );
 
-    for (project_id, mut project_tasks) in sorted_projects {
-        let project = state.projects.get(&project_id);
-        let project_name = project.map(|p| &p.name).unwrap_or(&project_id);
+    for (_, (header, group_tasks)) in sorted_groups {
+        write_group(out, "", &header, group_tasks, state, sort_by, show_id)?;
+    }
+    Ok(())
+}
 
-        // Count total tasks in this project (including subtasks)
-        let total_tasks = count_all_tasks(&project_tasks);
-        let visible_tasks = project_tasks.len();
+/// Writes the header of a group followed by its tasks, all indented by `indent`.
+fn write_group(
+    out: &mut dyn Write,
+    indent: &str,
+    name: &str,
+    mut group_tasks: Vec<&Tree<Task>>,
+    state: &State,
+    sort_by: Option<&SortBy>,
+    show_id: bool,
+) -> Result<()> {
+    // Count total tasks in this group (including subtasks)
+    let total_tasks = count_all_tasks(&group_tasks);
+    let visible_tasks = group_tasks.len();
 
-        // Print project header
-        println!(
-            "\n[{}] ({}/{} tasks)",
-            project_name, visible_tasks, total_tasks
-        );
+    // Print group header
+    writeln!(
+        out,
+        "\n{indent}[{}] ({}/{} tasks)",
+        name, visible_tasks, total_tasks
+    )?;
 
-        // Sort tasks within the project
-        apply_sort(&mut project_tasks, sort_by);
+    // Sort tasks within the group
+    apply_sort(&mut group_tasks, sort_by);
 
-        // Display tasks without project name
-        for task in project_tasks {
-            println!("  {}", state.table_task_without_project(task, show_id));
-        }
+    // Display tasks without project name, as it's either the group or shown in the header
+    for task in group_tasks {
+        writeln!(
+            out,
+            "{indent}  {}",
+            state.table_task_without_project(task, show_id)
+        )?;
     }
+    Ok(())
 }
 
 fn count_all_tasks(tasks: &[&Tree<Task>]) -> usize {
@@ -292,34 +733,52 @@ fn count_all_subtasks(task: &Tree<Task>) -> usize {
         .sum()
 }
 
-fn apply_sort(tasks: &mut Vec<&Tree<Task>>, sort_by: Option<&SortBy>) {
+fn apply_sort(tasks: &mut [&Tree<Task>], sort_by: Option<&SortBy>) {
+    tasks.sort_by(|a, b| compare_tasks(a, b, sort_by));
+}
+
+/// Compares two tasks by the given sort criteria, falling back to the default order.
+fn compare_tasks(a: &Task, b: &Task, sort_by: Option<&SortBy>) -> std::cmp::Ordering {
     match sort_by {
-        Some(SortBy::Created) => {
-            tasks.sort_by(|a, b| a.created_at.cmp(&b.created_at));
-        }
-        Some(SortBy::Duration) => {
-            tasks.sort_by(|a, b| match (&a.duration, &b.duration) {
-                (Some(dur_a), Some(dur_b)) => {
-                    let minutes_a = match (dur_a.amount(), dur_a.unit()) {
-                        (Some(amount), Some(DurationUnit::Minute)) => amount,
-                        (Some(amount), Some(DurationUnit::Day)) => amount * 24 * 60,
-                        _ => 0,
-                    };
-                    let minutes_b = match (dur_b.amount(), dur_b.unit()) {
-                        (Some(amount), Some(DurationUnit::Minute)) => amount,
-                        (Some(amount), Some(DurationUnit::Day)) => amount * 24 * 60,
-                        _ => 0,
-                    };
-                    minutes_a.cmp(&minutes_b)
-                }
+        // Sort by creation time (oldest first)
+        Some(SortBy::Created) => a.created_at.cmp(&b.created_at),
+        // Sort by duration (shortest first), then by default sort
+        Some(SortBy::Duration) => match (&a.duration, &b.duration) {
+            (Some(dur_a), Some(dur_b)) => {
+                // Convert to minutes for comparison
+                let minutes = |duration: &crate::api::rest::Duration| match (
+                    duration.amount(),
+                    duration.unit(),
+                ) {
+                    (Some(amount), Some(DurationUnit::Minute)) => amount,
+                    (Some(amount), Some(DurationUnit::Day)) => amount * 24 * 60,
+                    _ => 0,
+                };
+                minutes(dur_a).cmp(&minutes(dur_b))
+            }
+            (Some(_), None) => std::cmp::Ordering::Less, // Tasks with duration come first
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => a.cmp(b), // Fall back to default sort
+        },
+        // Sort by priority (urgent first), then by default sort
+        Some(SortBy::Priority) => b.priority.cmp(&a.priority).then_with(|| a.cmp(b)),
+        // Sort by due date (earliest first, tasks without due date last), then by default sort
+        Some(SortBy::Due) => {
+            let due = |task: &Task| {
+                task.due.as_ref().map(|d| {
+                    let exact = d.exact_datetime();
+                    (d.date_naive(), exact.is_none(), exact)
+                })
+            };
+            match (due(a), due(b)) {
+                (Some(due_a), Some(due_b)) => due_a.cmp(&due_b).then_with(|| a.cmp(b)),
                 (Some(_), None) => std::cmp::Ordering::Less,
                 (None, Some(_)) => std::cmp::Ordering::Greater,
                 (None, None) => a.cmp(b),
-            });
-        }
-        None => {
-            tasks.sort();
+            }
         }
+        // Default sort
+        None => a.cmp(b),
     }
 }
 
@@ -328,47 +787,138 @@ pub fn list_tasks_with_sort<'a>(
     state: &'a State,
     sort_by: Option<&SortBy>,
     show_id: bool,
-) {
-    let mut tasks = tasks.to_vec();
+    out: &mut dyn Write,
+) -> Result<()> {
+    let mut tasks = tasks.iter().collect::<Vec<_>>();
+    apply_sort(&mut tasks, sort_by);
 
-    match sort_by {
-        Some(SortBy::Created) => {
-            // Sort by creation time (oldest first)
-            tasks.sort_by(|a, b| a.created_at.cmp(&b.created_at));
-        }
-        Some(SortBy::Duration) => {
-            // Sort by duration (shortest first), then by default sort
-            tasks.sort_by(|a, b| {
-                match (&a.duration, &b.duration) {
-                    (Some(dur_a), Some(dur_b)) => {
-                        // Convert to minutes for comparison
-                        let minutes_a = match (dur_a.amount(), dur_a.unit()) {
-                            (Some(amount), Some(DurationUnit::Minute)) => amount,
-                            (Some(amount), Some(DurationUnit::Day)) => amount * 24 * 60,
-                            _ => 0,
-                        };
-                        let minutes_b = match (dur_b.amount(), dur_b.unit()) {
-                            (Some(amount), Some(DurationUnit::Minute)) => amount,
-                            (Some(amount), Some(DurationUnit::Day)) => amount * 24 * 60,
-                            _ => 0,
-                        };
-                        minutes_a.cmp(&minutes_b)
-                    }
-                    (Some(_), None) => std::cmp::Ordering::Less, // Tasks with duration come first
-                    (None, Some(_)) => std::cmp::Ordering::Greater,
-                    (None, None) => a.cmp(b), // Fall back to default sort
-                }
-            });
+    for task in tasks.iter() {
+        writeln!(out, "{}", state.table_task(task, show_id))?;
+        list_subtasks(
+            &task.subitems,
+            state,
+            sort_by,
+            show_id,
+            &mut Vec::new(),
+            out,
+        )?;
+    }
+    Ok(())
+}
+
+/// Lists subtasks below their parent, drawing the tree branches. `branches` tracks for each level
+/// above the subtasks whether the ancestor on that level was the last of its siblings.
+fn list_subtasks(
+    tasks: &[Tree<Task>],
+    state: &State,
+    sort_by: Option<&SortBy>,
+    show_id: bool,
+    branches: &mut Vec<bool>,
+    out: &mut dyn Write,
+) -> Result<()> {
+    let mut tasks = tasks.iter().collect::<Vec<_>>();
+    apply_sort(&mut tasks, sort_by);
+
+    let count = tasks.len();
+    for (i, task) in tasks.into_iter().enumerate() {
+        branches.push(i + 1 == count);
+        let table = TableTask {
+            branches: Some(branches),
+            ..state.table_task(task, show_id)
+        };
+        writeln!(out, "{table}")?;
+        list_subtasks(&task.subitems, state, sort_by, show_id, branches, out)?;
+        branches.pop();
+    }
+    Ok(())
+}
+
+/// Lists all tasks and subtasks that are due at an exact time in chronological order, prefixed
+/// by their due time.
+fn list_timed_tasks(
+    tasks: &[Tree<Task>],
+    state: &State,
+    show_id: bool,
+    out: &mut dyn Write,
+) -> Result<()> {
+    fn collect<'a>(tasks: &'a [Tree<Task>], timed: &mut Vec<(DateTime<FixedOffset>, &'a Task)>) {
+        for task in tasks {
+            if let Some(exact) = task.due.as_ref().and_then(|d| d.exact_datetime()) {
+                timed.push((exact, &task.item));
+            }
+            collect(&task.subitems, timed);
         }
-        None => {
-            // Default sort
-            tasks.sort();
+    }
+
+    let mut timed = Vec::new();
+    collect(tasks, &mut timed);
+    timed.sort_by_key(|(exact, _)| *exact);
+
+    let now = state.config.override_time.unwrap_or_else(Utc::now);
+    let today = state.config.local_date(&now);
+    for (exact, task) in timed {
+        let local = state.config.local_datetime(&exact);
+        let time = if local.date() == today {
+            local.format("%H:%M")
+        } else {
+            local.format("%m/%d %H:%M")
+        };
+        // Subtasks are shown flat, as the agenda is ordered by time instead of hierarchy.
+        let task = Tree::new(task.clone());
+        writeln!(
+            out,
+            "{} — {}",
+            time.if_supports_color(Stream::Stdout, |text| text.bold()),
+            state.table_task(&task, show_id)
+        )?;
+    }
+    Ok(())
+}
+
+/// Rollup counts tasks by how urgent their due date is.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Rollup {
+    overdue: usize,
+    today: usize,
+    upcoming: usize,
+}
+
+impl Rollup {
+    /// Counts the tasks, tasks without a due date are not counted.
+    fn count(tasks: &[Task], now: &DateTime<Utc>, cfg: &Config) -> Rollup {
+        let today = cfg.local_date(now);
+        let mut rollup = Rollup::default();
+        for task in tasks {
+            let Some(due) = &task.due else {
+                continue;
+            };
+            let date = match due.exact_datetime() {
+                Some(exact) => Some(cfg.local_date(&exact)),
+                None => due.date_naive(),
+            };
+            if task.is_overdue(now, today) {
+                rollup.overdue += 1;
+            } else if date == Some(today) {
+                rollup.today += 1;
+            } else if date.is_some_and(|d| d > today) {
+                rollup.upcoming += 1;
+            }
         }
+        rollup
     }
 
-    for task in tasks.iter() {
-        println!("{}", state.table_task(task, show_id));
-        list_tasks_with_sort(&task.subitems, state, sort_by, show_id);
+    /// Returns the summary line, with an ASCII marker instead of the emoji in plain mode.
+    fn line(&self, cfg: &Config) -> String {
+        format!(
+            "{}{} overdue, {} due today, {} upcoming",
+            marker(cfg, "⚠ ", "[!] "),
+            self.overdue
+                .if_supports_color(Stream::Stdout, |text| text.bright_red()),
+            self.today
+                .if_supports_color(Stream::Stdout, |text| text.bright_yellow()),
+            self.upcoming
+                .if_supports_color(Stream::Stdout, |text| text.bright_green())
+        )
     }
 }
 
@@ -385,36 +935,20 @@ async fn select_task_option<'a>(
     state: &'a State<'_>,
     gw: &'_ Gateway,
 ) -> Result<()> {
-    println!("{}", state.full_task(task));
-    let result = match make_selection(TaskOptions::VARIANTS)? {
+    info!("{}", state.full_task(task));
+    let result = match make_selection(TaskOptions::VARIANTS, &state.config.theme)? {
         Some(index) => TaskOptions::from_repr(index).unwrap(),
         None => {
-            println!("No selection made");
+            info!("No selection made");
             return Ok(());
         }
     };
     match result {
         TaskOptions::Close => {
-            close::close(
-                close::Params {
-                    task: task.id.clone().into(),
-                    complete: false,
-                },
-                gw,
-                state.config,
-            )
-            .await?
+            close::close(close::Params::new(task.id.clone(), false), gw, state.config).await?
         }
         TaskOptions::Complete => {
-            close::close(
-                close::Params {
-                    task: task.id.clone().into(),
-                    complete: true,
-                },
-                gw,
-                state.config,
-            )
-            .await?
+            close::close(close::Params::new(task.id.clone(), true), gw, state.config).await?
         }
         TaskOptions::Edit => edit_task(task, gw, state.config).await?,
         TaskOptions::Quit => {}
@@ -427,63 +961,519 @@ enum EditOptions {
     Name,
     Description,
     Due,
+    Deadline,
+    Duration,
     Priority,
-    // Project, TODO: allow to edit project and section when API supports it
-    // TODO: allow adding, removing labels
+    Labels,
+    // Project and section are changed with `doist move`
     Quit,
 }
 
 async fn edit_task(task: &Tree<Task>, gw: &Gateway, cfg: &Config) -> Result<()> {
-    let result = match make_selection(EditOptions::VARIANTS)? {
+    let result = match make_selection(EditOptions::VARIANTS, &cfg.theme)? {
         Some(index) => EditOptions::from_repr(index).unwrap(),
         None => {
-            println!("No selection made");
+            info!("No selection made");
             return Ok(());
         }
     };
     match result {
         EditOptions::Quit => {}
         EditOptions::Priority => {
-            let selection = dialoguer::Select::new()
-                .with_prompt("Set priority")
-                .items(&["1 - Urgent", "2 - Very High", "3 - High", "4 - Normal"])
-                .default((4 - task.priority as u8) as usize)
-                .interact()
-                .wrap_err("Bad user input")?
-                + 1;
+            let selection =
+                dialoguer::Select::with_theme(interactive::dialoguer_theme(&cfg.theme).as_ref())
+                    .with_prompt("Set priority")
+                    .items(&["1 - Urgent", "2 - Very High", "3 - High", "4 - Normal"])
+                    .default((4 - task.priority as u8) as usize)
+                    .interact()
+                    .wrap_err("Bad user input")?
+                    + 1;
             let mut params = edit::Params::new(task.id.clone());
             params.priority = Some(selection.try_into()?);
             edit::edit(params, gw, cfg).await?;
         }
-        _ => {
-            let text = dialoguer::Input::new()
-                .with_prompt("New value")
-                .interact_text()
-                .wrap_err("Bad user input")?;
+        EditOptions::Due => {
             let mut params = edit::Params::new(task.id.clone());
-            match result {
-                EditOptions::Name => {
-                    params.name = Some(text);
-                }
-                EditOptions::Description => {
-                    params.desc = Some(text);
+            match make_selection(&["Pick a date", "Type a date"], &cfg.theme)? {
+                Some(0) => {
+                    let now = cfg.override_time.unwrap_or_else(Utc::now);
+                    let initial = task
+                        .due
+                        .as_ref()
+                        .and_then(|d| d.date_naive())
+                        .unwrap_or_else(|| cfg.local_date(&now));
+                    match interactive::pick_date("Due date", initial, cfg.week_start)? {
+                        Some(date) => params.due_date = Some(date),
+                        None => {
+                            info!("No date picked");
+                            return Ok(());
+                        }
+                    }
                 }
-                EditOptions::Due => {
-                    params.due = Some(text);
+                Some(_) => {
+                    params.due = Some(
+                        dialoguer::Input::with_theme(
+                            interactive::dialoguer_theme(&cfg.theme).as_ref(),
+                        )
+                        .with_prompt("Due (e.g. \"tomorrow\", \"every monday\")")
+                        .interact_text()
+                        .wrap_err("Bad user input")?,
+                    );
                 }
-                EditOptions::Priority => unreachable!(),
-                EditOptions::Quit => unreachable!(),
+                None => return Ok(()),
+            }
+            edit::edit(params, gw, cfg).await?;
+        }
+        EditOptions::Labels => {
+            let labels = gw.labels().await?;
+            let names = labels.iter().map(|l| l.name.as_str()).collect::<Vec<_>>();
+            let checked = labels
+                .iter()
+                .map(|l| task.labels.contains(&l.name))
+                .collect::<Vec<_>>();
+            let Some(picked) = dialoguer::MultiSelect::with_theme(
+                interactive::dialoguer_theme(&cfg.theme).as_ref(),
+            )
+            .with_prompt("Labels (space to toggle, enter to confirm)")
+            .items(&names)
+            .defaults(&checked)
+            .interact_opt()
+            .wrap_err("Bad user input")?
+            else {
+                info!("No selection made");
+                return Ok(());
             };
+            let mut params = edit::Params::new(task.id.clone());
+            params.label_names = Some(picked_labels(&task.labels, &labels, &picked));
             edit::edit(params, gw, cfg).await?;
         }
+        _ => {
+            let (prompt, initial) = match result {
+                EditOptions::Deadline => (
+                    "Deadline (e.g. \"2025-12-24\", \"next friday\", empty to remove)",
+                    task.deadline
+                        .as_ref()
+                        .and_then(|d| d.date())
+                        .map(|d| d.to_string()),
+                ),
+                EditOptions::Duration => (
+                    "Duration (e.g. \"90m\", \"30:minute\", empty to remove)",
+                    task.duration
+                        .as_ref()
+                        .and_then(|d| Some(format!("{}:{}", d.amount()?, d.unit()?))),
+                ),
+                _ => ("New value", None),
+            };
+            let text =
+                dialoguer::Input::with_theme(interactive::dialoguer_theme(&cfg.theme).as_ref())
+                    .with_prompt(prompt)
+                    .with_initial_text(initial.unwrap_or_default())
+                    .allow_empty(matches!(
+                        result,
+                        EditOptions::Deadline | EditOptions::Duration
+                    ))
+                    .validate_with(|input: &String| {
+                        menu_params(&result, input, task, cfg)
+                            .map(|_| ())
+                            .map_err(|e| format!("{e:#}"))
+                    })
+                    .interact_text()
+                    .wrap_err("Bad user input")?;
+            edit::edit(menu_params(&result, &text, task, cfg)?, gw, cfg).await?;
+        }
     };
     Ok(())
 }
 
-fn make_selection<T: ToString + std::fmt::Display>(variants: &[T]) -> Result<Option<usize>> {
-    dialoguer::FuzzySelect::with_theme(&dialoguer::theme::ColorfulTheme::default())
+/// Turns the text typed into the edit menu into the parameters to edit the task with, validating
+/// it like the matching flag of `doist edit` would.
+fn menu_params(
+    option: &EditOptions,
+    text: &str,
+    task: &Task,
+    cfg: &Config,
+) -> Result<edit::Params> {
+    let mut params = edit::Params::new(task.id.clone());
+    match option {
+        EditOptions::Name => params.name = Some(text.to_owned()),
+        EditOptions::Description => params.desc = Some(text.to_owned()),
+        EditOptions::Deadline if text.is_empty() => params.no_deadline = true,
+        EditOptions::Deadline => {
+            dates::parse_date(text, cfg).wrap_err("Invalid deadline")?;
+            params.deadline = Some(text.to_owned());
+        }
+        EditOptions::Duration if text.is_empty() => params.no_duration = true,
+        EditOptions::Duration => {
+            if task.due.is_none() {
+                return Err(eyre!("Duration requires a due date, set one first."));
+            }
+            dates::parse_duration(text)?;
+            params.duration = Some(text.to_owned());
+        }
+        EditOptions::Due | EditOptions::Priority | EditOptions::Labels | EditOptions::Quit => {
+            return Err(eyre!("{option} is not edited as text"));
+        }
+    }
+    Ok(params)
+}
+
+/// Returns the labels of a task after picking the labels at the given indices out of all labels.
+/// Labels of the task that weren't available to pick, like shared labels, are kept.
+fn picked_labels(current: &[String], labels: &[Label], picked: &[usize]) -> Vec<String> {
+    current
+        .iter()
+        .filter(|name| !labels.iter().any(|l| l.name == **name))
+        .cloned()
+        .chain(picked.iter().map(|&i| labels[i].name.clone()))
+        .collect()
+}
+
+fn make_selection<T: ToString + std::fmt::Display>(
+    variants: &[T],
+    theme: &Theme,
+) -> Result<Option<usize>> {
+    dialoguer::FuzzySelect::with_theme(interactive::dialoguer_theme(theme).as_ref())
         .items(variants)
         .default(0)
         .interact_opt()
         .wrap_err("Unable to make a selection")
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::api::rest::{DueDate, Priority};
+    use clap::Parser;
+
+    fn task(id: &str, priority: Priority, due: Option<&str>) -> Tree<Task> {
+        let mut task = Task::new(id, id);
+        task.priority = priority;
+        task.due = due.map(DueDate::on);
+        Tree::new(task)
+    }
+
+    fn sorted_ids(tasks: &[Tree<Task>], sort_by: SortBy) -> Vec<&str> {
+        let mut tasks = tasks.iter().collect::<Vec<_>>();
+        apply_sort(&mut tasks, Some(&sort_by));
+        tasks.iter().map(|t| t.id.as_str()).collect()
+    }
+
+    #[test]
+    fn rolls_up_due_dates() {
+        let cfg = Config {
+            timezone: Some(chrono_tz::UTC),
+            ..Default::default()
+        };
+        let now = "2025-10-10T12:00:00Z".parse().unwrap();
+        let tasks = [
+            task("1", Priority::Normal, Some("2025-10-09")),
+            task("2", Priority::Normal, Some("2025-10-10T09:00:00Z")),
+            task("3", Priority::Normal, Some("2025-10-10")),
+            task("4", Priority::Normal, Some("2025-10-10T18:00:00Z")),
+            task("5", Priority::Normal, Some("2025-10-11")),
+            task("6", Priority::Normal, None),
+        ]
+        .map(|t| t.item);
+        let rollup = Rollup::count(&tasks, &now, &cfg);
+        assert_eq!(
+            rollup,
+            Rollup {
+                overdue: 2,
+                today: 2,
+                upcoming: 1
+            }
+        );
+        // Emoji are only shown on terminals, which the tests don't run in.
+        assert_eq!(rollup.line(&cfg), "2 overdue, 2 due today, 1 upcoming");
+        let plain = Config { plain: true, ..cfg };
+        assert_eq!(
+            rollup.line(&plain),
+            "[!] 2 overdue, 2 due today, 1 upcoming"
+        );
+    }
+
+    #[test]
+    fn menu_params_validate_input() {
+        let cfg = Config {
+            override_time: Some("2025-10-10T12:00:00Z".parse().unwrap()),
+            timezone: Some(chrono_tz::UTC),
+            ..Default::default()
+        };
+        let undated = task("1", Priority::Normal, None);
+        let dated = task("2", Priority::Normal, Some("2025-10-11"));
+
+        let params = menu_params(&EditOptions::Deadline, "next friday", &undated, &cfg).unwrap();
+        assert_eq!(params.deadline.as_deref(), Some("next friday"));
+        assert!(menu_params(&EditOptions::Deadline, "someday", &undated, &cfg).is_err());
+        assert!(
+            menu_params(&EditOptions::Deadline, "", &undated, &cfg)
+                .unwrap()
+                .no_deadline
+        );
+
+        let params = menu_params(&EditOptions::Duration, "30:minute", &dated, &cfg).unwrap();
+        assert_eq!(params.duration.as_deref(), Some("30:minute"));
+        assert!(menu_params(&EditOptions::Duration, "soon", &dated, &cfg).is_err());
+        assert!(menu_params(&EditOptions::Duration, "30:minute", &undated, &cfg).is_err());
+        assert!(
+            menu_params(&EditOptions::Duration, "", &dated, &cfg)
+                .unwrap()
+                .no_duration
+        );
+
+        let params = menu_params(&EditOptions::Name, "Renamed", &dated, &cfg).unwrap();
+        assert_eq!(params.name.as_deref(), Some("Renamed"));
+        assert!(menu_params(&EditOptions::Priority, "1", &dated, &cfg).is_err());
+    }
+
+    #[test]
+    fn groups_by_label() {
+        let cfg = Config::default();
+        let mut both = task("1", Priority::Normal, None);
+        both.item.content = "Both".to_string();
+        both.item.labels = vec!["home".to_string(), "work".to_string()];
+        let mut work = task("2", Priority::Normal, None);
+        work.item.content = "Work only".to_string();
+        work.item.labels = vec!["work".to_string()];
+        let mut none = task("3", Priority::Normal, None);
+        none.item.content = "Unlabeled".to_string();
+        let state = State::for_tests(vec![both, work, none], &cfg);
+        let mut out = Vec::new();
+        list_tasks_grouped_by(&state.tasks, &state, &GroupBy::Label, None, false, &mut out)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\n[home] (1/1 tasks)\n  Both\n\
+             \n[work] (2/2 tasks)\n  Both\n  Work only\n\
+             \n[(no label)] (1/1 tasks)\n  Unlabeled\n"
+        );
+    }
+
+    #[test]
+    fn groups_by_section_id() {
+        let cfg = Config::default();
+        let mut home = task("1", Priority::Normal, None);
+        home.item.content = "Home".to_string();
+        home.item.project_id = "1".to_string();
+        home.item.section_id = Some("10".to_string());
+        let mut work = task("2", Priority::Normal, None);
+        work.item.content = "Work".to_string();
+        work.item.project_id = "2".to_string();
+        work.item.section_id = Some("20".to_string());
+        let state = State {
+            projects: [Project::new("1", "Home"), Project::new("2", "Work")]
+                .into_iter()
+                .map(|p| (p.id.clone(), p))
+                .collect(),
+            sections: [
+                Section::new("10", "1", "Later"),
+                Section::new("20", "2", "Later"),
+            ]
+            .into_iter()
+            .map(|s| (s.id.clone(), s))
+            .collect(),
+            ..State::for_tests(vec![work, home], &cfg)
+        };
+        let mut out = Vec::new();
+        list_tasks_grouped_by(
+            &state.tasks,
+            &state,
+            &GroupBy::Section,
+            None,
+            false,
+            &mut out,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\n[Home / Later] (1/1 tasks)\n  Home\n\
+             \n[Work / Later] (1/1 tasks)\n  Work\n"
+        );
+    }
+
+    #[test]
+    fn filters_due_window() {
+        let date = |d| NaiveDate::from_ymd_opt(2025, 10, d).unwrap();
+        let no_due = task("1", Priority::Normal, None);
+        assert!(!due_between(&no_due, None, Some(date(10))));
+
+        let mut task = task("2", Priority::Normal, Some("2025-10-10")).item;
+        assert!(due_between(&task, None, None));
+        // Both bounds are exclusive.
+        assert!(!due_between(&task, None, Some(date(10))));
+        assert!(due_between(&task, None, Some(date(11))));
+        assert!(!due_between(&task, Some(date(10)), None));
+        assert!(due_between(&task, Some(date(9)), None));
+        assert!(due_between(&task, Some(date(9)), Some(date(11))));
+        assert!(!due_between(&task, Some(date(11)), Some(date(9))));
+
+        // Exact times count by their day.
+        task.due.as_mut().unwrap().date = "2025-10-10T23:30:00Z".to_string();
+        assert!(due_between(&task, Some(date(9)), Some(date(11))));
+        assert!(!due_between(&task, Some(date(10)), None));
+    }
+
+    #[tokio::test]
+    async fn filters_overdue_and_no_due() {
+        let cfg = Config {
+            override_time: Some("2025-10-10T12:00:00Z".parse().unwrap()),
+            timezone: Some(chrono_tz::UTC),
+            ..Default::default()
+        };
+        let mut recurring = task("3", Priority::Normal, Some("2025-10-09"));
+        recurring.item.due.as_mut().unwrap().is_recurring = true;
+        let tasks = vec![
+            task("1", Priority::Normal, Some("2025-10-10T11:00:00Z")),
+            task("2", Priority::Normal, Some("2025-10-10T13:00:00Z")),
+            recurring,
+            task("4", Priority::Normal, Some("2025-10-10")),
+            task("5", Priority::Normal, None),
+        ];
+        // Only used to resolve assignees, so it never gets called.
+        let gw = Gateway::new("", &"http://localhost".parse().unwrap());
+        let filtered = async |flag| {
+            let state = State::for_tests(tasks.clone(), &cfg);
+            let params = Params::try_parse_from(["list", flag]).unwrap();
+            let state = filter_list(state, &params, &gw).await.unwrap();
+            state.tasks.iter().map(|t| t.id.clone()).collect::<Vec<_>>()
+        };
+        assert_eq!(filtered("--overdue").await, ["1", "3"]);
+        assert_eq!(filtered("--no-due").await, ["5"]);
+    }
+
+    #[test]
+    fn groups_by_project_hierarchy() {
+        let cfg = Config::default();
+        let work = Project::new("1", "Work");
+        let mut backend = Project::new("2", "Backend");
+        backend.parent_id = Some("1".to_string());
+        let mut api = Project::new("3", "API");
+        api.parent_id = Some("2".to_string());
+        let mut admin = Project::new("4", "Admin");
+        admin.parent_id = Some("1".to_string());
+        let home = Project::new("5", "Home");
+        let idle = Project::new("6", "Idle");
+        let projects = [work, backend, api, admin, home, idle]
+            .into_iter()
+            .map(|p| (p.id.clone(), p))
+            .collect();
+
+        let mut tasks = Vec::new();
+        for (id, project) in [("1", "3"), ("2", "4"), ("3", "4"), ("4", "5"), ("5", "9")] {
+            let mut task = task(id, Priority::Normal, None);
+            task.item.content = format!("Task {id}");
+            task.item.project_id = project.to_string();
+            tasks.push(task);
+        }
+        let state = State {
+            projects,
+            ..State::for_tests(tasks, &cfg)
+        };
+        let mut out = Vec::new();
+        list_tasks_grouped_by(
+            &state.tasks,
+            &state,
+            &GroupBy::Project,
+            None,
+            false,
+            &mut out,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\n[Home] (1/1 tasks)\n  Task 4\n\
+             \n[Work]\n\
+             \n  [Admin] (2/2 tasks)\n    Task 2\n    Task 3\n\
+             \n  [Backend]\n\
+             \n    [API] (1/1 tasks)\n      Task 1\n\
+             \n[9] (1/1 tasks)\n  Task 5\n"
+        );
+    }
+
+    #[test]
+    fn draws_tree_branches() {
+        let mut tasks = Vec::new();
+        for (id, parent) in [
+            ("1", None),
+            ("2", Some("1")),
+            ("3", Some("2")),
+            ("4", Some("2")),
+            ("5", Some("1")),
+            ("6", Some("5")),
+            ("7", None),
+        ] {
+            let mut task = Task::new(id, &format!("Task {id}"));
+            task.parent_id = parent.map(|p| p.to_string());
+            tasks.push(task);
+        }
+        let tasks = Tree::from_items(tasks).unwrap();
+        let render = |tree_style| {
+            let cfg = Config {
+                tree_style,
+                ..Default::default()
+            };
+            let state = State::for_tests(tasks.clone(), &cfg);
+            let mut out = Vec::new();
+            list_tasks_with_sort(&state.tasks, &state, None, false, &mut out).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+        assert_eq!(
+            render(TreeStyle::Unicode).lines().collect::<Vec<_>>(),
+            vec![
+                "Task 1",
+                "├─ Task 2",
+                "│  ├─ Task 3",
+                "│  └─ Task 4",
+                "└─ Task 5",
+                "   └─ Task 6",
+                "Task 7",
+            ]
+        );
+        assert_eq!(
+            render(TreeStyle::Ascii).lines().collect::<Vec<_>>(),
+            vec![
+                "Task 1",
+                "|- Task 2",
+                "|  |- Task 3",
+                "|  `- Task 4",
+                "`- Task 5",
+                "   `- Task 6",
+                "Task 7",
+            ]
+        );
+    }
+
+    #[test]
+    fn picks_labels() {
+        let labels = [Label::new("1", "home"), Label::new("2", "work")];
+        let current = vec!["home".to_string(), "shared".to_string()];
+        assert_eq!(
+            picked_labels(&current, &labels, &[1]),
+            vec!["shared".to_string(), "work".to_string()]
+        );
+        assert_eq!(
+            picked_labels(&current, &labels, &[0, 1]),
+            vec!["shared".to_string(), "home".to_string(), "work".to_string()]
+        );
+        assert!(picked_labels(&["home".to_string()], &labels, &[]).is_empty());
+    }
+
+    #[test]
+    fn sorts_by_priority_and_due() {
+        let tasks = vec![
+            task("1", Priority::Normal, Some("2025-10-03")),
+            task("2", Priority::Urgent, None),
+            task("3", Priority::High, Some("2025-10-01T09:00:00Z")),
+            task("4", Priority::VeryHigh, Some("2025-10-01")),
+            task("5", Priority::Urgent, Some("2025-10-02")),
+        ];
+        assert_eq!(
+            sorted_ids(&tasks, SortBy::Priority),
+            vec!["2", "5", "4", "3", "1"]
+        );
+        assert_eq!(
+            sorted_ids(&tasks, SortBy::Due),
+            vec!["3", "4", "5", "1", "2"]
+        );
+    }
+}
@@ -1,14 +1,26 @@
 //! Controls things that work with [`crate::api::rest::Task`]s.
 pub mod add;
+mod assignee;
+pub mod bump;
 pub mod close;
 pub mod comment;
+pub mod comments;
 pub mod completed;
 pub mod create;
+mod dates;
+pub mod duplicate;
 pub mod edit;
 mod filter;
+pub mod import;
 pub mod list;
+pub mod move_task;
 mod priority;
+pub mod reopen;
+pub mod reorder;
+pub mod reschedule;
+pub mod show;
 mod state;
+pub mod stats;
 pub mod view;
 
 pub use priority::*;
@@ -0,0 +1,84 @@
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, eyre},
+};
+use owo_colors::{OwoColorize, Stream};
+
+use crate::{
+    api::rest::{Gateway, Project, Section},
+    config::Config,
+    interactive,
+    output::info,
+    tasks::state::available,
+};
+
+use super::filter;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    #[clap(flatten)]
+    pub task: filter::TaskOrInteractive,
+    #[clap(flatten)]
+    project: interactive::Selection<Project>,
+    #[clap(flatten)]
+    section: interactive::Selection<Section>,
+}
+
+/// Moves a task into another project and/or section. If only a section is given, the task is
+/// moved into the project of that section.
+pub async fn move_task(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let id = params
+        .task
+        .task_id(gw, cfg)
+        .await
+        .wrap_err("no task selected for moving")?;
+    let (projects, sections) = tokio::join!(gw.projects(), gw.sections());
+    let projects = projects?;
+    let sections = available("sections", sections);
+    let project = params.project.optional(&projects)?;
+    let section = params.section.optional(&sections)?;
+    let project_id = match (project, section) {
+        (None, None) => {
+            return Err(eyre!(
+                "nothing to move to, specify a project (-P) and/or a section (-S)"
+            ));
+        }
+        (Some(p), Some(s)) if p.id != s.project_id => {
+            return Err(eyre!(
+                "section {} is not part of project {}",
+                s.name,
+                p.name
+            ));
+        }
+        (Some(p), _) => &p.id,
+        (None, Some(s)) => &s.project_id,
+    };
+    let payload = serde_json::json!({
+        "project_id": project_id,
+        "section_id": section.map(|s| &s.id),
+    });
+    if gw.dry_run(&format!("move task {id}"), &payload)? {
+        return Ok(());
+    }
+    gw.move_task(&id, Some(project_id), section.map(|s| &s.id))
+        .await?;
+    let project_name = projects
+        .iter()
+        .find(|p| p.id == *project_id)
+        .map(|p| p.name.as_str())
+        .unwrap_or(project_id);
+    match section {
+        Some(s) => info!(
+            "moved task {} to [{}/{}]",
+            id.if_supports_color(Stream::Stdout, |text| text.bright_red()),
+            project_name,
+            s.name
+        ),
+        None => info!(
+            "moved task {} to [{}]",
+            id.if_supports_color(Stream::Stdout, |text| text.bright_red()),
+            project_name
+        ),
+    }
+    Ok(())
+}
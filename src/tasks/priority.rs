@@ -3,15 +3,22 @@ use color_eyre::eyre::eyre;
 use serde::{Deserialize, Serialize};
 
 /// Maps priority from arguments to API priorities.
-#[derive(clap::ValueEnum, Debug, Copy, Clone, Deserialize, Serialize)]
+///
+/// Arguments use the numbering of the Todoist apps, where p1 is the most urgent priority, while
+/// the API uses 4 for it.
+#[derive(clap::ValueEnum, Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub enum Priority {
-    #[value(name = "1")]
+    /// Urgent, shown in red in the apps. Priority 4 in the API.
+    #[value(name = "1", alias = "p1")]
     Urgent,
-    #[value(name = "2")]
+    /// Very high, shown in orange in the apps. Priority 3 in the API.
+    #[value(name = "2", alias = "p2")]
     VeryHigh,
-    #[value(name = "3")]
+    /// High, shown in blue in the apps. Priority 2 in the API.
+    #[value(name = "3", alias = "p3")]
     High,
-    #[value(name = "4")]
+    /// Normal, the default without a color. Priority 1 in the API.
+    #[value(name = "4", alias = "p4")]
     Normal,
 }
 
@@ -40,3 +47,30 @@ impl TryFrom<usize> for Priority {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use clap::ValueEnum;
+
+    use super::*;
+
+    #[test]
+    fn parses_ui_priorities() {
+        for (input, priority) in [
+            ("p1", Priority::Urgent),
+            ("1", Priority::Urgent),
+            ("P1", Priority::Urgent),
+            ("p2", Priority::VeryHigh),
+            ("p3", Priority::High),
+            ("p4", Priority::Normal),
+            ("4", Priority::Normal),
+        ] {
+            assert_eq!(Priority::from_str(input, true), Ok(priority), "{input}");
+        }
+        assert!(Priority::from_str("p5", true).is_err());
+        assert_eq!(
+            RESTPriority::from(Priority::from_str("p1", true).unwrap()),
+            RESTPriority::Urgent
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use chrono::{Duration, Utc};
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, eyre},
+};
+use owo_colors::{OwoColorize, Stream};
+
+use crate::{
+    api::rest::{COMPLETED_TASKS_MAX_LIMIT, CompletedTasksByCompletionDateParams, Gateway, TaskID},
+    config::Config,
+    interactive,
+    output::info,
+};
+
+use super::filter;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// The Task ID as provided from the Todoist API. Use `completed` to find out what ID your
+    /// task has. If omitted, will interactively select among the tasks completed in the last week.
+    id: Option<TaskID>,
+    #[clap(flatten)]
+    filter: filter::Filter,
+}
+
+/// Reopens a previously closed task.
+///
+/// Tasks that were closed with `close --complete` had their due date changed to today before
+/// closing, so reopening them will not restore the original recurring due date.
+pub async fn reopen(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let id = match params.id {
+        Some(id) => id,
+        None => select_completed(&params.filter, gw, cfg)
+            .await
+            .wrap_err("no task selected for reopening")?,
+    };
+    gw.reopen(&id).await?;
+    info!(
+        "reopened task {}",
+        id.if_supports_color(Stream::Stdout, |text| text.bright_green())
+    );
+    Ok(())
+}
+
+/// Selects one of the tasks completed in the last week.
+async fn select_completed(filter: &filter::Filter, gw: &Gateway, cfg: &Config) -> Result<TaskID> {
+    let now = cfg.override_time.unwrap_or_else(Utc::now);
+    let since = (now - Duration::weeks(1))
+        .format("%Y-%m-%dT%H:%M:%SZ")
+        .to_string();
+    let until = now.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+    let tasks = gw
+        .completed_tasks_by_completion_date(CompletedTasksByCompletionDateParams {
+            since: &since,
+            until: &until,
+            workspace_id: None,
+            project_id: None,
+            section_id: None,
+            parent_id: None,
+            filter_query: filter.query(),
+            cursor: None,
+            limit: Some(COMPLETED_TASKS_MAX_LIMIT),
+        })
+        .await?
+        .items;
+    if tasks.is_empty() {
+        return Err(eyre!("no tasks were completed in the last week"));
+    }
+    let index = interactive::select(
+        "Select task to reopen",
+        &tasks.iter().map(|t| &t.content).collect::<Vec<_>>(),
+        &cfg.theme,
+    )?
+    .ok_or_else(|| eyre!("no task selected"))?;
+    Ok(tasks[index].id.clone())
+}
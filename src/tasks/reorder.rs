@@ -0,0 +1,123 @@
+use color_eyre::{Result, eyre::eyre};
+
+use crate::{
+    api::rest::{Gateway, Task, TaskID},
+    output::info,
+};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// IDs of the tasks in the order they should be in. All of them need to be in the same
+    /// project and section, and have the same parent task.
+    #[arg(required = true, num_args = 2..)]
+    ids: Vec<TaskID>,
+}
+
+/// Puts the given tasks into the given order. Other tasks of the list keep their place, as the
+/// given tasks only swap the positions they already had.
+pub async fn reorder(params: Params, gw: &Gateway) -> Result<()> {
+    if let Some((i, id)) = params
+        .ids
+        .iter()
+        .enumerate()
+        .find(|(i, id)| params.ids[..*i].contains(id))
+    {
+        return Err(eyre!(
+            "task {id} is given more than once (position {})",
+            i + 1
+        ));
+    }
+    let tasks = futures::future::try_join_all(params.ids.iter().map(|id| gw.task(id))).await?;
+    if let Some(task) = tasks.iter().find(|task| !are_siblings(task, &tasks[0])) {
+        return Err(eyre!(
+            "task {} has a different project, section or parent task than task {}",
+            task.id,
+            tasks[0].id
+        ));
+    }
+    let siblings = gw.project_tasks(&tasks[0].project_id).await?;
+    let siblings = siblings.iter().filter(|t| are_siblings(t, &tasks[0]));
+    let items = reordered(siblings, &tasks);
+    if gw.dry_run("reorder tasks", &items)? {
+        return Ok(());
+    }
+    gw.reorder_tasks(&items).await?;
+    info!("reordered {} tasks", items.len());
+    Ok(())
+}
+
+/// Checks if both tasks are in the same list of tasks, which is the only place they can be
+/// ordered in.
+pub(super) fn are_siblings(a: &Task, b: &Task) -> bool {
+    a.project_id == b.project_id && a.section_id == b.section_id && a.parent_id == b.parent_id
+}
+
+/// Numbers the whole list of siblings from the top, with the given tasks taking the places they
+/// already had in the order they are given. Renumbering all of them keeps the order exact, even
+/// if some tasks shared an order value.
+///
+/// * `siblings` - the other tasks of the list, the given tasks may be included.
+/// * `tasks` - the tasks to reorder, in their new order.
+pub(super) fn reordered<'a>(
+    siblings: impl IntoIterator<Item = &'a Task>,
+    tasks: &'a [Task],
+) -> Vec<(TaskID, isize)> {
+    let is_given = |task: &Task| tasks.iter().any(|t| t.id == task.id);
+    let mut list = siblings
+        .into_iter()
+        .filter(|t| !is_given(t))
+        .chain(tasks)
+        .collect::<Vec<_>>();
+    list.sort_by(|a, b| a.order.cmp(&b.order).then_with(|| a.id.cmp(&b.id)));
+    let mut given = tasks.iter();
+    list.into_iter()
+        .map(|task| {
+            if is_given(task) {
+                given.next().unwrap_or(task)
+            } else {
+                task
+            }
+        })
+        .zip(1..)
+        .map(|(task, order)| (task.id.clone(), order))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn task(id: &str, order: isize) -> Task {
+        let mut task = Task::new(id, id);
+        task.order = order;
+        task
+    }
+
+    fn ids(items: &[(TaskID, isize)]) -> Vec<(&str, isize)> {
+        items
+            .iter()
+            .map(|(id, order)| (id.as_str(), *order))
+            .collect()
+    }
+
+    #[test]
+    fn reuses_existing_places() {
+        let siblings = [task("x", 3), task("y", 5)];
+        let tasks = [task("a", 7), task("b", 2), task("c", 4)];
+        assert_eq!(
+            ids(&reordered(&siblings, &tasks)),
+            [("a", 1), ("x", 2), ("b", 3), ("y", 4), ("c", 5)]
+        );
+    }
+
+    #[test]
+    fn renumbers_shared_orders() {
+        // The given tasks share their order values with a task that isn't reordered.
+        let siblings = [task("a", 1), task("b", 1), task("x", 1), task("y", 2)];
+        let tasks = [task("b", 1), task("a", 1)];
+        assert_eq!(
+            ids(&reordered(&siblings, &tasks)),
+            [("b", 1), ("a", 2), ("x", 3), ("y", 4)]
+        );
+    }
+}
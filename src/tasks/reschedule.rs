@@ -0,0 +1,179 @@
+use chrono::{Days, NaiveDate, Utc};
+use color_eyre::{Result, eyre::eyre};
+use owo_colors::{OwoColorize, Stream};
+
+use crate::{
+    api::{
+        rest::{Gateway, Task, TaskDue, UpdateTask},
+        tree::Tree,
+    },
+    config::Config,
+    output::info,
+    tasks::state::State,
+};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Distributes the overdue tasks evenly across this many days, starting today. Without it,
+    /// all overdue tasks are moved to today.
+    #[arg(long = "spread", default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    spread: u32,
+    /// Maximum amount of tasks to put on a single day. Tasks that don't fit stay overdue.
+    #[arg(long = "max-per-day", value_parser = clap::value_parser!(u32).range(1..))]
+    max_per_day: Option<u32>,
+    /// Also reschedules recurring tasks. This replaces their recurrence with a single due date.
+    #[arg(long = "include-recurring")]
+    include_recurring: bool,
+}
+
+/// Moves overdue tasks to today, or spreads them across the upcoming days.
+pub async fn reschedule(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let state = State::fetch_tree(Some("overdue"), gw, cfg).await?;
+    let mut tasks = Vec::new();
+    collect(&state.tasks, params.include_recurring, &mut tasks);
+    if tasks.is_empty() {
+        info!("No overdue tasks to reschedule");
+        return Ok(());
+    }
+    // Oldest first, so the tasks that waited the longest get the earliest days.
+    tasks.sort_by(|a, b| {
+        let due = |t: &Task| t.due.as_ref().and_then(|d| d.date_naive());
+        due(a).cmp(&due(b)).then_with(|| a.cmp(b))
+    });
+
+    let now = cfg.override_time.unwrap_or_else(Utc::now);
+    let today = cfg.local_date(&now);
+    let (planned, unscheduled) = plan(tasks, today, params.spread, params.max_per_day)?;
+
+    for (date, tasks) in &planned {
+        info!(
+            "{} ({} tasks)",
+            date.format("%a %m/%d")
+                .if_supports_color(Stream::Stdout, |text| text.bold()),
+            tasks.len()
+        );
+        for task in tasks {
+            // Subtasks are shown flat, as they are planned independently of their parents.
+            let task = Tree::new((*task).clone());
+            info!("  {}", state.table_task(&task, false));
+        }
+    }
+    if !unscheduled.is_empty() {
+        eprintln!(
+            "{} tasks did not fit and stay overdue, increase --spread or --max-per-day",
+            unscheduled.len()
+        );
+    }
+    // The plan above already shows everything that would change.
+    if gw.is_dry_run() {
+        return Ok(());
+    }
+
+    let mut rescheduled = 0;
+    for (date, tasks) in &planned {
+        for task in tasks {
+            gw.update(
+                &task.id,
+                &UpdateTask {
+                    due: Some(TaskDue::Date(date.format("%Y-%m-%d").to_string())),
+                    ..Default::default()
+                },
+            )
+            .await?;
+            rescheduled += 1;
+        }
+    }
+    info!("rescheduled {rescheduled} tasks");
+    Ok(())
+}
+
+/// Flattens the task trees, skipping recurring tasks unless they are included.
+fn collect<'a>(tasks: &'a [Tree<Task>], include_recurring: bool, out: &mut Vec<&'a Task>) {
+    for task in tasks {
+        let recurring = task.due.as_ref().is_some_and(|d| d.is_recurring);
+        if include_recurring || !recurring {
+            out.push(&task.item);
+        }
+        collect(&task.subitems, include_recurring, out);
+    }
+}
+
+/// A list of days with the tasks planned on each of them.
+type Plan<'a> = Vec<(NaiveDate, Vec<&'a Task>)>;
+
+/// Distributes the tasks round-robin across `days` days starting at `start`, so every day gets
+/// about the same amount. Returns the plan and the tasks that did not fit due to `max_per_day`.
+fn plan(
+    tasks: Vec<&Task>,
+    start: NaiveDate,
+    days: u32,
+    max_per_day: Option<u32>,
+) -> Result<(Plan<'_>, Vec<&Task>)> {
+    let mut planned = (0..days)
+        .map(|offset| {
+            start
+                .checked_add_days(Days::new(offset.into()))
+                .map(|date| (date, Vec::new()))
+                .ok_or_else(|| eyre!("--spread of {days} days is out of range"))
+        })
+        .collect::<Result<Plan>>()?;
+    let capacity = max_per_day.map(|max| max as usize * days as usize);
+    let mut unscheduled = Vec::new();
+    for (i, task) in tasks.into_iter().enumerate() {
+        if capacity.is_some_and(|c| i >= c) {
+            unscheduled.push(task);
+        } else {
+            planned[i % days as usize].1.push(task);
+        }
+    }
+    planned.retain(|(_, tasks)| !tasks.is_empty());
+    Ok((planned, unscheduled))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn plans_round_robin() {
+        let tasks = (1..=7)
+            .map(|i| Task::new(&i.to_string(), "task"))
+            .collect::<Vec<_>>();
+        let start = NaiveDate::from_ymd_opt(2025, 10, 30).unwrap();
+        fn ids(plan: &Plan) -> Vec<(String, Vec<String>)> {
+            plan.iter()
+                .map(|(date, tasks)| {
+                    (
+                        date.format("%m/%d").to_string(),
+                        tasks.iter().map(|t| t.id.clone()).collect(),
+                    )
+                })
+                .collect()
+        }
+
+        let (planned, unscheduled) = plan(tasks.iter().collect(), start, 3, None).unwrap();
+        assert_eq!(
+            ids(&planned),
+            vec![
+                (
+                    "10/30".to_string(),
+                    vec!["1".to_string(), "4".into(), "7".into()]
+                ),
+                ("10/31".to_string(), vec!["2".to_string(), "5".into()]),
+                ("11/01".to_string(), vec!["3".to_string(), "6".into()]),
+            ]
+        );
+        assert!(unscheduled.is_empty());
+
+        let (planned, unscheduled) = plan(tasks.iter().collect(), start, 3, Some(2)).unwrap();
+        assert_eq!(planned.iter().map(|(_, t)| t.len()).sum::<usize>(), 6);
+        assert_eq!(unscheduled.len(), 1);
+        assert_eq!(unscheduled[0].id, "7");
+
+        let (planned, _) = plan(tasks[..1].iter().collect(), start, 5, None).unwrap();
+        assert_eq!(
+            ids(&planned),
+            vec![("10/30".to_string(), vec!["1".to_string()])]
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use color_eyre::{Result, eyre::WrapErr};
+
+use crate::{
+    api::rest::{FullTask, Gateway, TaskID},
+    comments,
+    config::Config,
+    output::info,
+};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// The Task ID as provided from the Todoist API. Use `list` to find out what ID your task has.
+    id: TaskID,
+    /// Also lists the comments of the task.
+    #[arg(short = 'c', long = "comments")]
+    comments: bool,
+    /// Renders the Markdown of the description when printing to a terminal.
+    #[arg(long = "render")]
+    render: bool,
+}
+
+/// Prints full information about a single task without any interaction.
+pub async fn show(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let mut cfg = cfg.clone();
+    cfg.render_markdown |= params.render;
+    let task = gw
+        .task(&params.id)
+        .await
+        .wrap_err_with(|| format!("unable to get task {}", params.id))?;
+    let section = async {
+        match &task.section_id {
+            Some(id) => gw.section(id).await.map(Some),
+            None => Ok(None),
+        }
+    };
+    let (project, section, labels) =
+        tokio::try_join!(gw.project(&task.project_id), section, gw.labels())?;
+    let labels = labels
+        .iter()
+        .filter(|l| task.labels.contains(&l.name))
+        .collect();
+    info!(
+        "{}",
+        FullTask(&task, Some(&project), section.as_ref(), labels, &cfg)
+    );
+    if params.comments {
+        let comments = gw.task_comments(&task.id).await?;
+        let collaborators = comments::collaborators(Some(&project), gw).await;
+        comments::list(&comments, &collaborators)
+    }
+    Ok(())
+}
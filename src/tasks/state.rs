@@ -6,8 +6,8 @@ use owo_colors::OwoColorize;
 use crate::{
     api::{
         rest::{
-            FullTask, Gateway, Label, Project, ProjectID, Section, SectionID, TableTask, Task,
-            TaskID,
+            FullTask, Gateway, JsonTask, Label, Project, ProjectID, Section, SectionID, TableTask,
+            Task, TaskField, TaskID,
         },
         tree::{Tree, TreeFlattenExt},
     },
@@ -22,6 +22,23 @@ pub struct State<'a> {
     pub sections: HashMap<SectionID, Section>,
     pub labels: HashMap<String, Label>,
     pub config: &'a Config,
+    /// Patterns that are highlighted in task names when displaying tasks.
+    pub highlights: Vec<String>,
+    /// The fields that are shown when displaying tasks. Uses the default layout if unset.
+    pub fields: Option<Vec<TaskField>>,
+}
+
+/// Returns the fetched items, or warns about the failed fetch and continues without them.
+///
+/// Useful for metadata that is nice to have, but not essential to run a command.
+pub fn available<T>(what: &str, result: Result<Vec<T>>) -> Vec<T> {
+    match result {
+        Ok(items) => items,
+        Err(e) => {
+            eprintln!("{} unavailable: {e:#}", what);
+            Vec::new()
+        }
+    }
 }
 
 // TaskMenu is used for the more complex fully interactive task creation.
@@ -39,6 +56,35 @@ impl<'a> State<'a> {
     ) -> Result<State<'a>> {
         let (filtered_tasks, projects, sections, labels) =
             tokio::try_join!(gw.tasks(filter), gw.projects(), gw.sections(), gw.labels())?;
+        Self::from_parts(filtered_tasks, projects, sections, labels, cfg)
+    }
+
+    /// Same as [`State::fetch_tree`], but only fetches a single page of tasks. Also returns the
+    /// cursor of the next page if there is one.
+    pub async fn fetch_tree_page(
+        filter: Option<&'_ str>,
+        cursor: Option<&'_ str>,
+        limit: Option<u32>,
+        gw: &'_ Gateway,
+        cfg: &'a Config,
+    ) -> Result<(State<'a>, Option<String>)> {
+        let (page, projects, sections, labels) = tokio::try_join!(
+            gw.tasks_paged(filter, cursor, limit),
+            gw.projects(),
+            gw.sections(),
+            gw.labels()
+        )?;
+        let state = Self::from_parts(page.results, projects, sections, labels, cfg)?;
+        Ok((state, page.next_cursor))
+    }
+
+    fn from_parts(
+        filtered_tasks: Vec<Task>,
+        projects: Vec<Project>,
+        sections: Vec<Section>,
+        labels: Vec<Label>,
+        cfg: &'a Config,
+    ) -> Result<State<'a>> {
         let projects = projects.into_iter().map(|p| (p.id.clone(), p)).collect();
         let sections = sections.into_iter().map(|s| (s.id.clone(), s)).collect();
         // We save by name so it works with the shared labels concept of todoist
@@ -50,6 +96,8 @@ impl<'a> State<'a> {
             sections,
             labels,
             config: cfg,
+            highlights: Vec::new(),
+            fields: None,
         })
     }
     pub async fn fetch_full_tree(
@@ -80,6 +128,7 @@ impl<'a> State<'a> {
                 .iter()
                 .map(|t| self.table_task(t, true))
                 .collect::<Vec<_>>(),
+            &self.config.theme,
         )?;
         Ok(result.map(|index| items[index]))
     }
@@ -92,6 +141,7 @@ impl<'a> State<'a> {
                 .into_iter()
                 .chain(items.iter().map(|t| self.table_task(t, true).to_string()))
                 .collect::<Vec<_>>(),
+            &self.config.theme,
         )?;
         match result {
             Some(0) => Ok(TaskMenu::Menu),
@@ -111,6 +161,8 @@ impl<'a> State<'a> {
             sections: self.sections,
             labels: self.labels,
             config: self.config,
+            highlights: self.highlights,
+            fields: self.fields,
         }
     }
 
@@ -119,9 +171,7 @@ impl<'a> State<'a> {
     }
 
     fn section<'s>(&'s self, task: &'s Tree<Task>) -> Option<&'s Section> {
-        task.section_id
-            .as_ref()
-            .map(|s| self.sections.get(s).unwrap())
+        task.section_id.as_ref().and_then(|s| self.sections.get(s))
     }
 
     fn labels<'s>(&'s self, task: &'s Tree<Task>) -> Vec<&'s Label> {
@@ -132,14 +182,10 @@ impl<'a> State<'a> {
     }
 
     pub fn table_task<'s>(&'s self, task: &'s Tree<Task>, show_id: bool) -> TableTask<'s> {
-        TableTask(
-            task,
-            self.project(task),
-            self.section(task),
-            self.labels(task),
-            self.config,
-            show_id,
-        )
+        TableTask {
+            project: self.project(task),
+            ..self.table_task_without_project(task, show_id)
+        }
     }
 
     pub fn table_task_without_project<'s>(
@@ -147,14 +193,26 @@ impl<'a> State<'a> {
         task: &'s Tree<Task>,
         show_id: bool,
     ) -> TableTask<'s> {
-        TableTask(
+        TableTask {
             task,
-            None, // No project info to avoid duplication in grouped view
-            self.section(task),
-            self.labels(task),
-            self.config,
+            project: None, // No project info to avoid duplication in grouped view
+            section: self.section(task),
+            labels: self.labels(task),
+            config: self.config,
             show_id,
-        )
+            highlights: &self.highlights,
+            fields: self.fields.as_deref(),
+            branches: None,
+        }
+    }
+
+    pub fn json_task<'s>(&'s self, task: &'s Tree<Task>) -> JsonTask<'s> {
+        JsonTask {
+            task,
+            project_name: self.project(task).map(|p| p.name.as_str()),
+            section_name: self.section(task).map(|s| s.name.as_str()),
+            subtasks: task.subitems.iter().map(|t| self.json_task(t)).collect(),
+        }
     }
 
     pub fn full_task<'s>(&'s self, task: &'s Tree<Task>) -> FullTask<'s> {
@@ -167,3 +225,19 @@ impl<'a> State<'a> {
         )
     }
 }
+
+#[cfg(test)]
+impl<'a> State<'a> {
+    /// Builds a state without any projects, sections or labels, which only shows task names.
+    pub fn for_tests(tasks: Vec<Tree<Task>>, cfg: &'a Config) -> State<'a> {
+        State {
+            tasks,
+            projects: HashMap::new(),
+            sections: HashMap::new(),
+            labels: HashMap::new(),
+            config: cfg,
+            highlights: Vec::new(),
+            fields: Some(vec![TaskField::Content]),
+        }
+    }
+}
@@ -0,0 +1,379 @@
+use std::collections::BTreeSet;
+
+use chrono::{Datelike, Duration, Months, NaiveDate, Utc, Weekday};
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, eyre},
+};
+
+use crate::{
+    api::rest::{COMPLETED_TASKS_MAX_LIMIT, CompletedTasksByCompletionDateParams, Gateway, TaskID},
+    config::{Config, WeekStart},
+    output::info,
+};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Shows the current and longest completion streak of a recurring task, since it was
+    /// created.
+    #[arg(long = "streak", value_name = "TASK_ID")]
+    streak: TaskID,
+}
+
+/// How often a recurring task is expected to be completed.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Cadence {
+    /// Every given number of days.
+    Days(u32),
+    /// Every day from Monday to Friday.
+    Weekdays,
+    /// Every given number of weeks, with weeks starting on the given day.
+    Weeks(u32, WeekStart),
+    /// Every given number of months.
+    Months(u32),
+}
+
+impl Cadence {
+    /// Parses the cadence from the human-readable due string of a recurring task, like
+    /// "every 2 weeks" or "every other monday". Fails for cadences that can't be scored, like
+    /// hourly tasks.
+    fn from_due_string(due: &str, week_start: WeekStart) -> Result<Cadence> {
+        const WEEKDAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+        const MONTHS: [&str; 12] = [
+            "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+        ];
+        let lower = due.to_lowercase();
+        let words = lower
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|w| !w.is_empty())
+            .flat_map(|w| match w {
+                "every!" => vec!["every"],
+                "daily" => vec!["every", "day"],
+                "weekly" => vec!["every", "week"],
+                "monthly" => vec!["every", "month"],
+                "yearly" => vec!["every", "year"],
+                w => vec![w],
+            })
+            .collect::<Vec<_>>();
+        let unsupported = || eyre!("unable to calculate streaks of tasks due {due:?}");
+        let every = words
+            .iter()
+            .position(|w| *w == "every")
+            .ok_or_else(unsupported)?;
+        let (interval, rest) = match words.get(every + 1) {
+            Some(&"other") => (2, &words[every + 2..]),
+            Some(n) => match n.parse::<u32>() {
+                Ok(n) if n > 0 => (n, &words[every + 2..]),
+                _ => (1, &words[every + 1..]),
+            },
+            None => (1, &words[every + 1..]),
+        };
+        let unit = rest.first().ok_or_else(unsupported)?;
+        let cadence = if unit.starts_with("weekday") || unit.starts_with("workday") {
+            if interval != 1 {
+                return Err(unsupported());
+            }
+            Cadence::Weekdays
+        } else if unit.starts_with("day") {
+            Cadence::Days(interval)
+        } else if unit.starts_with("month")
+            || unit.starts_with(|c: char| c.is_ascii_digit())
+            || ["first", "last"].contains(unit)
+        {
+            // Days of the month, like "every 15th" or "every last friday".
+            Cadence::Months(interval)
+        } else if unit.starts_with("week") || WEEKDAYS.iter().any(|d| unit.starts_with(d)) {
+            Cadence::Weeks(interval, week_start)
+        } else if unit.starts_with("year") || MONTHS.iter().any(|m| unit.starts_with(m)) {
+            Cadence::Months(interval * 12)
+        } else {
+            return Err(unsupported());
+        };
+        Ok(cadence)
+    }
+
+    /// Maps a date to the period it belongs to. For weekdays, completions on a weekend count
+    /// for the following Monday. Periods longer than a day or week are counted from the Unix
+    /// epoch.
+    fn period(&self, date: NaiveDate) -> NaiveDate {
+        match *self {
+            Cadence::Days(days) => {
+                let since_epoch = (date - NaiveDate::default()).num_days();
+                date - Duration::days(since_epoch.rem_euclid(days.into()))
+            }
+            Cadence::Weekdays => match date.weekday() {
+                Weekday::Sat => date + Duration::days(2),
+                Weekday::Sun => date + Duration::days(1),
+                _ => date,
+            },
+            Cadence::Weeks(weeks, week_start) => {
+                let first = week_start.first_day(date);
+                let since_epoch = (first - week_start.first_day(NaiveDate::default())).num_weeks();
+                first - Duration::weeks(since_epoch.rem_euclid(weeks.into()))
+            }
+            Cadence::Months(months) => {
+                let since_epoch = (date.year() - 1970) * 12 + date.month0() as i32;
+                let start = since_epoch - since_epoch.rem_euclid(months as i32);
+                NaiveDate::from_ymd_opt(
+                    1970 + start.div_euclid(12),
+                    start.rem_euclid(12) as u32 + 1,
+                    1,
+                )
+                .expect("first day of a month is a valid date")
+            }
+        }
+    }
+
+    /// Returns the period following the given one.
+    fn next(&self, period: NaiveDate) -> NaiveDate {
+        match *self {
+            Cadence::Days(days) => period + Duration::days(days.into()),
+            Cadence::Weekdays => self.period(period + Duration::days(1)),
+            Cadence::Weeks(weeks, _) => period + Duration::weeks(weeks.into()),
+            Cadence::Months(months) => period + Months::new(months),
+        }
+    }
+
+    fn unit(&self, count: usize) -> &'static str {
+        match (self, count) {
+            (Cadence::Days(1), 1) => "day",
+            (Cadence::Days(1), _) => "days",
+            (Cadence::Weekdays, 1) => "weekday",
+            (Cadence::Weekdays, _) => "weekdays",
+            (Cadence::Weeks(1, _), 1) => "week",
+            (Cadence::Weeks(1, _), _) => "weeks",
+            (Cadence::Months(1), 1) => "month",
+            (Cadence::Months(1), _) => "months",
+            (_, 1) => "time",
+            (_, _) => "times",
+        }
+    }
+}
+
+/// Shows statistics about tasks.
+pub async fn stats(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let task = gw.task(&params.streak).await?;
+    let cadence = task
+        .due
+        .as_ref()
+        .map(|d| Cadence::from_due_string(&d.string, cfg.week_start))
+        .transpose()?
+        .unwrap_or(Cadence::Days(1));
+
+    // The API allows to look back at most 3 months at once, so the whole history since the task
+    // was created is fetched in windows of 12 weeks.
+    let now = cfg.override_time.unwrap_or_else(Utc::now);
+    let mut periods = BTreeSet::new();
+    let mut window_end = now;
+    while window_end > task.created_at {
+        let window_start = (window_end - Duration::weeks(12)).max(task.created_at);
+        let since = window_start.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        let until = window_end.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        let mut cursor: Option<String> = None;
+        loop {
+            let response = gw
+                .completed_tasks_by_completion_date(CompletedTasksByCompletionDateParams {
+                    since: &since,
+                    until: &until,
+                    workspace_id: None,
+                    project_id: Some(&task.project_id),
+                    section_id: None,
+                    parent_id: None,
+                    filter_query: None,
+                    cursor: cursor.as_deref(),
+                    limit: Some(COMPLETED_TASKS_MAX_LIMIT),
+                })
+                .await
+                .wrap_err("failed to fetch completion history")?;
+            periods.extend(
+                response
+                    .items
+                    .iter()
+                    .filter(|t| t.id == task.id)
+                    .filter_map(|t| t.completed_at.as_deref())
+                    .filter_map(|c| chrono::DateTime::parse_from_rfc3339(c).ok())
+                    .map(|c| cadence.period(cfg.local_date(&c))),
+            );
+            cursor = response.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        window_end = window_start;
+    }
+
+    let (current, longest) = streaks(&periods, cadence.period(cfg.local_date(&now)), cadence);
+    info!("{}", task.content);
+    info!(
+        "Current streak: {current} {}, Longest: {longest}",
+        cadence.unit(current)
+    );
+    Ok(())
+}
+
+/// Calculates the current and the longest streak of consecutive periods.
+///
+/// The current streak is still considered alive if the current period has not been completed
+/// yet, but the previous one was.
+fn streaks(periods: &BTreeSet<NaiveDate>, current: NaiveDate, cadence: Cadence) -> (usize, usize) {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut last: Option<NaiveDate> = None;
+    for &period in periods {
+        run = match last {
+            Some(l) if cadence.next(l) == period => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        last = Some(period);
+    }
+    let current_streak = match last {
+        Some(l) if l == current || cadence.next(l) == current => run,
+        _ => 0,
+    };
+    (current_streak, longest)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn date(d: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(d, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn daily_streaks() {
+        let periods = [
+            "2025-10-01",
+            "2025-10-02",
+            "2025-10-03",
+            "2025-10-05",
+            "2025-10-06",
+        ]
+        .into_iter()
+        .map(date)
+        .collect();
+        let daily = Cadence::Days(1);
+        assert_eq!(streaks(&periods, date("2025-10-06"), daily), (2, 3));
+        assert_eq!(streaks(&periods, date("2025-10-07"), daily), (2, 3));
+        assert_eq!(streaks(&periods, date("2025-10-08"), daily), (0, 3));
+        assert_eq!(streaks(&BTreeSet::new(), date("2025-10-08"), daily), (0, 0));
+    }
+
+    #[test]
+    fn weekly_streaks() {
+        let cadence = Cadence::Weeks(1, WeekStart::Monday);
+        let periods = ["2025-09-16", "2025-09-24", "2025-10-02"]
+            .into_iter()
+            .map(|d| cadence.period(date(d)))
+            .collect();
+        assert_eq!(
+            streaks(&periods, cadence.period(date("2025-10-08")), cadence),
+            (3, 3)
+        );
+        assert_eq!(cadence.period(date("2025-10-05")), date("2025-09-29"));
+
+        // With weeks starting on Sunday, a Sunday completion belongs to the week it starts.
+        let cadence = Cadence::Weeks(1, WeekStart::Sunday);
+        assert_eq!(cadence.period(date("2025-10-05")), date("2025-10-05"));
+        let periods = ["2025-09-21", "2025-09-28", "2025-10-05"]
+            .into_iter()
+            .map(|d| cadence.period(date(d)))
+            .collect();
+        assert_eq!(
+            streaks(&periods, cadence.period(date("2025-10-08")), cadence),
+            (3, 3)
+        );
+    }
+
+    #[test]
+    fn weekday_streaks() {
+        let cadence = Cadence::Weekdays;
+        let periods = |dates: &[&str]| -> BTreeSet<NaiveDate> {
+            dates.iter().map(|d| cadence.period(date(d))).collect()
+        };
+        // On the weekend the streak is still alive, as Monday is the next period.
+        let thursday_to_friday = periods(&["2025-10-02", "2025-10-03"]);
+        assert_eq!(
+            streaks(
+                &thursday_to_friday,
+                cadence.period(date("2025-10-05")),
+                cadence
+            ),
+            (2, 2)
+        );
+        // Thursday to the following Tuesday, skipping the weekend.
+        let periods = periods(&["2025-10-02", "2025-10-03", "2025-10-06", "2025-10-07"]);
+        assert_eq!(
+            streaks(&periods, cadence.period(date("2025-10-08")), cadence),
+            (4, 4)
+        );
+        assert_eq!(cadence.period(date("2025-10-04")), date("2025-10-06"));
+    }
+
+    #[test]
+    fn interval_streaks() {
+        let cadence = Cadence::Days(2);
+        let periods = ["2025-10-01", "2025-10-03", "2025-10-05"]
+            .into_iter()
+            .map(|d| cadence.period(date(d)))
+            .collect();
+        assert_eq!(
+            streaks(&periods, cadence.period(date("2025-10-06")), cadence),
+            (3, 3)
+        );
+
+        let cadence = Cadence::Weeks(2, WeekStart::Monday);
+        let periods = ["2025-09-09", "2025-09-24", "2025-10-06"]
+            .into_iter()
+            .map(|d| cadence.period(date(d)))
+            .collect();
+        assert_eq!(
+            streaks(&periods, cadence.period(date("2025-10-08")), cadence),
+            (3, 3)
+        );
+
+        let cadence = Cadence::Months(1);
+        assert_eq!(cadence.period(date("2025-10-15")), date("2025-10-01"));
+        let periods = ["2025-08-31", "2025-09-01", "2025-10-15"]
+            .into_iter()
+            .map(|d| cadence.period(date(d)))
+            .collect();
+        assert_eq!(
+            streaks(&periods, cadence.period(date("2025-11-02")), cadence),
+            (3, 3)
+        );
+        assert_eq!(
+            Cadence::Months(12).period(date("2025-10-15")),
+            date("2025-01-01")
+        );
+    }
+
+    #[test]
+    fn cadence_from_due() {
+        let cadence = |due| Cadence::from_due_string(due, WeekStart::Sunday).unwrap();
+        let weeks = |n| Cadence::Weeks(n, WeekStart::Sunday);
+        assert_eq!(cadence("every day"), Cadence::Days(1));
+        assert_eq!(cadence("daily"), Cadence::Days(1));
+        assert_eq!(cadence("every 2 days"), Cadence::Days(2));
+        assert_eq!(cadence("every other day"), Cadence::Days(2));
+        assert_eq!(cadence("every weekday"), Cadence::Weekdays);
+        assert_eq!(cadence("every week"), weeks(1));
+        assert_eq!(cadence("every monday"), weeks(1));
+        assert_eq!(cadence("every mon, fri"), weeks(1));
+        assert_eq!(cadence("every 2 weeks"), weeks(2));
+        assert_eq!(cadence("every other week"), weeks(2));
+        assert_eq!(cadence("every other monday"), weeks(2));
+        assert_eq!(cadence("every month"), Cadence::Months(1));
+        assert_eq!(cadence("every 15th"), Cadence::Months(1));
+        assert_eq!(cadence("every last friday"), Cadence::Months(1));
+        assert_eq!(cadence("every 3 months"), Cadence::Months(3));
+        assert_eq!(cadence("every year"), Cadence::Months(12));
+        assert_eq!(cadence("every! jan 1"), Cadence::Months(12));
+        let unsupported = |due| Cadence::from_due_string(due, WeekStart::Sunday).is_err();
+        assert!(unsupported("every 2 hours"));
+        assert!(unsupported("every 2 weekdays"));
+        assert!(unsupported("tomorrow"));
+    }
+}
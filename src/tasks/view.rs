@@ -1,6 +1,6 @@
 use color_eyre::{Result, eyre::eyre};
 
-use crate::{api::rest::Gateway, comments, config::Config};
+use crate::{api::rest::Gateway, comments, config::Config, output::info};
 
 use super::filter::TaskOrInteractive;
 
@@ -14,10 +14,12 @@ pub struct Params {
 pub async fn view(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
     let (id, state) = params.task.task(gw, cfg).await?;
     let task = state.full_task(state.task(&id).ok_or_else(|| eyre!("no valid task"))?);
-    println!("{task}");
+    info!("{task}");
     if task.0.comment_count > 0 {
         let comments = gw.task_comments(&id).await?;
-        comments::list(&comments)
+        let collaborators =
+            comments::collaborators(state.projects.get(&task.0.project_id), gw).await;
+        comments::list(&comments, &collaborators)
     }
     Ok(())
 }
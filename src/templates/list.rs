@@ -0,0 +1,58 @@
+use std::fmt::Write;
+
+use color_eyre::Result;
+use owo_colors::{OwoColorize, Stream};
+
+use crate::{config::Config, output::info};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {}
+
+/// Lists the task and project templates from the config.
+pub fn list(_params: Params, cfg: &Config) -> Result<()> {
+    if cfg.templates.is_empty() && cfg.project_templates.is_empty() {
+        info!(
+            "No templates configured, add them to the [templates] or [project_templates] section of the config."
+        );
+        return Ok(());
+    }
+    for (name, template) in &cfg.templates {
+        let mut line = name
+            .if_supports_color(Stream::Stdout, |text| text.bright_yellow())
+            .to_string();
+        if let Some(content) = &template.content {
+            write!(line, " {content}")?;
+        }
+        if let Some(priority) = template.priority {
+            write!(line, " p{priority}")?;
+        }
+        for label in &template.labels {
+            write!(line, " @{label}")?;
+        }
+        if let Some(project) = &template.project {
+            write!(line, " [{project}]")?;
+        }
+        if let Some(due) = &template.due {
+            write!(line, " due {due}")?;
+        }
+        if !template.subtasks.is_empty() {
+            write!(line, " ({} subtasks)", template.subtasks.len())?;
+        }
+        info!("{line}");
+    }
+    for (name, template) in &cfg.project_templates {
+        let mut line = format!(
+            "{} project",
+            name.if_supports_color(Stream::Stdout, |text| text.bright_yellow())
+        );
+        if let Some(project) = &template.name {
+            write!(line, " {project}")?;
+        }
+        info!(
+            "{line} ({} sections, {} tasks)",
+            template.sections.len(),
+            template.tasks.len()
+        );
+    }
+    Ok(())
+}
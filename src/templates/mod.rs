@@ -0,0 +1,2 @@
+//! Works with the task templates defined in the [`crate::config::Config`].
+pub mod list;
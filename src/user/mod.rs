@@ -0,0 +1,3 @@
+//! Works with the [`crate::api::rest::User`] the API token belongs to.
+pub mod sync;
+pub mod whoami;
@@ -0,0 +1,30 @@
+use crate::{
+    api::rest::{Gateway, TokenRejected},
+    output::info,
+};
+use color_eyre::{Result, eyre::eyre};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {}
+
+/// Fetches projects, sections and labels in one go, which refreshes the disk cache and doubles as
+/// a check that the API token still works.
+pub async fn sync(_params: Params, gw: &Gateway) -> Result<()> {
+    // Anything cached would hide an expired token, so everything is fetched from the API.
+    gw.refresh();
+    let (projects, sections, labels) = tokio::try_join!(gw.projects(), gw.sections(), gw.labels())
+        .map_err(|err| {
+            if err.chain().any(|e| e.is::<TokenRejected>()) {
+                eyre!("token invalid or expired, set up a new one with `doist auth <TOKEN>`")
+            } else {
+                err
+            }
+        })?;
+    info!(
+        "Synced {} projects, {} sections and {} labels",
+        projects.len(),
+        sections.len(),
+        labels.len()
+    );
+    Ok(())
+}
@@ -0,0 +1,19 @@
+use crate::{api::rest::Gateway, output::info, tasks::list::Format};
+use color_eyre::Result;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Output format. JSON prints the user as returned by the API.
+    #[arg(long = "format", value_enum, default_value_t = Format::Table)]
+    format: Format,
+}
+
+/// Shows the account that the configured API token belongs to.
+pub async fn whoami(params: Params, gw: &Gateway) -> Result<()> {
+    let user = gw.user().await?;
+    match params.format {
+        Format::Table => info!("{user}"),
+        Format::Json => info!("{}", serde_json::to_string_pretty(&user)?),
+    }
+    Ok(())
+}
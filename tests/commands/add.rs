@@ -0,0 +1,571 @@
+use super::mocks::{self, TaskJson};
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::{Mock, ResponseTemplate, matchers};
+
+async fn mock_metadata(cmd: &Tool) {
+    mocks::mock_labels(cmd, 1).await;
+    mocks::mock_projects(cmd, 1).await;
+    mocks::mock_sections(cmd, 1).await;
+}
+
+#[tokio::test]
+async fn multiple_labels() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mock_metadata(&cmd).await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/tasks"))
+        .and(matchers::body_partial_json(serde_json::json!({
+            "content": "Party hard",
+            "labels": ["one", "two"],
+        })))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(
+                TaskJson::new("7000009", "Party hard")
+                    .labels(&["one", "two"])
+                    .build(),
+            ),
+        )
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    let mut command = cmd.cmd()?;
+    command
+        .args(["add", "Party hard", "-L", "one", "-L", "@two", "-L", "one"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Party hard"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn note() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mock_metadata(&cmd).await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/tasks"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(TaskJson::new("7000009", "Party hard").build()),
+        )
+        .mount(&cmd.mock)
+        .await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/comments"))
+        .and(matchers::body_partial_json(serde_json::json!({
+            "task_id": "7000009",
+            "content": "Bring snacks",
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "9000001",
+            "task_id": "7000009",
+            "posted_at": "2022-08-26T19:33:20Z",
+            "content": "Bring snacks"
+        })))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    let mut command = cmd.cmd()?;
+    command
+        .args(["add", "Party hard", "--note", "Bring snacks"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("created task").and(predicate::str::contains("added note")),
+        );
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn note_failure_keeps_task() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mock_metadata(&cmd).await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/tasks"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(TaskJson::new("7000009", "Party hard").build()),
+        )
+        .mount(&cmd.mock)
+        .await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/comments"))
+        .respond_with(ResponseTemplate::new(400))
+        .mount(&cmd.mock)
+        .await;
+
+    let mut command = cmd.cmd()?;
+    command
+        .args(["add", "Party hard", "--note", "Bring snacks"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("created task"))
+        .stderr(predicate::str::contains(
+            "task 7000009 was created, but adding the note failed",
+        ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn labels_fetched_once() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/tasks"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(
+                TaskJson::new("7000009", "Party hard")
+                    .labels(&["one"])
+                    .build(),
+            ),
+        )
+        .mount(&cmd.mock)
+        .await;
+
+    let mut command = cmd.cmd()?;
+    command
+        .args(["add", "Party hard", "-L", "one"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("@one"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn dry_run_shows_everything() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.templates.insert(
+        "weekly-review".to_string(),
+        doist::config::TaskTemplate {
+            content: Some("Weekly review".to_string()),
+            subtasks: vec!["Clear inbox".to_string()],
+            ..Default::default()
+        },
+    );
+    cmd.cfg.save()?;
+
+    mock_metadata(&cmd).await;
+    cmd.cmd()?
+        .args([
+            "--dry-run",
+            "add",
+            "--template",
+            "weekly-review",
+            "--note",
+            "Bring snacks",
+        ])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("would create task")
+                .and(predicate::str::contains("would create subtasks"))
+                .and(predicate::str::contains("Clear inbox"))
+                .and(predicate::str::contains("would add note"))
+                .and(predicate::str::contains("Bring snacks")),
+        );
+    let requests = cmd.mock.received_requests().await.unwrap_or_default();
+    assert!(
+        requests.iter().all(|req| req.method.as_str() == "GET"),
+        "unexpected requests: {requests:?}"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn template() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.templates.insert(
+        "weekly-review".to_string(),
+        doist::config::TaskTemplate {
+            content: Some("Weekly review".to_string()),
+            labels: vec!["one".to_string()],
+            priority: Some(2),
+            project: Some("Project One".to_string()),
+            due: Some("friday".to_string()),
+            subtasks: vec!["Clear inbox".to_string()],
+            ..Default::default()
+        },
+    );
+    cmd.cfg.save()?;
+
+    mock_metadata(&cmd).await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/tasks"))
+        .and(matchers::body_partial_json(serde_json::json!({
+            "content": "Weekly review of Q3",
+            "labels": ["two"],
+            "priority": 3,
+            "project_id": "1000002",
+            "due_string": "friday",
+        })))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(
+                TaskJson::new("7000009", "Weekly review of Q3")
+                    .labels(&["two"])
+                    .build(),
+            ),
+        )
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/tasks"))
+        .and(matchers::body_partial_json(serde_json::json!({
+            "content": "Clear inbox",
+            "parent_id": "7000009",
+        })))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(TaskJson::new("7000010", "Clear inbox").build()),
+        )
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["add", "--template", "weekly-review", "of Q3", "-L", "two"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("Weekly review of Q3")
+                .and(predicate::str::contains("Clear inbox")),
+        );
+    cmd.mock.verify().await;
+
+    cmd.cmd()?
+        .args(["template", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "weekly-review Weekly review p2 @one [Project One] due friday (1 subtasks)",
+        ));
+
+    // Flags win over the fields of the template.
+    cmd.mock.reset().await;
+    mock_metadata(&cmd).await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/tasks"))
+        .and(matchers::body_partial_json(serde_json::json!({
+            "content": "Weekly review",
+            "labels": ["one"],
+            "priority": 4,
+            "due_string": "tomorrow",
+        })))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(
+                TaskJson::new("7000009", "Weekly review")
+                    .labels(&["one"])
+                    .build(),
+            ),
+        )
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/tasks"))
+        .and(matchers::body_partial_json(serde_json::json!({
+            "parent_id": "7000009",
+        })))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(TaskJson::new("7000010", "Clear inbox").build()),
+        )
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    cmd.cmd()?
+        .args([
+            "add",
+            "--template",
+            "weekly-review",
+            "-p",
+            "p1",
+            "--due",
+            "tomorrow",
+        ])
+        .assert()
+        .success();
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn assignee() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_shared_projects(&cmd, 1).await;
+    mocks::mock_collaborators(&cmd, &[("111", "Alice Doe"), ("222", "Bob Roe")], 1).await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/tasks"))
+        .and(matchers::body_partial_json(serde_json::json!({
+            "content": "Review",
+            "project_id": "1000002",
+            "assignee_id": "222",
+        })))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(TaskJson::new("7000009", "Review").build()),
+        )
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["add", "Review", "-P", "Project One", "--assignee", "bob"])
+        .assert()
+        .success();
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn assignee_needs_shared_project() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mock_metadata(&cmd).await;
+
+    cmd.cmd()?
+        .args(["add", "Review", "-P", "Project Two", "--assignee", "me"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is not shared"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn parent() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mock_metadata(&cmd).await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/tasks/7000001"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(TaskJson::new("7000001", "Plan party").build()),
+        )
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/tasks"))
+        .and(matchers::body_partial_json(serde_json::json!({
+            "content": "Buy balloons",
+            "parent_id": "7000001",
+        })))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(TaskJson::new("7000009", "Buy balloons").build()),
+        )
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    let mut command = cmd.cmd()?;
+    command
+        .args([
+            "add",
+            "Buy balloons",
+            "--parent",
+            "7000001",
+            "-P",
+            "Project Two",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Buy balloons"))
+        .stderr(predicate::str::contains(
+            "using the ones of the parent task",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn due_datetime() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.timezone = Some(chrono_tz::Asia::Seoul);
+    cmd.cfg.save()?;
+
+    mock_metadata(&cmd).await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/tasks"))
+        .and(matchers::body_partial_json(serde_json::json!({
+            "content": "Open presents",
+            "due_datetime": "2025-12-25T05:00:00Z",
+        })))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(TaskJson::new("7000009", "Open presents").build()),
+        )
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["add", "Open presents", "--due-datetime", "2025-12-25T14:00"])
+        .assert()
+        .success();
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn quiet() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mock_metadata(&cmd).await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/tasks"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(TaskJson::new("7000009", "Party hard").build()),
+        )
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["add", "Party hard", "--quiet"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn default_project() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.default_project = Some("Project One".to_string());
+    cmd.cfg.save()?;
+    mock_metadata(&cmd).await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/tasks"))
+        .and(matchers::body_partial_json(serde_json::json!({
+            "content": "Party hard",
+            "project_id": "1000002",
+        })))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(TaskJson::new("7000009", "Party hard").build()),
+        )
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    cmd.cmd()?.args(["add", "Party hard"]).assert().success();
+    cmd.mock.verify().await;
+
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.default_project = Some("Deleted project".to_string());
+    cmd.cfg.save()?;
+    mock_metadata(&cmd).await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/tasks"))
+        .and(|req: &wiremock::Request| {
+            let body: serde_json::Value = serde_json::from_slice(&req.body).unwrap();
+            body.get("project_id").is_none()
+        })
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(TaskJson::new("7000009", "Party hard").build()),
+        )
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    cmd.cmd()?
+        .args(["add", "Party hard"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "default project 'Deleted project' was not found",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn default_labels() -> Result<()> {
+    let labels_are = |expected: &'static [&'static str]| {
+        move |req: &wiremock::Request| {
+            let body: serde_json::Value = serde_json::from_slice(&req.body).unwrap();
+            body.get("labels").cloned().unwrap_or_default() == serde_json::json!(expected)
+        }
+    };
+
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.default_labels = vec!["two".to_string(), "missing".to_string()];
+    cmd.cfg.save()?;
+    mock_metadata(&cmd).await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/tasks"))
+        .and(labels_are(&["one", "two"]))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(
+                TaskJson::new("7000009", "Party hard")
+                    .labels(&["one", "two"])
+                    .build(),
+            ),
+        )
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    cmd.cmd()?
+        .args(["add", "Party hard", "-L", "one", "-L", "two"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "default label 'missing' was not found",
+        ));
+    cmd.mock.verify().await;
+
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.default_labels = vec!["two".to_string()];
+    cmd.cfg.save()?;
+    mock_metadata(&cmd).await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/tasks"))
+        .and(labels_are(&["one"]))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(
+                TaskJson::new("7000009", "Party hard")
+                    .labels(&["one"])
+                    .build(),
+            ),
+        )
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    cmd.cmd()?
+        .args(["add", "Party hard", "-L", "one", "--no-default-labels"])
+        .assert()
+        .success();
+    cmd.mock.verify().await;
+
+    Ok(())
+}
@@ -1,8 +1,11 @@
+use super::setup::Tool;
 use assert_cmd::prelude::*;
 use assert_fs::prelude::*;
 use color_eyre::Result;
 use doist::config::Config;
+use predicates::prelude::*;
 use std::process::Command;
+use wiremock::{Mock, ResponseTemplate, matchers};
 
 #[cfg(not(windows))]
 #[test]
@@ -21,3 +24,108 @@ fn authentication() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn config_file() -> Result<()> {
+    let tmp = assert_fs::TempDir::new()?;
+    let file = tmp.child("custom.toml");
+    let mut cmd = Command::cargo_bin("doist")?;
+    cmd.arg("list")
+        .arg("--config")
+        .arg(file.path())
+        .assert()
+        .failure();
+    let mut cmd = Command::cargo_bin("doist")?;
+    cmd.arg("auth")
+        .arg("AUTH_KEY")
+        .env("DOIST_CONFIG", file.path())
+        .assert()
+        .success();
+    file.assert(predicates::str::contains("AUTH_KEY"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn token_precedence() -> Result<()> {
+    let cmd = Tool::init().await?;
+    for token in ["AUTH_KEY", "ENV_KEY", "FLAG_KEY"] {
+        Mock::given(matchers::method("GET"))
+            .and(matchers::path("/api/v1/user"))
+            .and(matchers::bearer_token(token))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "100",
+                "full_name": token,
+                "email": "me@example.com"
+            })))
+            .expect(1)
+            .mount(&cmd.mock)
+            .await;
+    }
+
+    cmd.cmd()?
+        .arg("whoami")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("AUTH_KEY"));
+    cmd.cmd()?
+        .arg("whoami")
+        .env("DOIST_TOKEN", "ENV_KEY")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("ENV_KEY"));
+    cmd.cmd()?
+        .args(["whoami", "--token", "FLAG_KEY"])
+        .env("DOIST_TOKEN", "ENV_KEY")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("FLAG_KEY"));
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn login_stdin() -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = assert_fs::TempDir::new()?;
+    let file = tmp.child("config.toml");
+    assert_cmd::Command::cargo_bin("doist")?
+        .args(["login", "--stdin"])
+        .env("DOIST_CONFIG", file.path())
+        .write_stdin("STDIN_KEY\n")
+        .assert()
+        .success();
+    let cfg: Config = toml::from_str(&std::fs::read_to_string(&file)?)?;
+    assert_eq!(cfg.token.unwrap(), "STDIN_KEY");
+    let mode = std::fs::metadata(&file)?.permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn login_rejects_empty_token() -> Result<()> {
+    let tmp = assert_fs::TempDir::new()?;
+    let file = tmp.child("config.toml");
+    for stdin in ["", "  \n\t\n"] {
+        assert_cmd::Command::cargo_bin("doist")?
+            .args(["login", "--stdin"])
+            .env("DOIST_CONFIG", file.path())
+            .write_stdin(stdin)
+            .assert()
+            .failure()
+            .stdout(predicates::str::contains("Token successfully saved").not());
+    }
+    assert_cmd::Command::cargo_bin("doist")?
+        .args(["auth", " "])
+        .env("DOIST_CONFIG", file.path())
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("the token must not be empty"));
+    file.assert(predicates::path::missing());
+
+    Ok(())
+}
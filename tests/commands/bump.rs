@@ -0,0 +1,44 @@
+use super::mocks::TaskJson;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::{Mock, ResponseTemplate, matchers};
+
+#[tokio::test]
+async fn fetches_only_the_project() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    let tasks = [("123", 1), ("456", 2)]
+        .map(|(id, order)| {
+            let mut task = TaskJson::new(id, &format!("Task {id}")).build();
+            task["order"] = order.into();
+            task
+        })
+        .to_vec();
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/tasks/456"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&tasks[1]))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/tasks"))
+        .and(matchers::query_param("project_id", "1000002"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "results": tasks,
+            "next_cursor": null
+        })))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["--dry-run", "bump", "456"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("dry run, would reorder tasks"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
@@ -0,0 +1,340 @@
+use super::mocks::{self, TaskJson};
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::{Mock, ResponseTemplate, matchers};
+
+/// Mocks the tasks of Project One, which are checked for open subtasks before closing, and
+/// makes each of them available on its own.
+async fn mock_project_tasks(cmd: &Tool, tasks: &[serde_json::Value]) {
+    for task in tasks {
+        Mock::given(matchers::method("GET"))
+            .and(matchers::path(format!(
+                "/api/v1/tasks/{}",
+                task["id"].as_str().unwrap()
+            )))
+            .respond_with(ResponseTemplate::new(200).set_body_json(task))
+            .mount(&cmd.mock)
+            .await;
+    }
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/tasks"))
+        .and(matchers::query_param("project_id", "1000002"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "results": tasks,
+            "next_cursor": null
+        })))
+        .mount(&cmd.mock)
+        .await;
+}
+
+/// Returns plain tasks without subtasks.
+fn tasks(ids: &[&str]) -> Vec<serde_json::Value> {
+    ids.iter()
+        .map(|id| TaskJson::new(id, &format!("Task {id}")).build())
+        .collect()
+}
+
+#[tokio::test]
+async fn close_multiple() -> Result<()> {
+    let cmd = Tool::init().await?;
+    mock_project_tasks(&cmd, &tasks(&["123", "456"])).await;
+
+    for id in ["123", "456"] {
+        Mock::given(matchers::method("POST"))
+            .and(matchers::path(format!("/api/v1/tasks/{id}/close")))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&cmd.mock)
+            .await;
+    }
+
+    cmd.cmd()?
+        .args(["close", "123", "456"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("closed task 123")
+                .and(predicate::str::contains("closed task 456"))
+                .and(predicate::str::contains("closed 2 of 2 tasks")),
+        );
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn close_multiple_keeps_going() -> Result<()> {
+    let cmd = Tool::init().await?;
+    mock_project_tasks(&cmd, &tasks(&["123", "456"])).await;
+
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/tasks/123/close"))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/tasks/456/close"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["close", "123", "456"])
+        .assert()
+        .failure()
+        .stdout(
+            predicate::str::contains("closed task 456")
+                .and(predicate::str::contains("closed 1 of 2 tasks")),
+        )
+        .stderr(predicate::str::contains("failed to close task 123"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn project_requires_next() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    cmd.cmd()?
+        .args(["close", "123", "-P", "Project Four"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--next"));
+    let requests = cmd.mock.received_requests().await.unwrap_or_default();
+    assert!(requests.is_empty(), "unexpected requests: {requests:?}");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn close_next_in_project() -> Result<()> {
+    let cmd = Tool::init().await?;
+    mock_project_tasks(&cmd, &tasks(&["123", "456"])).await;
+
+    for id in ["123", "456"] {
+        Mock::given(matchers::method("POST"))
+            .and(matchers::path(format!("/api/v1/tasks/{id}/close")))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&cmd.mock)
+            .await;
+    }
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/tasks/filter"))
+        .and(matchers::query_param("query", "(today | overdue)"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(super::fixtures::TASKS.to_string()),
+        )
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["close", "123", "456", "--next", "-P", "Project Four"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("closed 2 of 2 tasks").and(predicate::str::contains(
+                "next task: 7000016 p4 Get some data",
+            )),
+        );
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn dry_run() -> Result<()> {
+    let cmd = Tool::init().await?;
+    mock_project_tasks(&cmd, &tasks(&["123", "456"])).await;
+
+    cmd.cmd()?
+        .args(["close", "123", "456", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "dry run, would close tasks: [\n  \"123\",\n  \"456\"\n]",
+        ));
+    let requests = cmd.mock.received_requests().await.unwrap_or_default();
+    assert!(
+        requests.iter().all(|req| req.method.as_str() == "GET"),
+        "unexpected requests: {requests:?}"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn complete_keep_recurring() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    let task = |id: &str, recurring: bool| {
+        TaskJson::new(id, "Water plants")
+            .due("every day", "2022-08-27", recurring)
+            .build()
+    };
+    mock_project_tasks(&cmd, &[task("123", true), task("456", false)]).await;
+    // The recurring task is only closed, so it keeps its schedule.
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/tasks/123/close"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(2)
+        .mount(&cmd.mock)
+        .await;
+    // The other one is completed like without --keep-recurring.
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/sync"))
+        .and(matchers::body_string_contains("456"))
+        .respond_with(|req: &wiremock::Request| {
+            let commands = url::form_urlencoded::parse(&req.body)
+                .find(|(key, _)| key == "commands")
+                .map(|(_, value)| serde_json::from_str::<Vec<serde_json::Value>>(&value).unwrap())
+                .unwrap();
+            let status = commands
+                .iter()
+                .map(|c| (c["uuid"].as_str().unwrap().to_string(), "ok".into()))
+                .collect::<serde_json::Map<_, _>>();
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({"sync_status": status}))
+        })
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["close", "--complete", "--keep-recurring", "123", "456"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("completed 2 of 2 tasks"));
+    cmd.cmd()?
+        .args(["close", "--complete", "--keep-recurring", "123"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("closed task")
+                .and(predicate::str::contains("next due date: 2022-08-27")),
+        );
+    cmd.mock.verify().await;
+    // Checking for subtasks already fetched the tasks, so they aren't fetched again.
+    let requests = cmd.mock.received_requests().await.unwrap_or_default();
+    assert_eq!(
+        requests
+            .iter()
+            .filter(|r| r.method.as_str() == "GET" && r.url.path() == "/api/v1/tasks/456")
+            .count(),
+        1
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn open_subtasks() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    let tasks = [
+        TaskJson::new("123", "Task 123").build(),
+        TaskJson::new("124", "Task 124").parent("123").build(),
+        TaskJson::new("125", "Task 125").parent("124").build(),
+    ];
+    let mock_close = || async {
+        mock_project_tasks(&cmd, &tasks).await;
+        Mock::given(matchers::method("POST"))
+            .and(matchers::path_regex(r"^/api/v1/tasks/\d+/close$"))
+            .respond_with(ResponseTemplate::new(204))
+            .mount(&cmd.mock)
+            .await;
+    };
+    mock_close().await;
+    let closed = || async {
+        cmd.mock
+            .received_requests()
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|req| req.method.as_str() == "POST")
+            .map(|req| req.url.path().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    // Without a terminal to confirm on, nothing is closed.
+    cmd.cmd()?
+        .args(["close", "123"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "2 open subtasks would be left behind, use --force",
+        ));
+    assert!(closed().await.is_empty());
+
+    cmd.cmd()?
+        .args(["close", "123", "--recursive", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "dry run, would close subtasks: [\n  \"125\",\n  \"124\"\n]",
+        ));
+    assert!(closed().await.is_empty());
+
+    cmd.cmd()?
+        .args(["close", "123", "--recursive"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("closed subtask").and(predicate::str::contains("closed task")),
+        );
+    assert_eq!(
+        closed().await,
+        vec![
+            "/api/v1/tasks/125/close",
+            "/api/v1/tasks/124/close",
+            "/api/v1/tasks/123/close"
+        ]
+    );
+
+    // Forcing it closes the task right away, leaving the subtasks alone.
+    cmd.mock.reset().await;
+    mock_close().await;
+    cmd.cmd()?
+        .args(["close", "123", "--force"])
+        .assert()
+        .success();
+    assert_eq!(closed().await, vec!["/api/v1/tasks/123/close"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn recurring_parent() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    // Closing moves the task on to its next due date, so the subtasks aren't left behind.
+    mock_project_tasks(
+        &cmd,
+        &[
+            TaskJson::new("123", "Weekly review")
+                .due("every friday", "2022-08-26", true)
+                .build(),
+            TaskJson::new("124", "Clear inbox").parent("123").build(),
+        ],
+    )
+    .await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/tasks/123/close"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?.args(["close", "123"]).assert().success();
+    cmd.mock.verify().await;
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::{Mock, ResponseTemplate, matchers};
+
+async fn mock_create_comment(cmd: &Tool, body: serde_json::Value) {
+    let mut response = body.clone();
+    response["id"] = "999".into();
+    response["posted_at"] = "2025-10-01T10:00:00Z".into();
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/comments"))
+        .and(matchers::body_json(body))
+        .respond_with(ResponseTemplate::new(200).set_body_json(response))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+}
+
+#[tokio::test]
+async fn task_comment_from_stdin() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mock_create_comment(
+        &cmd,
+        serde_json::json!({"task_id": "123", "content": "# Notes\n\n- first\n- second"}),
+    )
+    .await;
+
+    let mut comment = cmd.cmd()?;
+    comment.args(["comment", "--task", "123", "-"]);
+    assert_cmd::Command::from_std(comment)
+        .write_stdin("# Notes\n\n- first\n- second\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ID: 999"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn project_comment() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_projects(&cmd, 1).await;
+    mock_create_comment(
+        &cmd,
+        serde_json::json!({"project_id": "1000002", "content": "hello"}),
+    )
+    .await;
+
+    cmd.cmd()?
+        .args(["comment", "-P", "Project One", "hello"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ID: 999"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use super::mocks::{self, TaskJson};
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::{Mock, ResponseTemplate, matchers};
+
+#[tokio::test]
+async fn task_comments_sorted() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_projects(&cmd, 1).await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/tasks/123"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(TaskJson::new("123", "Review").build()),
+        )
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/comments"))
+        .and(matchers::query_param("task_id", "123"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "results": [
+                {
+                    "id": "2",
+                    "task_id": "123",
+                    "posted_at": "2025-10-02T10:00:00Z",
+                    "content": "second"
+                },
+                {
+                    "id": "1",
+                    "task_id": "123",
+                    "posted_at": "2025-10-01T10:00:00Z",
+                    "content": "first"
+                }
+            ],
+            "next_cursor": null
+        })))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["comments", "--task", "123"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(
+            "(?s)1 2025-10-01 10:00\n  first.*2 2025-10-02 10:00\n  second",
+        )?);
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn project_without_comments() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_projects(&cmd, 1).await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/comments"))
+        .and(matchers::query_param("project_id", "1000002"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "results": [],
+            "next_cursor": null
+        })))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["comments", "--project_id", "1000002"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No comments"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn shared_project_posters() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_shared_projects(&cmd, 1).await;
+    mocks::mock_collaborators(&cmd, &[("111", "Alice Doe")], 1).await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/comments"))
+        .and(matchers::query_param("project_id", "1000002"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "results": [{
+                "id": "1",
+                "project_id": "1000002",
+                "posted_uid": "111",
+                "posted_at": "2025-10-01T10:00:00Z",
+                "content": "kickoff"
+            }],
+            "next_cursor": null
+        })))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["comments", "-P", "Project One"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Alice Doe").and(predicate::str::contains("kickoff")));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
@@ -0,0 +1,216 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::{Mock, ResponseTemplate, matchers};
+
+#[tokio::test]
+async fn page_hint_on_stderr() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    let tasks: serde_json::Value = serde_json::from_str(super::fixtures::TASKS)?;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/tasks/completed/by_completion_date"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "items": tasks["results"].as_array().unwrap()[..2],
+            "next_cursor": "next"
+        })))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .arg("completed")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("Bla bla").and(predicate::str::contains("Showing page").not()),
+        )
+        .stderr(predicate::str::contains("Showing page 1 (2 tasks)"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn page_hint_silenced() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 2).await;
+    mocks::mock_sections(&cmd, 2).await;
+    let tasks: serde_json::Value = serde_json::from_str(super::fixtures::TASKS)?;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/tasks/completed/by_completion_date"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "items": tasks["results"].as_array().unwrap()[..2],
+            "next_cursor": "next"
+        })))
+        .expect(2)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["completed", "--format", "json"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Showing page").not());
+    cmd.cmd()?
+        .args(["--quiet", "completed"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Showing page").not());
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn output_json() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    let tasks: serde_json::Value = serde_json::from_str(super::fixtures::TASKS)?;
+    let tasks = tasks["results"].as_array().unwrap();
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/tasks/completed/by_completion_date"))
+        .and(matchers::query_param_is_missing("cursor"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "items": tasks[..2],
+            "next_cursor": "next"
+        })))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/tasks/completed/by_completion_date"))
+        .and(matchers::query_param("cursor", "next"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "items": tasks[2..3],
+            "next_cursor": null
+        })))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    let output = cmd
+        .cmd()?
+        .args(["completed", "--all", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Total").not())
+        .get_output()
+        .stdout
+        .clone();
+    let json: Vec<serde_json::Value> = serde_json::from_slice(&output)?;
+    let ids = json.iter().map(|t| t["id"].clone()).collect::<Vec<_>>();
+    assert_eq!(
+        ids,
+        tasks[..3]
+            .iter()
+            .map(|t| t["id"].clone())
+            .collect::<Vec<_>>()
+    );
+    for (task, fixture) in json.iter().zip(tasks) {
+        assert_eq!(task["project_id"], fixture["project_id"]);
+        assert!(task.get("completed_at").is_some());
+        assert!(task["project_name"].is_string());
+    }
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn now_sets_date_range() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/tasks/completed/by_completion_date"))
+        .and(matchers::query_param("since", "2025-10-06T00:00:00Z"))
+        .and(matchers::query_param("until", "2025-10-08T23:59:59Z"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "items": [],
+            "next_cursor": null
+        })))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args([
+            "completed",
+            "--this-week",
+            "--tz",
+            "UTC",
+            "--now",
+            "2025-10-08T12:00:00Z",
+        ])
+        .assert()
+        .success();
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn uses_account_timezone() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/user"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "42",
+            "full_name": "Alice Doe",
+            "email": "alice@example.com",
+            "tz_info": {"timezone": "Asia/Seoul"}
+        })))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/tasks/completed/by_completion_date"))
+        .and(matchers::query_param("since", "2025-10-05T15:00:00Z"))
+        .and(matchers::query_param("until", "2025-10-06T14:59:59Z"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "items": [],
+            "next_cursor": null
+        })))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["completed", "--today", "--now", "2025-10-05T15:30:00Z"])
+        .assert()
+        .success();
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn stats_rejected_with_json() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    cmd.cmd()?
+        .args(["completed", "--stats", "--format", "json"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+    let requests = cmd.mock.received_requests().await.unwrap_or_default();
+    assert!(requests.is_empty(), "unexpected requests: {requests:?}");
+
+    Ok(())
+}
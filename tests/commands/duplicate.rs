@@ -0,0 +1,58 @@
+use super::mocks::{self, TaskJson};
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::{Mock, ResponseTemplate, matchers};
+
+fn task(id: &str, content: &str) -> serde_json::Value {
+    TaskJson::new(id, content)
+        .labels(&["one", "two"])
+        .priority(3)
+        .build()
+}
+
+#[tokio::test]
+async fn copies_labels_and_priority() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/tasks/7000001"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(task("7000001", "Water plants")))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/tasks"))
+        .and(matchers::body_partial_json(serde_json::json!({
+            "content": "Water cactus",
+            "project_id": "1000002",
+            "labels": ["one", "two"],
+            "priority": 3,
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(task("7000009", "Water cactus")))
+        .expect(2)
+        .mount(&cmd.mock)
+        .await;
+
+    let mut command = cmd.cmd()?;
+    command
+        .args([
+            "duplicate",
+            "7000001",
+            "--count",
+            "2",
+            "--content",
+            "Water cactus",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Water cactus"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
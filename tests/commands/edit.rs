@@ -0,0 +1,250 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::{Mock, ResponseTemplate, matchers};
+
+#[tokio::test]
+async fn priority_bump_multiple() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_labels(&cmd, 1).await;
+    let tasks: serde_json::Value = serde_json::from_str(super::fixtures::TASKS)?;
+    let tasks = tasks["results"].as_array().unwrap();
+    // 7000001 has the lowest priority, 7000004 is one level above.
+    for (task, priority) in [(&tasks[0], 2), (&tasks[3], 3)] {
+        let id = task["id"].as_str().unwrap();
+        Mock::given(matchers::method("GET"))
+            .and(matchers::path(format!("/api/v1/tasks/{id}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(task))
+            .expect(1)
+            .mount(&cmd.mock)
+            .await;
+        Mock::given(matchers::method("POST"))
+            .and(matchers::path(format!("/api/v1/tasks/{id}")))
+            .and(matchers::body_partial_json(
+                serde_json::json!({ "priority": priority }),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(task))
+            .expect(1)
+            .mount(&cmd.mock)
+            .await;
+    }
+
+    cmd.cmd()?
+        .args(["edit", "7000001", "7000004", "--priority-bump"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("task 7000001: p4 -> p3")
+                .and(predicate::str::contains("task 7000004: p3 -> p2")),
+        );
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn clears_fields() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_labels(&cmd, 1).await;
+    let tasks: serde_json::Value = serde_json::from_str(super::fixtures::TASKS)?;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/tasks/123"))
+        .and(matchers::body_json(serde_json::json!({
+            "due_string": "no date",
+            "deadline_date": null,
+            "duration": null,
+            "duration_unit": null
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&tasks["results"][0]))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["edit", "123", "--no-due", "--no-deadline", "--no-duration"])
+        .assert()
+        .success();
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn duration_uses_existing_due() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_labels(&cmd, 1).await;
+    let task = mocks::TaskJson::new("123", "Dated")
+        .due("tomorrow", "2025-10-11", false)
+        .build();
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/tasks/123"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&task))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/tasks/123"))
+        .and(matchers::body_json(serde_json::json!({
+            "duration": 30,
+            "duration_unit": "minute"
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&task))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["edit", "123", "--duration", "30m"])
+        .assert()
+        .success();
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn due_datetime() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.timezone = Some(chrono_tz::Asia::Seoul);
+    cmd.cfg.save()?;
+
+    mocks::mock_labels(&cmd, 1).await;
+    let tasks: serde_json::Value = serde_json::from_str(super::fixtures::TASKS)?;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/tasks/123"))
+        .and(matchers::body_json(serde_json::json!({
+            "due_datetime": "2025-12-25T05:00:00Z"
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&tasks["results"][0]))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["edit", "123", "--due-datetime", "2025-12-25 14:00"])
+        .assert()
+        .success();
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn dry_run() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_labels(&cmd, 1).await;
+
+    cmd.cmd()?
+        .args(["--dry-run", "edit", "123", "--name", "Renamed"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "dry run, would update task 123: {\n  \"content\": \"Renamed\"\n}",
+        ));
+    let requests = cmd.mock.received_requests().await.unwrap_or_default();
+    assert!(
+        requests.iter().all(|r| r.method.as_str() == "GET"),
+        "unexpected requests: {requests:?}"
+    );
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn filter_edits_all_matches() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_labels(&cmd, 1).await;
+    let tasks: serde_json::Value = serde_json::from_str(super::fixtures::TASKS)?;
+    let matching = &tasks["results"].as_array().unwrap()[..3];
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/tasks/filter"))
+        .and(matchers::query_param("query", "p4 & overdue"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "results": matching,
+            "next_cursor": null
+        })))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    for task in matching {
+        Mock::given(matchers::method("POST"))
+            .and(matchers::path(format!(
+                "/api/v1/tasks/{}",
+                task["id"].as_str().unwrap()
+            )))
+            .and(matchers::body_json(serde_json::json!({ "priority": 3 })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(task))
+            .expect(1)
+            .mount(&cmd.mock)
+            .await;
+    }
+
+    cmd.cmd()?
+        .args([
+            "edit",
+            "--filter",
+            "p4 & overdue",
+            "--all-matching",
+            "--priority",
+            "2",
+            "--yes",
+        ])
+        .assert()
+        .success();
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn filter_requires_confirmation() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_tasks(&cmd, 1).await;
+
+    // Without a terminal there is nobody to confirm, so nothing gets edited.
+    cmd.cmd()?
+        .args([
+            "edit",
+            "--filter",
+            "all",
+            "--all-matching",
+            "--priority",
+            "2",
+        ])
+        .stdin(std::process::Stdio::null())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("use --yes"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn all_matching_requires_changes() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_labels(&cmd, 1).await;
+    cmd.cmd()?
+        .args(["edit", "--filter", "all", "--all-matching", "--yes"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no changes given"));
+    let requests = cmd.mock.received_requests().await.unwrap_or_default();
+    assert!(
+        requests.iter().all(|req| req.method.as_str() == "GET"),
+        "unexpected requests: {requests:?}"
+    );
+
+    Ok(())
+}
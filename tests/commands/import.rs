@@ -0,0 +1,87 @@
+use super::mocks::{self, TaskJson};
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::{Mock, ResponseTemplate, matchers};
+
+const TASKS: &str = r#"[
+    {"content": "Buy milk", "priority": "p1", "project": "Project One", "labels": ["one"]},
+    {"content": "Call mom", "due": "tomorrow"}
+]"#;
+
+async fn mock_create(cmd: &Tool, id: &str, body: serde_json::Value) {
+    let content = body["content"].as_str().unwrap().to_string();
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/tasks"))
+        .and(matchers::body_json(body))
+        .respond_with(ResponseTemplate::new(200).set_body_json(TaskJson::new(id, &content).build()))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+}
+
+#[tokio::test]
+async fn import() -> Result<()> {
+    let cmd = Tool::init().await?;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mock_create(
+        &cmd,
+        "7000009",
+        serde_json::json!({
+            "content": "Buy milk",
+            "project_id": "1000002",
+            "labels": ["one"],
+            "priority": 4
+        }),
+    )
+    .await;
+    mock_create(
+        &cmd,
+        "7000010",
+        serde_json::json!({"content": "Call mom", "due_string": "tomorrow"}),
+    )
+    .await;
+    let file = cmd.tmp.child("tasks.json");
+    file.write_str(TASKS)?;
+
+    cmd.cmd()?
+        .arg("import")
+        .arg(file.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Buy milk"))
+        .stdout(predicate::str::contains("Call mom"))
+        .stdout(predicate::str::contains("imported 2 of 2 tasks"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn dry_run() -> Result<()> {
+    let cmd = Tool::init().await?;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/tasks"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(0)
+        .mount(&cmd.mock)
+        .await;
+    let file = cmd.tmp.child("tasks.csv");
+    file.write_str("content,project\nBuy milk,Project One\nCall mom,\n")?;
+
+    cmd.cmd()?
+        .args(["--dry-run", "import"])
+        .arg(file.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("would create tasks"))
+        .stdout(predicate::str::contains(r#""project_id": "1000002""#));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
@@ -1,8 +1,9 @@
-use super::mocks;
+use super::mocks::{self, TaskJson};
 use super::setup::Tool;
 use assert_cmd::prelude::*;
 use color_eyre::Result;
 use predicates::prelude::*;
+use wiremock::{Mock, ResponseTemplate, matchers};
 
 #[tokio::test]
 async fn list() -> Result<()> {
@@ -29,3 +30,185 @@ async fn list() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn counts() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_labels(&cmd, 2).await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/tasks"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(super::fixtures::TASKS))
+        .expect(2)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["labels", "--counts"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "1999991 @one (0 tasks)\n1999992 @two (4 tasks)\n",
+        ));
+    cmd.cmd()?
+        .args(["labels", "--counts", "--sort", "count"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "1999992 @two (4 tasks)\n1999991 @one (0 tasks)\n",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+async fn mock_shared_labels(cmd: &Tool, names: &[&str]) {
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/labels/shared"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "results": names,
+            "next_cursor": null
+        })))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+}
+
+#[tokio::test]
+async fn prune() -> Result<()> {
+    let cmd = Tool::init().await?;
+    let task = TaskJson::new("7000001", "Water plants")
+        .labels(&["one", "gone"])
+        .build();
+
+    mocks::mock_labels(&cmd, 1).await;
+    mock_shared_labels(&cmd, &[]).await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/tasks"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "results": [task],
+            "next_cursor": null
+        })))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/tasks/7000001"))
+        .and(matchers::body_json(serde_json::json!({"labels": ["one"]})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&task))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["labels", "prune", "--fix"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("7000001 Water plants: @gone")
+                .and(predicate::str::contains("from 1 of 1 tasks")),
+        );
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn prune_keeps_shared_labels() -> Result<()> {
+    let cmd = Tool::init().await?;
+    let task = TaskJson::new("7000001", "Review plan")
+        .labels(&["team", "gone"])
+        .build();
+
+    mocks::mock_labels(&cmd, 1).await;
+    mock_shared_labels(&cmd, &["team"]).await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/tasks"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "results": [task],
+            "next_cursor": null
+        })))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/tasks/7000001"))
+        .and(matchers::body_json(serde_json::json!({"labels": ["team"]})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&task))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["labels", "prune", "--fix"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("7000001 Review plan: @gone")
+                .and(predicate::str::contains("@team").not()),
+        );
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn delete_requires_confirmation() -> Result<()> {
+    let cmd = Tool::init().await?;
+    mocks::mock_labels(&cmd, 1).await;
+    Mock::given(matchers::method("DELETE"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(0)
+        .mount(&cmd.mock)
+        .await;
+
+    // Without a terminal there is nobody to confirm, so nothing gets deleted.
+    cmd.cmd()?
+        .args(["labels", "delete", "-L", "one"])
+        .stdin(std::process::Stdio::null())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("use --force"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn dry_run() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    cmd.cmd()?
+        .args(["--dry-run", "labels", "add", "errand"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "dry run, would POST api/v1/labels: {\n  \"name\": \"errand\"",
+        ));
+    let requests = cmd.mock.received_requests().await.unwrap_or_default();
+    assert!(requests.is_empty(), "unexpected requests: {requests:?}");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn dry_run_delete() -> Result<()> {
+    let cmd = Tool::init().await?;
+    mocks::mock_labels(&cmd, 1).await;
+    Mock::given(matchers::method("DELETE"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(0)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["--dry-run", "labels", "delete", "-L", "one", "--force"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "dry run, would DELETE api/v1/labels/",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
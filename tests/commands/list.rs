@@ -1,8 +1,9 @@
-use super::mocks;
+use super::mocks::{self, TaskJson};
 use super::setup::Tool;
 use assert_cmd::prelude::*;
 use color_eyre::Result;
 use predicates::prelude::*;
+use wiremock::{Mock, ResponseTemplate, matchers};
 
 #[tokio::test]
 async fn list() -> Result<()> {
@@ -48,3 +49,283 @@ async fn expand() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn output_file() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    let path = cmd.tmp.path().join("tasks.txt");
+    let mut command = cmd.cmd()?;
+    command
+        .arg("list")
+        .arg("--output-file")
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+    assert_eq!(
+        std::fs::read_to_string(&path)?,
+        super::fixtures::TASK_OUTPUT
+    );
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn timed_only() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.timezone = Some(chrono_tz::UTC);
+    cmd.cfg.save()?;
+
+    let task = |id: &str, content: &str, date: &str| {
+        TaskJson::new(id, content).due(content, date, false).build()
+    };
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/tasks/filter"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "results": [
+                task("1", "Lunch", "2022-08-26T12:00:00Z"),
+                task("2", "Anytime", "2022-08-26"),
+                task("3", "Standup", "2022-08-26T09:30:00Z"),
+                task("4", "Review", "2022-08-27T14:00:00Z"),
+            ],
+            "next_cursor": null
+        })))
+        .mount(&cmd.mock)
+        .await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    let output = cmd.cmd()?.arg("list").arg("--timed-only").output()?;
+    assert!(output.status.success());
+    let lines = String::from_utf8(output.stdout)?
+        .lines()
+        .map(|l| l.split_once(" — ").map(|(time, _)| time.to_string()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        vec![
+            Some("09:30".to_string()),
+            Some("12:00".to_string()),
+            Some("08/27 14:00".to_string())
+        ]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn format_json() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    let mut command = cmd.cmd()?;
+    let output = command
+        .args(["list", "--format", "json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: Vec<serde_json::Value> = serde_json::from_slice(&output)?;
+    let tasks: Vec<doist::api::rest::Task> = serde_json::from_slice(&output)?;
+    assert_eq!(json.len(), tasks.len());
+    let impossible = tasks
+        .iter()
+        .position(|t| t.content == "Impossible")
+        .unwrap();
+    assert_eq!(json[impossible]["project_name"], "Project Three");
+    assert_eq!(json[impossible]["section_name"], "Section Five");
+    let subtasks: Vec<doist::api::rest::Task> =
+        serde_json::from_value(json[impossible]["subtasks"].clone())?;
+    assert_eq!(subtasks.len(), 1);
+    assert_eq!(subtasks[0].content, "Nope");
+    assert_eq!(subtasks[0].parent_id.as_ref(), Some(&tasks[impossible].id));
+    assert!(tasks.iter().all(|t| t.parent_id.is_none()));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn pages_with_cursor() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_labels(&cmd, 2).await;
+    mocks::mock_projects(&cmd, 2).await;
+    mocks::mock_sections(&cmd, 2).await;
+    let tasks: serde_json::Value = serde_json::from_str(super::fixtures::TASKS)?;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/tasks/filter"))
+        .and(matchers::query_param("query", "all"))
+        .and(matchers::query_param("cursor", "page2"))
+        .and(matchers::query_param("limit", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "results": tasks["results"].as_array().unwrap()[..2],
+            "next_cursor": "page3"
+        })))
+        .expect(2)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["list", "--limit", "2", "--cursor", "page2"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("page3").not())
+        .stderr(predicate::str::contains("--cursor page3"));
+    cmd.cmd()?
+        .args(["--quiet", "list", "--limit", "2", "--cursor", "page2"])
+        .assert()
+        .success()
+        .stderr(predicate::str::is_empty());
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn show_id() -> Result<()> {
+    for (args, shows_ids) in [
+        (vec!["list"], false),
+        (vec!["list", "--show-id"], true),
+        (vec!["list", "--group-by", "project"], false),
+        (vec!["list", "--group-by", "project", "--show-id"], true),
+    ] {
+        let cmd = Tool::init().await?;
+
+        mocks::mock_tasks(&cmd, 1).await;
+        mocks::mock_labels(&cmd, 1).await;
+        mocks::mock_projects(&cmd, 1).await;
+        mocks::mock_sections(&cmd, 1).await;
+
+        let id = predicate::str::contains("7000001");
+        let assert = cmd.cmd()?.args(&args).assert().success();
+        if shows_ids {
+            assert.stdout(id);
+        } else {
+            assert.stdout(id.not());
+        }
+        cmd.mock.verify().await;
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn assignee() -> Result<()> {
+    let mut tasks: serde_json::Value = serde_json::from_str(super::fixtures::TASKS)?;
+    // "Bla bla" is assigned to the current user and "Test" to a collaborator.
+    tasks["results"][0]["assignee_id"] = "100".into();
+    tasks["results"][1]["assignee_id"] = "200".into();
+
+    for (assignee, shown, hidden) in [
+        ("me", "Bla bla", "Test"),
+        ("alice", "Test", "Bla bla"),
+        ("unassigned", "Woah", "Test"),
+    ] {
+        let cmd = Tool::init().await?;
+        mocks::mock_labels(&cmd, 1).await;
+        mocks::mock_sections(&cmd, 1).await;
+        Mock::given(matchers::method("GET"))
+            .and(matchers::path("/api/v1/tasks/filter"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&tasks))
+            .expect(1)
+            .mount(&cmd.mock)
+            .await;
+        mocks::mock_shared_projects(&cmd, 1).await;
+        Mock::given(matchers::method("GET"))
+            .and(matchers::path("/api/v1/user"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "100",
+                "full_name": "Me Myself",
+                "email": "me@example.com"
+            })))
+            .expect(u64::from(assignee == "me"))
+            .mount(&cmd.mock)
+            .await;
+        mocks::mock_collaborators(
+            &cmd,
+            &[("100", "Me Myself"), ("200", "Alice")],
+            u64::from(assignee == "alice"),
+        )
+        .await;
+
+        cmd.cmd()?
+            .args(["list", "-P", "Project One", "--assignee", assignee])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(shown).and(predicate::str::contains(hidden).not()));
+        cmd.mock.verify().await;
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn parent() -> Result<()> {
+    let mut tasks: serde_json::Value = serde_json::from_str(super::fixtures::TASKS)?;
+    // "Impossible" has the subtask "Nope", which gets "Yay" as its own subtask.
+    tasks["results"][8]["parent_id"] = "7000008".into();
+    tasks["results"][8]["project_id"] = "1000004".into();
+
+    for (parent, shown, hidden) in [
+        ("7000008", vec!["Nope", "Yay"], vec!["Impossible", "Darn"]),
+        ("7000005", vec!["Impossible", "Nope", "Yay"], vec!["Darn"]),
+    ] {
+        let cmd = Tool::init().await?;
+        mocks::mock_labels(&cmd, 1).await;
+        mocks::mock_projects(&cmd, 1).await;
+        mocks::mock_sections(&cmd, 1).await;
+        Mock::given(matchers::method("GET"))
+            .and(matchers::path("/api/v1/tasks/filter"))
+            .and(matchers::query_param("query", "all"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&tasks))
+            .expect(1)
+            .mount(&cmd.mock)
+            .await;
+
+        let output = cmd.cmd()?.args(["list", "--parent", parent]).output()?;
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout)?;
+        let lines = stdout.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), shown.len(), "{stdout}");
+        for (line, content) in lines.iter().zip(&shown) {
+            assert!(line.contains(content), "{stdout}");
+        }
+        // The parent is the root, so only its subtasks are drawn as part of a tree.
+        assert!(!lines[0].contains("└─"), "{stdout}");
+        assert!(
+            lines[1..].iter().all(|line| line.contains("└─")),
+            "{stdout}"
+        );
+        for content in hidden {
+            assert!(!stdout.contains(content), "{stdout}");
+        }
+        cmd.mock.verify().await;
+    }
+
+    let cmd = Tool::init().await?;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_tasks(&cmd, 1).await;
+    cmd.cmd()?
+        .args(["list", "--parent", "123"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no open task with ID 123"));
+
+    Ok(())
+}
@@ -1,7 +1,19 @@
+mod add;
 mod auth;
+mod bump;
+mod close;
+mod comment;
+mod comments;
+mod completed;
+mod duplicate;
+mod edit;
 mod fixtures;
+mod import;
 mod labels;
 mod list;
 mod mocks;
 mod projects;
 mod setup;
+mod show;
+mod stats;
+mod sync;
@@ -2,6 +2,72 @@ use wiremock::{Mock, MockBuilder, ResponseTemplate, matchers};
 
 use crate::setup::Tool;
 
+/// Builds the JSON of a task like the API returns it. Fields that aren't set get the values of a
+/// plain open task in Project One.
+pub struct TaskJson(serde_json::Value);
+
+impl TaskJson {
+    pub fn new(id: &str, content: &str) -> Self {
+        Self(serde_json::json!({
+            "id": id,
+            "project_id": "1000002",
+            "section_id": null,
+            "parent_id": null,
+            "order": 1,
+            "content": content,
+            "description": "",
+            "is_completed": false,
+            "labels": [],
+            "priority": 1,
+            "comment_count": 0,
+            "creator_id": "1111111111",
+            "created_at": "2022-04-28T03:09:47Z",
+            "url": format!("https://todoist.com/showTask?id={id}")
+        }))
+    }
+
+    pub fn project(mut self, id: &str) -> Self {
+        self.0["project_id"] = id.into();
+        self
+    }
+
+    pub fn parent(mut self, id: &str) -> Self {
+        self.0["parent_id"] = id.into();
+        self
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.0["description"] = description.into();
+        self
+    }
+
+    pub fn labels(mut self, labels: &[&str]) -> Self {
+        self.0["labels"] = labels.into();
+        self
+    }
+
+    /// Sets the priority as the API counts it, with 4 as urgent.
+    pub fn priority(mut self, priority: u8) -> Self {
+        self.0["priority"] = priority.into();
+        self
+    }
+
+    pub fn comment_count(mut self, count: u32) -> Self {
+        self.0["comment_count"] = count.into();
+        self
+    }
+
+    pub fn due(mut self, string: &str, date: &str, recurring: bool) -> Self {
+        self.0["due"] =
+            serde_json::json!({"is_recurring": recurring, "string": string, "date": date});
+        self
+    }
+
+    pub fn build(self) -> serde_json::Value {
+        self.0
+    }
+}
+
 pub async fn mock_tasks(tool: &Tool, times: u64) {
     // Default filter is "all", so this needs to use /filter endpoint
     mock_http_with_builder(
@@ -53,6 +119,39 @@ pub async fn mock_projects(tool: &Tool, times: u64) {
     .await
 }
 
+/// Mocks the projects with Project One being shared, expecting them to be fetched `times` times.
+pub async fn mock_shared_projects(tool: &Tool, times: u64) {
+    let mut projects: serde_json::Value = serde_json::from_str(super::fixtures::PROJECTS).unwrap();
+    projects["results"][1]["is_shared"] = true.into();
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/projects"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(projects))
+        .expect(times)
+        .mount(&tool.mock)
+        .await
+}
+
+/// Mocks the collaborators of Project One, given by ID and name, expecting them to be fetched
+/// `times` times.
+pub async fn mock_collaborators(tool: &Tool, collaborators: &[(&str, &str)], times: u64) {
+    let results = collaborators
+        .iter()
+        .map(|(id, name)| {
+            let first = name.split(' ').next().unwrap_or_default().to_lowercase();
+            serde_json::json!({"id": id, "name": name, "email": format!("{first}@example.com")})
+        })
+        .collect::<Vec<_>>();
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/projects/1000002/collaborators"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({"results": results, "next_cursor": null})),
+        )
+        .expect(times)
+        .mount(&tool.mock)
+        .await
+}
+
 pub async fn mock_sections(tool: &Tool, times: u64) {
     mock_http(
         tool,
@@ -1,8 +1,9 @@
-use super::mocks;
+use super::mocks::{self, TaskJson};
 use super::setup::Tool;
 use assert_cmd::prelude::*;
 use color_eyre::Result;
 use predicates::prelude::*;
+use wiremock::{Mock, ResponseTemplate, matchers};
 
 #[tokio::test]
 async fn list() -> Result<()> {
@@ -29,3 +30,235 @@ async fn list() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn create_from_template() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.project_templates.insert(
+        "client".to_string(),
+        doist::config::ProjectTemplate {
+            name: Some("Client".to_string()),
+            sections: vec!["Backlog".to_string(), "Done".to_string()],
+            tasks: vec!["Kickoff".to_string()],
+            ..Default::default()
+        },
+    );
+    cmd.cfg.save()?;
+
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/projects"))
+        .and(matchers::body_partial_json(
+            serde_json::json!({"name": "ACME"}),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "2000001",
+            "name": "ACME",
+            "color": "grey",
+            "is_shared": false,
+            "child_order": 1,
+            "is_favorite": false,
+            "view_style": "list"
+        })))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    for (id, name, order) in [("2100001", "Backlog", 1), ("2100002", "Done", 2)] {
+        Mock::given(matchers::method("POST"))
+            .and(matchers::path("/api/v1/sections"))
+            .and(matchers::body_json(serde_json::json!({
+                "name": name,
+                "project_id": "2000001",
+                "order": order
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": id,
+                "project_id": "2000001",
+                "order": order,
+                "name": name
+            })))
+            .expect(1)
+            .mount(&cmd.mock)
+            .await;
+    }
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/tasks"))
+        .and(matchers::body_partial_json(serde_json::json!({
+            "content": "Kickoff",
+            "project_id": "2000001"
+        })))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(
+                TaskJson::new("7000001", "Kickoff")
+                    .project("2000001")
+                    .build(),
+            ),
+        )
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["project", "create", "ACME", "--template", "client"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("created project")
+                .and(predicate::str::contains("created section: 2100001 Backlog"))
+                .and(predicate::str::contains("created section: 2100002 Done"))
+                .and(predicate::str::contains("created task: p4 Kickoff")),
+        );
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn output_json() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_projects(&cmd, 1).await;
+
+    let output = cmd
+        .cmd()?
+        .args(["projects", "--format", "json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let projects: Vec<doist::api::rest::Project> = serde_json::from_slice(&output)?;
+    let fixture: serde_json::Value = serde_json::from_str(super::fixtures::PROJECTS)?;
+    let fixture: Vec<doist::api::rest::Project> =
+        serde_json::from_value(fixture["results"].clone())?;
+    assert_eq!(projects, fixture);
+
+    let json: serde_json::Value = serde_json::from_slice(&output)?;
+    assert_eq!(json[2]["parent_id"], "1000002");
+    assert_eq!(json[1]["is_favorite"], true);
+    assert_eq!(json[1]["view_style"], "list");
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn include_archived() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_projects(&cmd, 2).await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/projects/archived"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "results": [{
+                "id": "1000009",
+                "name": "Old Project",
+                "color": "grey",
+                "is_shared": false,
+                "child_order": 9,
+                "is_favorite": false,
+                "is_archived": true,
+                "view_style": "list"
+            }],
+            "next_cursor": null
+        })))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["projects"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Old Project").not());
+    cmd.cmd()?
+        .args(["projects", "--include-archived"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1000009 Old Project (archived)"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn archive() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_projects(&cmd, 1).await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/api/v1/projects/1000005/archive"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "1000005",
+            "name": "Project Four",
+            "color": "berry_red",
+            "is_shared": false,
+            "child_order": 3,
+            "is_favorite": false,
+            "is_archived": true,
+            "view_style": "list"
+        })))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["project", "archive", "--project_id", "1000005"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "archived project: 1000005 Project Four",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn delete_requires_confirmation() -> Result<()> {
+    let cmd = Tool::init().await?;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    Mock::given(matchers::method("DELETE"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(0)
+        .mount(&cmd.mock)
+        .await;
+
+    // Without a terminal there is nobody to confirm, so nothing gets deleted.
+    for args in [
+        vec!["projects", "delete", "--project_id", "1000002"],
+        vec!["projects", "sections", "delete", "--section_id", "1100001"],
+    ] {
+        cmd.cmd()?
+            .args(args)
+            .stdin(std::process::Stdio::null())
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("use --force"));
+    }
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn delete_force() -> Result<()> {
+    let cmd = Tool::init().await?;
+    mocks::mock_projects(&cmd, 1).await;
+    Mock::given(matchers::method("DELETE"))
+        .and(matchers::path("/api/v1/projects/1000002"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["projects", "delete", "--project_id", "1000002", "--force"])
+        .stdin(std::process::Stdio::null())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("deleted project"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
@@ -16,6 +16,7 @@ impl Tool {
         let tmp = assert_fs::TempDir::new()?;
         let mut cmd = Command::cargo_bin("doist")?;
         cmd.env("RUST_BACKTRACE", "1")
+            .env_remove("DOIST_TOKEN")
             .arg(format!("--config_prefix={}", tmp.path().display()))
             .arg("auth")
             .arg("AUTH_KEY")
@@ -33,6 +34,7 @@ impl Tool {
     pub fn cmd(&self) -> Result<Command> {
         let mut cmd = Command::cargo_bin("doist")?;
         cmd.env("RUST_BACKTRACE", "1")
+            .env_remove("DOIST_TOKEN")
             .arg(format!("--config_prefix={}", self.tmp.path().display()));
         Ok(cmd)
     }
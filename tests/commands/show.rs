@@ -0,0 +1,75 @@
+use super::mocks::{self, TaskJson};
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::{Mock, ResponseTemplate, matchers};
+
+#[tokio::test]
+async fn shows_task() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_labels(&cmd, 2).await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/tasks/7000001"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(
+                TaskJson::new("7000001", "Water plants")
+                    .description("The ones on the balcony")
+                    .priority(4)
+                    .comment_count(1)
+                    .build(),
+            ),
+        )
+        .expect(2)
+        .mount(&cmd.mock)
+        .await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/projects/1000002"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "1000002",
+            "name": "Project One",
+            "color": "berry_red",
+            "is_shared": false,
+            "child_order": 1,
+            "is_favorite": false,
+            "view_style": "list"
+        })))
+        .expect(2)
+        .mount(&cmd.mock)
+        .await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/comments"))
+        .and(matchers::query_param("task_id", "7000001"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "results": [{
+                "id": "1",
+                "task_id": "7000001",
+                "posted_at": "2025-10-01T10:00:00Z",
+                "content": "Don't forget the cactus"
+            }],
+            "next_cursor": null
+        })))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["show", "7000001"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("Content: Water plants")
+                .and(predicate::str::contains("Priority: p1"))
+                .and(predicate::str::contains("Project One"))
+                .and(predicate::str::contains("cactus").not()),
+        );
+    cmd.cmd()?
+        .args(["show", "7000001", "--comments"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Don't forget the cactus"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use super::mocks::TaskJson;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::{Mock, ResponseTemplate, matchers};
+
+#[tokio::test]
+async fn streak_covers_the_whole_history() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    let mut task = TaskJson::new("123", "Pay rent")
+        .due("every month", "2025-11-05", true)
+        .build();
+    task["created_at"] = "2025-06-01T00:00:00Z".into();
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/api/v1/tasks/123"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&task))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    let completed = |dates: &[&str]| {
+        let items = dates
+            .iter()
+            .map(|date| {
+                let mut item = task.clone();
+                item["completed_at"] = format!("{date}T10:00:00Z").into();
+                item
+            })
+            .collect::<Vec<_>>();
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "items": items,
+            "next_cursor": null
+        }))
+    };
+    // Five months don't fit into a single window of 12 weeks.
+    for (since, until, dates) in [
+        (
+            "2025-07-18T12:00:00Z",
+            "2025-10-10T12:00:00Z",
+            &["2025-08-05", "2025-09-05", "2025-10-05"][..],
+        ),
+        (
+            "2025-06-01T00:00:00Z",
+            "2025-07-18T12:00:00Z",
+            &["2025-06-05", "2025-07-05"][..],
+        ),
+    ] {
+        Mock::given(matchers::method("GET"))
+            .and(matchers::path("/api/v1/tasks/completed/by_completion_date"))
+            .and(matchers::query_param("since", since))
+            .and(matchers::query_param("until", until))
+            .respond_with(completed(dates))
+            .expect(1)
+            .mount(&cmd.mock)
+            .await;
+    }
+
+    cmd.cmd()?
+        .args(["--now", "2025-10-10T12:00:00Z", "stats", "--streak", "123"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Current streak: 5 months, Longest: 5",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::{Mock, ResponseTemplate, matchers};
+
+#[tokio::test]
+async fn sync() -> Result<()> {
+    let cmd = Tool::init().await?;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+
+    cmd.cmd()?
+        .arg("sync")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "Synced 6 projects, 8 sections and 2 labels\n",
+        ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn token_rejected() -> Result<()> {
+    let cmd = Tool::init().await?;
+    for path in ["/api/v1/projects", "/api/v1/sections", "/api/v1/labels"] {
+        Mock::given(matchers::method("GET"))
+            .and(matchers::path(path))
+            .respond_with(ResponseTemplate::new(401).set_body_string("Forbidden"))
+            .mount(&cmd.mock)
+            .await;
+    }
+
+    cmd.cmd()?
+        .arg("sync")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("token invalid or expired"))
+        .stderr(predicate::str::contains("unable to get").not());
+
+    Ok(())
+}